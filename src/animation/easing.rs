@@ -0,0 +1,263 @@
+use std::f32::consts::PI;
+
+/// maps linear progress 0..1 to eased progress, 0 and 1 are fixed points
+///
+/// Elastic and back-like curves (`CubicBezier` with y outside 0..1) overshoot, interpolation
+/// then extrapolates past the keyframe values.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Easing {
+    #[default]
+    Linear,
+    /// holds the start value until the end of the segment
+    Step,
+    QuadIn,
+    QuadOut,
+    QuadInOut,
+    CubicIn,
+    CubicOut,
+    CubicInOut,
+    ElasticIn,
+    ElasticOut,
+    ElasticInOut,
+    BounceIn,
+    BounceOut,
+    BounceInOut,
+    /// CSS `cubic-bezier(x1, y1, x2, y2)`, x1 and x2 must be in 0..1
+    CubicBezier {
+        x1: f32,
+        y1: f32,
+        x2: f32,
+        y2: f32,
+    },
+}
+
+impl Easing {
+    /// CSS `ease`
+    pub const EASE: Easing = Easing::CubicBezier {
+        x1: 0.25,
+        y1: 0.1,
+        x2: 0.25,
+        y2: 1.0,
+    };
+    /// CSS `ease-in-out`
+    pub const EASE_IN_OUT: Easing = Easing::CubicBezier {
+        x1: 0.42,
+        y1: 0.0,
+        x2: 0.58,
+        y2: 1.0,
+    };
+
+    /// t is clamped to 0..1
+    pub fn apply(self, t: f32) -> f32 {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            Easing::Linear => t,
+            Easing::Step => {
+                if t < 1.0 {
+                    0.0
+                } else {
+                    1.0
+                }
+            }
+            Easing::QuadIn => t * t,
+            Easing::QuadOut => 1.0 - (1.0 - t) * (1.0 - t),
+            Easing::QuadInOut => in_out(t, |t| t * t),
+            Easing::CubicIn => t * t * t,
+            Easing::CubicOut => 1.0 - (1.0 - t).powi(3),
+            Easing::CubicInOut => in_out(t, |t| t * t * t),
+            Easing::ElasticIn => elastic_in(t),
+            Easing::ElasticOut => 1.0 - elastic_in(1.0 - t),
+            Easing::ElasticInOut => in_out(t, elastic_in),
+            Easing::BounceIn => 1.0 - bounce_out(1.0 - t),
+            Easing::BounceOut => bounce_out(t),
+            Easing::BounceInOut => in_out(t, |t| 1.0 - bounce_out(1.0 - t)),
+            Easing::CubicBezier { x1, y1, x2, y2 } => cubic_bezier(t, x1, y1, x2, y2),
+        }
+    }
+}
+
+/// first half runs `ease_in` on 0..1, the second half mirrors it
+fn in_out(t: f32, ease_in: impl Fn(f32) -> f32) -> f32 {
+    if t < 0.5 {
+        ease_in(2.0 * t) / 2.0
+    } else {
+        1.0 - ease_in(2.0 - 2.0 * t) / 2.0
+    }
+}
+
+fn elastic_in(t: f32) -> f32 {
+    if t == 0.0 || t == 1.0 {
+        return t;
+    }
+    -(2.0f32.powf(10.0 * t - 10.0)) * ((t * 10.0 - 10.75) * (2.0 * PI / 3.0)).sin()
+}
+
+fn bounce_out(t: f32) -> f32 {
+    const N: f32 = 7.5625;
+    const D: f32 = 2.75;
+    if t < 1.0 / D {
+        N * t * t
+    } else if t < 2.0 / D {
+        let t = t - 1.5 / D;
+        N * t * t + 0.75
+    } else if t < 2.5 / D {
+        let t = t - 2.25 / D;
+        N * t * t + 0.9375
+    } else {
+        let t = t - 2.625 / D;
+        N * t * t + 0.984375
+    }
+}
+
+/// solves x(s) = t for the curve parameter s, then evaluates y(s)
+fn cubic_bezier(t: f32, x1: f32, y1: f32, x2: f32, y2: f32) -> f32 {
+    // 1D bezier with control points 0, p1, p2, 1
+    let curve = |s: f32, p1: f32, p2: f32| {
+        let inv = 1.0 - s;
+        3.0 * inv * inv * s * p1 + 3.0 * inv * s * s * p2 + s * s * s
+    };
+    let slope = |s: f32, p1: f32, p2: f32| {
+        let inv = 1.0 - s;
+        3.0 * inv * inv * p1 + 6.0 * inv * s * (p2 - p1) + 3.0 * s * s * (1.0 - p2)
+    };
+
+    // Newton converges in a few steps unless the slope flattens, bisection always works
+    let mut s = t;
+    for _ in 0..8 {
+        let error = curve(s, x1, x2) - t;
+        if error.abs() < 1e-6 {
+            return curve(s, y1, y2);
+        }
+        let derivative = slope(s, x1, x2);
+        if derivative.abs() < 1e-6 {
+            break;
+        }
+        s = (s - error / derivative).clamp(0.0, 1.0);
+    }
+
+    let (mut low, mut high) = (0.0, 1.0);
+    s = t;
+    for _ in 0..32 {
+        let x = curve(s, x1, x2);
+        if (x - t).abs() < 1e-6 {
+            break;
+        }
+        if x < t {
+            low = s;
+        } else {
+            high = s;
+        }
+        s = (low + high) / 2.0;
+    }
+    curve(s, y1, y2)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALL: [Easing; 16] = [
+        Easing::Linear,
+        Easing::Step,
+        Easing::QuadIn,
+        Easing::QuadOut,
+        Easing::QuadInOut,
+        Easing::CubicIn,
+        Easing::CubicOut,
+        Easing::CubicInOut,
+        Easing::ElasticIn,
+        Easing::ElasticOut,
+        Easing::ElasticInOut,
+        Easing::BounceIn,
+        Easing::BounceOut,
+        Easing::BounceInOut,
+        Easing::EASE,
+        Easing::EASE_IN_OUT,
+    ];
+
+    fn close(a: f32, b: f32) -> bool {
+        (a - b).abs() < 1e-4
+    }
+
+    #[test]
+    fn endpoints_are_fixed() {
+        for easing in ALL {
+            assert!(close(easing.apply(0.0), 0.0), "{easing:?} at 0");
+            assert!(close(easing.apply(1.0), 1.0), "{easing:?} at 1");
+            // t is clamped
+            assert!(close(easing.apply(-1.0), 0.0), "{easing:?} before 0");
+            assert!(close(easing.apply(2.0), 1.0), "{easing:?} after 1");
+        }
+    }
+
+    #[test]
+    fn in_out_is_symmetric() {
+        for easing in [
+            Easing::QuadInOut,
+            Easing::CubicInOut,
+            Easing::ElasticInOut,
+            Easing::BounceInOut,
+            Easing::EASE_IN_OUT,
+        ] {
+            assert!(close(easing.apply(0.5), 0.5), "{easing:?}");
+            for t in [0.1, 0.2, 0.3, 0.4] {
+                assert!(
+                    close(easing.apply(t), 1.0 - easing.apply(1.0 - t)),
+                    "{easing:?} at {t}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn polynomials() {
+        assert_eq!(Easing::Linear.apply(0.3), 0.3);
+        assert_eq!(Easing::Step.apply(0.999), 0.0);
+        assert_eq!(Easing::QuadIn.apply(0.5), 0.25);
+        assert_eq!(Easing::QuadOut.apply(0.5), 0.75);
+        assert_eq!(Easing::QuadInOut.apply(0.25), 0.125);
+        assert_eq!(Easing::CubicIn.apply(0.5), 0.125);
+        assert_eq!(Easing::CubicOut.apply(0.5), 0.875);
+        assert_eq!(Easing::CubicInOut.apply(0.25), 0.0625);
+        assert!(close(Easing::BounceOut.apply(1.0 / 2.75), 1.0));
+        assert!(Easing::ElasticOut.apply(0.2) > 1.0, "elastic overshoots");
+    }
+
+    #[test]
+    fn cubic_bezier_known_values() {
+        // reference values from bisecting the curve in double precision
+        let ease = [(0.25, 0.408_510_6), (0.5, 0.802_403_4), (0.75, 0.960_459)];
+        for (t, expected) in ease {
+            assert!(close(Easing::EASE.apply(t), expected), "ease at {t}");
+        }
+        let ease_in_out = [(0.25, 0.129_161_9), (0.75, 0.870_838_1)];
+        for (t, expected) in ease_in_out {
+            assert!(
+                close(Easing::EASE_IN_OUT.apply(t), expected),
+                "ease-in-out at {t}"
+            );
+        }
+
+        // y outside 0..1 overshoots both ways
+        let back = Easing::CubicBezier {
+            x1: 0.68,
+            y1: -0.6,
+            x2: 0.32,
+            y2: 1.6,
+        };
+        assert!(close(back.apply(0.25), -0.097_707_74));
+        assert!(close(back.apply(0.75), 1.097_707_7));
+
+        // control points on the diagonal give a straight line
+        let linear = Easing::CubicBezier {
+            x1: 0.3,
+            y1: 0.3,
+            x2: 0.7,
+            y2: 0.7,
+        };
+        for t in [0.1, 0.33, 0.5, 0.9] {
+            assert!(close(linear.apply(t), t));
+        }
+    }
+}
@@ -0,0 +1,321 @@
+// Keyframe animation. A `Track` holds keyframes of one value and maps elapsed time to a value,
+// the caller owns the clock and passes seconds since the animation started.
+
+mod easing;
+
+pub use easing::Easing;
+
+use crate::math::{
+    angle::{Deg, Rad},
+    color::Color,
+    quaternion::Quaternion,
+    transform::{Transform2D, Transform3D},
+    vector::Vector,
+};
+
+/// values a `Track` can blend between, t may leave 0..1 for overshooting easings
+pub trait Interpolate: Copy {
+    fn interpolate(&self, other: &Self, t: f32) -> Self;
+}
+
+impl Interpolate for f32 {
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        self + (other - self) * t
+    }
+}
+
+impl Interpolate for Deg {
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        *self + (*other - *self) * t
+    }
+}
+
+impl Interpolate for Rad {
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        *self + (*other - *self) * t
+    }
+}
+
+impl<const D: usize> Interpolate for Vector<D> {
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        self.lerp(other, t)
+    }
+}
+
+/// blends the linear components
+impl Interpolate for Color {
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        self.lerp(other, t)
+    }
+}
+
+/// shortest arc at constant angular speed
+impl Interpolate for Quaternion {
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        self.slerp(other, t)
+    }
+}
+
+impl Interpolate for Transform2D {
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        self.lerp(other, t)
+    }
+}
+
+impl Interpolate for Transform3D {
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        self.lerp(other, t)
+    }
+}
+
+/// what happens once the elapsed time passes the last keyframe
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Repeat {
+    /// holds the last value
+    #[default]
+    Once,
+    /// jumps back to the start
+    Loop,
+    /// plays backwards to the start, then forwards again
+    PingPong,
+}
+
+/// `easing` shapes the segment from this keyframe to the next one, like CSS keyframes
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Keyframe<V> {
+    /// seconds
+    pub time: f32,
+    pub value: V,
+    pub easing: Easing,
+}
+
+/// keyframes of one value, sorted by time
+///
+/// The track runs from 0 to its last keyframe, before the first keyframe its value is held.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq)]
+pub struct Track<V> {
+    keyframes: Vec<Keyframe<V>>,
+    pub repeat: Repeat,
+    /// 2 plays twice as fast, negative values play backwards
+    pub time_scale: f32,
+}
+
+impl<V: Interpolate> Track<V> {
+    pub fn new() -> Self {
+        Self {
+            keyframes: Vec::new(),
+            repeat: Repeat::Once,
+            time_scale: 1.0,
+        }
+    }
+
+    /// inserts a keyframe, one at the same time is replaced
+    pub fn with_key(mut self, time: f32, value: V, easing: Easing) -> Self {
+        self.insert(Keyframe {
+            time,
+            value,
+            easing,
+        });
+        self
+    }
+
+    pub fn with_repeat(self, repeat: Repeat) -> Self {
+        Self { repeat, ..self }
+    }
+
+    pub fn with_time_scale(self, time_scale: f32) -> Self {
+        Self { time_scale, ..self }
+    }
+
+    pub fn insert(&mut self, keyframe: Keyframe<V>) {
+        let index = self
+            .keyframes
+            .partition_point(|existing| existing.time < keyframe.time);
+        match self.keyframes.get_mut(index) {
+            Some(existing) if existing.time == keyframe.time => *existing = keyframe,
+            _ => self.keyframes.insert(index, keyframe),
+        }
+    }
+
+    pub fn keyframes(&self) -> &[Keyframe<V>] {
+        &self.keyframes
+    }
+
+    /// seconds of one pass at a time scale of 1
+    pub fn duration(&self) -> f32 {
+        self.keyframes.last().map_or(0.0, |last| last.time.max(0.0))
+    }
+
+    /// track time of an elapsed time after repeat and time scale, in 0..duration
+    pub fn local_time(&self, elapsed: f32) -> f32 {
+        let duration = self.duration();
+        if duration <= 0.0 {
+            return 0.0;
+        }
+        let time = elapsed * self.time_scale;
+        match self.repeat {
+            // played backwards a single pass starts at the end
+            Repeat::Once if self.time_scale < 0.0 => (duration + time).clamp(0.0, duration),
+            Repeat::Once => time.clamp(0.0, duration),
+            Repeat::Loop => time.rem_euclid(duration),
+            Repeat::PingPong => {
+                let time = time.rem_euclid(2.0 * duration);
+                if time > duration {
+                    2.0 * duration - time
+                } else {
+                    time
+                }
+            }
+        }
+    }
+
+    /// true once a `Repeat::Once` track has reached its end, repeating tracks never finish
+    pub fn is_finished(&self, elapsed: f32) -> bool {
+        self.repeat == Repeat::Once && elapsed * self.time_scale.abs() >= self.duration()
+    }
+
+    /// value at an elapsed time, `None` without keyframes
+    pub fn sample(&self, elapsed: f32) -> Option<V> {
+        self.value_at(self.local_time(elapsed))
+    }
+
+    /// value at a track time, ignoring repeat and time scale
+    pub fn value_at(&self, time: f32) -> Option<V> {
+        let first = self.keyframes.first()?;
+        let next = self
+            .keyframes
+            .partition_point(|keyframe| keyframe.time <= time);
+        if next == 0 {
+            return Some(first.value);
+        }
+        let Some(to) = self.keyframes.get(next) else {
+            return self.keyframes.last().map(|last| last.value);
+        };
+        let from = &self.keyframes[next - 1];
+        let t = (time - from.time) / (to.time - from.time);
+        Some(from.value.interpolate(&to.value, from.easing.apply(t)))
+    }
+}
+
+impl<V: Interpolate> Default for Track<V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::common::Dimension2;
+
+    /// 0 -> 10 linear over 2s, then 10 -> 20 quad in over 2s
+    fn track() -> Track<f32> {
+        Track::new()
+            .with_key(4.0, 20.0, Easing::Linear)
+            .with_key(0.0, 0.0, Easing::Linear)
+            .with_key(2.0, 10.0, Easing::QuadIn)
+    }
+
+    #[test]
+    fn keyframes_stay_sorted() {
+        let mut track = track();
+        let times: Vec<_> = track.keyframes().iter().map(|key| key.time).collect();
+        assert_eq!(times, [0.0, 2.0, 4.0]);
+
+        track.insert(Keyframe {
+            time: 2.0,
+            value: 12.0,
+            easing: Easing::Linear,
+        });
+        assert_eq!(track.keyframes().len(), 3);
+        assert_eq!(track.value_at(2.0), Some(12.0));
+        assert_eq!(track.duration(), 4.0);
+    }
+
+    #[test]
+    fn interpolates_between_keys() {
+        let track = track();
+        assert_eq!(track.value_at(0.0), Some(0.0));
+        assert_eq!(track.value_at(1.0), Some(5.0));
+        assert_eq!(track.value_at(2.0), Some(10.0));
+        // the easing of the key at 2s shapes the second segment
+        assert_eq!(track.value_at(3.0), Some(12.5));
+        assert_eq!(track.value_at(4.0), Some(20.0));
+    }
+
+    #[test]
+    fn clamps_outside_the_keys() {
+        let track =
+            Track::new()
+                .with_key(1.0, 3.0, Easing::Linear)
+                .with_key(2.0, 5.0, Easing::Linear);
+        assert_eq!(track.value_at(0.0), Some(3.0));
+        assert_eq!(track.value_at(-5.0), Some(3.0));
+        assert_eq!(track.value_at(9.0), Some(5.0));
+        assert_eq!(track.sample(-1.0), Some(3.0));
+        assert_eq!(track.sample(100.0), Some(5.0));
+
+        let single = Track::new().with_key(1.0, 7.0, Easing::Linear);
+        assert_eq!(single.sample(0.0), Some(7.0));
+        assert_eq!(single.sample(3.0), Some(7.0));
+
+        assert_eq!(Track::<f32>::new().sample(1.0), None);
+    }
+
+    #[test]
+    fn repeat() {
+        let once = track();
+        assert_eq!(once.local_time(5.0), 4.0);
+        assert!(!once.is_finished(3.9));
+        assert!(once.is_finished(4.0));
+
+        let looping = track().with_repeat(Repeat::Loop);
+        assert_eq!(looping.local_time(5.0), 1.0);
+        assert_eq!(looping.local_time(-1.0), 3.0);
+        assert_eq!(looping.sample(9.0), Some(5.0));
+        assert!(!looping.is_finished(100.0));
+
+        let ping_pong = track().with_repeat(Repeat::PingPong);
+        assert_eq!(ping_pong.local_time(3.0), 3.0);
+        assert_eq!(ping_pong.local_time(5.0), 3.0);
+        assert_eq!(ping_pong.local_time(9.0), 1.0);
+        assert_eq!(ping_pong.sample(7.0), Some(5.0));
+    }
+
+    #[test]
+    fn time_scale() {
+        let fast = track().with_time_scale(2.0);
+        assert_eq!(fast.sample(0.5), Some(5.0));
+        assert!(fast.is_finished(2.0));
+        assert!(!fast.is_finished(1.5));
+
+        let slow = track().with_time_scale(0.5);
+        assert_eq!(slow.sample(2.0), Some(5.0));
+
+        // a single backwards pass starts at the end
+        let backwards = track().with_time_scale(-1.0);
+        assert_eq!(backwards.sample(0.0), Some(20.0));
+        assert_eq!(backwards.sample(3.0), Some(5.0));
+        assert_eq!(backwards.sample(10.0), Some(0.0));
+        assert!(backwards.is_finished(4.0));
+
+        let backwards_loop = track().with_time_scale(-1.0).with_repeat(Repeat::Loop);
+        assert_eq!(backwards_loop.local_time(1.0), 3.0);
+    }
+
+    #[test]
+    fn interpolates_math_types() {
+        let track = Track::new()
+            .with_key(0.0, Vector::<2>::new(0.0, 0.0), Easing::Linear)
+            .with_key(1.0, Vector::<2>::new(4.0, -2.0), Easing::Linear);
+        assert_eq!(track.sample(0.25), Some(Vector::<2>::new(1.0, -0.5)));
+
+        let angles = Track::new()
+            .with_key(0.0, Deg(0.0), Easing::Linear)
+            .with_key(1.0, Deg(90.0), Easing::Linear);
+        assert_eq!(angles.sample(0.5), Some(Deg(45.0)));
+    }
+}
@@ -1,15 +1,22 @@
+use std::process;
+
 use crate::state::State;
+use web_gpu::configuration::Configuration;
 use winit::{
     application::ApplicationHandler, event::WindowEvent, event_loop::EventLoop, window::Window,
 };
 
 struct App<'a> {
     state: Option<State<'a>>,
+    configuration: Configuration,
 }
 
 impl<'a> App<'a> {
-    pub fn new() -> Self {
-        Self { state: None }
+    pub fn new(configuration: Configuration) -> Self {
+        Self {
+            state: None,
+            configuration,
+        }
     }
 }
 
@@ -18,7 +25,7 @@ impl<'a> ApplicationHandler for App<'a> {
         let window = event_loop
             .create_window(Window::default_attributes().with_title("web_gpu"))
             .unwrap();
-        self.state = Some(State::new(window));
+        self.state = Some(State::new(window, &self.configuration));
     }
 
     fn window_event(
@@ -51,7 +58,14 @@ impl<'a> ApplicationHandler for App<'a> {
 }
 
 pub async fn run() {
+    let configuration = match Configuration::from_env() {
+        Ok(configuration) => configuration,
+        Err(error) => {
+            eprintln!("error: {error}");
+            process::exit(1);
+        }
+    };
     let event_loop = EventLoop::new().unwrap();
-    let mut app_state = App::new();
+    let mut app_state = App::new(configuration);
     event_loop.run_app(&mut app_state).unwrap();
 }
@@ -1 +1,46 @@
+use std::{
+    env,
+    error::Error,
+    fmt::{self, Display},
+};
 
+use crate::constant::SEED_ENV;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConfigurationError {
+    /// the seed variable is set but not a u64
+    InvalidSeed(String),
+}
+
+impl Display for ConfigurationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigurationError::InvalidSeed(value) => {
+                write!(f, "{SEED_ENV} must be an unsigned integer, got {value:?}")
+            }
+        }
+    }
+}
+
+impl Error for ConfigurationError {}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Configuration {
+    /// seed of every random choice, a fresh one per run when unset
+    pub seed: Option<u64>,
+}
+
+impl Configuration {
+    /// defaults overridden by the environment
+    pub fn from_env() -> Result<Self, ConfigurationError> {
+        let mut configuration = Self::default();
+        if let Ok(value) = env::var(SEED_ENV) {
+            let seed = value
+                .trim()
+                .parse()
+                .map_err(|_| ConfigurationError::InvalidSeed(value))?;
+            configuration.seed = Some(seed);
+        }
+        Ok(configuration)
+    }
+}
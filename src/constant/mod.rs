@@ -1,2 +1,6 @@
 // ACCURACY
 pub const EPSILON: f32 = 0.000001; // compare two f32 value
+pub const EPSILON_F64: f64 = 0.000000000001; // compare two f64 value
+
+// CONFIGURATION
+pub const SEED_ENV: &str = "WEB_GPU_SEED"; // fixes the random seed, e.g. for reproducible frames
@@ -0,0 +1,9 @@
+pub mod animation;
+pub mod configuration;
+pub mod constant;
+pub mod math;
+pub mod mesh;
+pub mod primitive;
+pub mod random;
+pub mod tessellation;
+pub mod util;
@@ -0,0 +1,200 @@
+use std::{
+    cmp::Ordering,
+    ops::{Add, AddAssign, Div, Mul, Neg, Sub, SubAssign},
+};
+
+use super::common::{FuzzyEq, Scalar, ToBits};
+
+/// angle in degrees, converts into `Rad` wherever an angle is taken
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Deg<T: Scalar = f32>(pub T);
+
+/// angle in radians
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Rad<T: Scalar = f32>(pub T);
+
+impl<T: Scalar> Rad<T> {
+    pub fn sin(self) -> T {
+        self.0.sin()
+    }
+
+    pub fn cos(self) -> T {
+        self.0.cos()
+    }
+
+    pub fn sin_cos(self) -> (T, T) {
+        self.0.sin_cos()
+    }
+
+    pub fn tan(self) -> T {
+        self.0.tan()
+    }
+
+    /// wraps into -PI..PI
+    pub fn normalize(self) -> Self {
+        let wrapped = (self.0 + T::PI).rem_euclid(T::TWO * T::PI) - T::PI;
+        Self(wrapped)
+    }
+}
+
+impl<T: Scalar> Deg<T> {
+    /// wraps into -180..180
+    pub fn normalize(self) -> Self {
+        let half_turn = T::from_f64(180.0);
+        Self((self.0 + half_turn).rem_euclid(T::TWO * half_turn) - half_turn)
+    }
+}
+
+impl<T: Scalar> From<Deg<T>> for Rad<T> {
+    fn from(deg: Deg<T>) -> Self {
+        Self(deg.0.to_radians())
+    }
+}
+
+impl<T: Scalar> From<Rad<T>> for Deg<T> {
+    fn from(rad: Rad<T>) -> Self {
+        Self(rad.0.to_degrees())
+    }
+}
+
+macro_rules! angle_ops {
+    ($angle:ident) => {
+        impl<T: Scalar> FuzzyEq for $angle<T> {
+            fn fuzzy_eq(&self, other: &Self) -> bool {
+                self.0.fuzzy_eq(&other.0)
+            }
+        }
+
+        /// fuzzy like the other math types, wrap in `Exact` for bitwise equality
+        impl<T: Scalar> PartialEq for $angle<T> {
+            fn eq(&self, other: &Self) -> bool {
+                self.fuzzy_eq(other)
+            }
+        }
+
+        /// angles that are `==` compare as equal
+        impl<T: Scalar> PartialOrd for $angle<T> {
+            fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+                if self == other {
+                    Some(Ordering::Equal)
+                } else {
+                    self.0.partial_cmp(&other.0)
+                }
+            }
+        }
+
+        impl<T: Scalar> ToBits for $angle<T> {
+            type Bits = T::Bits;
+            fn to_bits(&self) -> Self::Bits {
+                self.0.to_bits()
+            }
+        }
+
+        impl<T: Scalar> Add for $angle<T> {
+            type Output = Self;
+            fn add(self, rhs: Self) -> Self::Output {
+                Self(self.0 + rhs.0)
+            }
+        }
+
+        impl<T: Scalar> AddAssign for $angle<T> {
+            fn add_assign(&mut self, rhs: Self) {
+                self.0 += rhs.0;
+            }
+        }
+
+        impl<T: Scalar> Sub for $angle<T> {
+            type Output = Self;
+            fn sub(self, rhs: Self) -> Self::Output {
+                Self(self.0 - rhs.0)
+            }
+        }
+
+        impl<T: Scalar> SubAssign for $angle<T> {
+            fn sub_assign(&mut self, rhs: Self) {
+                self.0 -= rhs.0;
+            }
+        }
+
+        impl<T: Scalar> Neg for $angle<T> {
+            type Output = Self;
+            fn neg(self) -> Self::Output {
+                Self(-self.0)
+            }
+        }
+
+        impl<T: Scalar> Mul<T> for $angle<T> {
+            type Output = Self;
+            fn mul(self, rhs: T) -> Self::Output {
+                Self(self.0 * rhs)
+            }
+        }
+
+        impl<T: Scalar> Div<T> for $angle<T> {
+            type Output = Self;
+            fn div(self, rhs: T) -> Self::Output {
+                Self(self.0 / rhs)
+            }
+        }
+    };
+}
+
+angle_ops!(Deg);
+angle_ops!(Rad);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::common::Exact;
+    use std::f32::consts::{FRAC_PI_2, PI};
+
+    #[test]
+    fn conversions() {
+        assert_eq!(Rad::from(Deg(180.0)), Rad(PI));
+        assert_eq!(Deg::from(Rad(FRAC_PI_2)), Deg(90.0));
+        assert_eq!(Deg::<f64>::from(Rad(std::f64::consts::PI)), Deg(180.0));
+        let (sin, cos) = Rad::from(Deg(30.0)).sin_cos();
+        assert!((sin - 0.5).abs() < 1e-6);
+        assert!((cos - 3.0f32.sqrt() / 2.0).abs() < 1e-6);
+        assert!((Rad::from(Deg(45.0)).tan() - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn normalize() {
+        assert_eq!(Deg(270.0).normalize(), Deg(-90.0));
+        assert_eq!(Deg(-190.0).normalize(), Deg(170.0));
+        assert_eq!(Deg(720.0 + 45.0).normalize(), Deg(45.0));
+        assert_eq!(Deg(180.0).normalize(), Deg(-180.0));
+        assert_eq!(Rad(3.0 * PI / 2.0).normalize(), Rad(-FRAC_PI_2));
+        assert_eq!(Rad(-5.0 * PI / 2.0).normalize(), Rad(-FRAC_PI_2));
+        for degrees in [-1000.0, -180.0, -1.0, 0.0, 179.0, 359.0, 1e4] {
+            let wrapped = Deg(degrees).normalize().0;
+            assert!((-180.0..180.0).contains(&wrapped), "{degrees} -> {wrapped}");
+        }
+    }
+
+    #[test]
+    fn arithmetic() {
+        let mut angle = Deg(30.0) + Deg(15.0) - Deg(5.0);
+        assert_eq!(angle, Deg(40.0));
+        angle += Deg(5.0);
+        angle -= Deg(15.0);
+        assert_eq!(angle, Deg(30.0));
+        assert_eq!(-angle * 2.0 / 4.0, Deg(-15.0));
+    }
+
+    #[test]
+    fn fuzzy_equality_and_order() {
+        // literals alone would make these Rad<f64>, with a much tighter tolerance
+        let one = Rad::<f32>(1.0);
+        assert_eq!(one, Rad(1.0 + 1e-7));
+        assert_ne!(one, Rad(1.0 + 1e-5));
+        assert_ne!(Rad::<f64>(1.0), Rad(1.0 + 1e-7));
+        assert_ne!(Exact(one), Exact(Rad(1.0 + 1e-7)));
+        assert_eq!(one.partial_cmp(&Rad(1.0 + 1e-7)), Some(Ordering::Equal));
+        assert!(Deg(10.0) < Deg(20.0));
+        assert!(Deg(f32::NAN).partial_cmp(&Deg(0.0)).is_none());
+    }
+}
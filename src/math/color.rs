@@ -0,0 +1,466 @@
+use std::{
+    error::Error,
+    fmt::{self, Display},
+    str::FromStr,
+};
+
+use super::{
+    angle::Deg,
+    common::FuzzyEq,
+    layout::{GpuLayout, GpuVec4},
+    vector::Vector,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorError {
+    /// not `rgb`, `rgba`, `rrggbb` or `rrggbbaa` hex digits, optionally prefixed with `#`
+    InvalidHex,
+}
+
+impl Display for ColorError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ColorError::InvalidHex => write!(f, "invalid hex color"),
+        }
+    }
+}
+
+impl Error for ColorError {}
+
+/// linear RGB with straight (not premultiplied) alpha
+///
+/// Linear is what shaders blend in and what an sRGB surface expects, the sRGB constructors
+/// and accessors are for values coming from or going to people: hex codes, color pickers, 8 bit
+/// images. HSV and HSL are defined on the sRGB encoded values, like in every color picker.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Color {
+    pub r: f32,
+    pub g: f32,
+    pub b: f32,
+    pub a: f32,
+}
+
+/// sRGB transfer function, encoded 0..1 to linear 0..1
+pub fn srgb_to_linear(value: f32) -> f32 {
+    if value <= 0.04045 {
+        value / 12.92
+    } else {
+        ((value + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+/// inverse of `srgb_to_linear`
+pub fn linear_to_srgb(value: f32) -> f32 {
+    if value <= 0.0031308 {
+        value * 12.92
+    } else {
+        1.055 * value.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+impl Color {
+    pub const TRANSPARENT: Color = Color::new(0.0, 0.0, 0.0, 0.0);
+    pub const BLACK: Color = Color::rgb(0.0, 0.0, 0.0);
+    pub const WHITE: Color = Color::rgb(1.0, 1.0, 1.0);
+    /// sRGB #808080
+    pub const GRAY: Color = Color::rgb(0.21586, 0.21586, 0.21586);
+    pub const RED: Color = Color::rgb(1.0, 0.0, 0.0);
+    pub const GREEN: Color = Color::rgb(0.0, 1.0, 0.0);
+    pub const BLUE: Color = Color::rgb(0.0, 0.0, 1.0);
+    pub const YELLOW: Color = Color::rgb(1.0, 1.0, 0.0);
+    pub const CYAN: Color = Color::rgb(0.0, 1.0, 1.0);
+    pub const MAGENTA: Color = Color::rgb(1.0, 0.0, 1.0);
+    /// sRGB #ff8000
+    pub const ORANGE: Color = Color::rgb(1.0, 0.21586, 0.0);
+    /// sRGB #800080
+    pub const PURPLE: Color = Color::rgb(0.21586, 0.0, 0.21586);
+
+    pub const fn new(r: f32, g: f32, b: f32, a: f32) -> Self {
+        Self { r, g, b, a }
+    }
+
+    /// opaque
+    pub const fn rgb(r: f32, g: f32, b: f32) -> Self {
+        Self::new(r, g, b, 1.0)
+    }
+
+    /// from sRGB encoded components, alpha is always linear
+    pub fn from_srgb(r: f32, g: f32, b: f32, a: f32) -> Self {
+        Self::new(srgb_to_linear(r), srgb_to_linear(g), srgb_to_linear(b), a)
+    }
+
+    /// sRGB encoded [r, g, b, a]
+    pub fn to_srgb(self) -> [f32; 4] {
+        [
+            linear_to_srgb(self.r),
+            linear_to_srgb(self.g),
+            linear_to_srgb(self.b),
+            self.a,
+        ]
+    }
+
+    pub fn from_srgb8(r: u8, g: u8, b: u8, a: u8) -> Self {
+        let unit = |value: u8| value as f32 / 255.0;
+        Self::from_srgb(unit(r), unit(g), unit(b), unit(a))
+    }
+
+    /// clamped and rounded to 8 bit sRGB
+    pub fn to_srgb8(self) -> [u8; 4] {
+        self.to_srgb()
+            .map(|value| (value.clamp(0.0, 1.0) * 255.0).round() as u8)
+    }
+
+    /// sRGB hex code `rgb`, `rgba`, `rrggbb` or `rrggbbaa` with an optional leading `#`
+    pub fn from_hex(hex: &str) -> Result<Self, ColorError> {
+        let digits = hex.strip_prefix('#').unwrap_or(hex);
+        // from_str_radix would also take a sign, and slicing needs single byte characters
+        if !digits.bytes().all(|byte| byte.is_ascii_hexdigit()) {
+            return Err(ColorError::InvalidHex);
+        }
+        let parse =
+            |digits: &str| u8::from_str_radix(digits, 16).map_err(|_| ColorError::InvalidHex);
+        // a single digit stands for itself repeated, f -> ff
+        let short = |index: usize| parse(&digits[index..index + 1]).map(|value| value * 17);
+        let long = |index: usize| parse(&digits[index * 2..index * 2 + 2]);
+        let [r, g, b, a] = match digits.len() {
+            3 => [short(0)?, short(1)?, short(2)?, 255],
+            4 => [short(0)?, short(1)?, short(2)?, short(3)?],
+            6 => [long(0)?, long(1)?, long(2)?, 255],
+            8 => [long(0)?, long(1)?, long(2)?, long(3)?],
+            _ => return Err(ColorError::InvalidHex),
+        };
+        Ok(Self::from_srgb8(r, g, b, a))
+    }
+
+    /// `#rrggbb`, or `#rrggbbaa` when not opaque
+    pub fn to_hex(self) -> String {
+        let [r, g, b, a] = self.to_srgb8();
+        if a == 255 {
+            format!("#{r:02x}{g:02x}{b:02x}")
+        } else {
+            format!("#{r:02x}{g:02x}{b:02x}{a:02x}")
+        }
+    }
+
+    /// hue wraps around, saturation and value are 0..1
+    pub fn from_hsv(hue: impl Into<Deg>, saturation: f32, value: f32, alpha: f32) -> Self {
+        let chroma = value * saturation;
+        let [r, g, b] = hue_to_rgb(hue.into(), chroma, value - chroma);
+        Self::from_srgb(r, g, b, alpha)
+    }
+
+    /// (hue in 0..360, saturation, value), hue is 0 for grays
+    pub fn to_hsv(self) -> (Deg, f32, f32) {
+        let (hue, max, min) = rgb_to_hue(self.to_srgb());
+        let saturation = if max > 0.0 { (max - min) / max } else { 0.0 };
+        (hue, saturation, max)
+    }
+
+    /// hue wraps around, saturation and lightness are 0..1
+    pub fn from_hsl(hue: impl Into<Deg>, saturation: f32, lightness: f32, alpha: f32) -> Self {
+        let chroma = (1.0 - (2.0 * lightness - 1.0).abs()) * saturation;
+        let [r, g, b] = hue_to_rgb(hue.into(), chroma, lightness - chroma / 2.0);
+        Self::from_srgb(r, g, b, alpha)
+    }
+
+    /// (hue in 0..360, saturation, lightness), hue is 0 for grays
+    pub fn to_hsl(self) -> (Deg, f32, f32) {
+        let (hue, max, min) = rgb_to_hue(self.to_srgb());
+        let lightness = (max + min) / 2.0;
+        let saturation = if lightness > 0.0 && lightness < 1.0 {
+            (max - min) / (1.0 - (2.0 * lightness - 1.0).abs())
+        } else {
+            0.0
+        };
+        (hue, saturation, lightness)
+    }
+
+    pub fn with_alpha(self, a: f32) -> Self {
+        Self { a, ..self }
+    }
+
+    /// rgb scaled by alpha, for `BlendState::PREMULTIPLIED_ALPHA_BLENDING`
+    pub fn premultiply(self) -> Self {
+        Self::new(self.r * self.a, self.g * self.a, self.b * self.a, self.a)
+    }
+
+    /// inverse of `premultiply`, fully transparent colors stay black
+    pub fn unpremultiply(self) -> Self {
+        if self.a == 0.0 {
+            return Self::TRANSPARENT;
+        }
+        Self::new(self.r / self.a, self.g / self.a, self.b / self.a, self.a)
+    }
+
+    /// interpolates the linear components, t = 0 gives self, t = 1 gives other
+    pub fn lerp(&self, other: &Self, t: f32) -> Self {
+        Vector::<4>::from(*self)
+            .lerp(&Vector::from(*other), t)
+            .into()
+    }
+
+    /// linear [r, g, b, a]
+    pub fn to_array(self) -> [f32; 4] {
+        [self.r, self.g, self.b, self.a]
+    }
+}
+
+/// rgb of a hue with the given chroma, offset by m
+fn hue_to_rgb(hue: Deg, chroma: f32, m: f32) -> [f32; 3] {
+    let sector = hue.0.rem_euclid(360.0) / 60.0;
+    let x = chroma * (1.0 - (sector % 2.0 - 1.0).abs());
+    let [r, g, b] = match sector as u32 {
+        0 => [chroma, x, 0.0],
+        1 => [x, chroma, 0.0],
+        2 => [0.0, chroma, x],
+        3 => [0.0, x, chroma],
+        4 => [x, 0.0, chroma],
+        _ => [chroma, 0.0, x],
+    };
+    [r + m, g + m, b + m]
+}
+
+/// (hue, max, min) of sRGB encoded components
+fn rgb_to_hue([r, g, b, _]: [f32; 4]) -> (Deg, f32, f32) {
+    let max = r.max(g).max(b);
+    let min = r.min(g).min(b);
+    let delta = max - min;
+    let hue = if delta == 0.0 {
+        0.0
+    } else if max == r {
+        60.0 * ((g - b) / delta).rem_euclid(6.0)
+    } else if max == g {
+        60.0 * ((b - r) / delta + 2.0)
+    } else {
+        60.0 * ((r - g) / delta + 4.0)
+    };
+    (Deg(hue), max, min)
+}
+
+impl FromStr for Color {
+    type Err = ColorError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::from_hex(s)
+    }
+}
+
+impl FuzzyEq for Color {
+    fn fuzzy_eq(&self, other: &Self) -> bool {
+        self.to_array()
+            .iter()
+            .zip(other.to_array().iter())
+            .all(|(a, b)| a.fuzzy_eq(b))
+    }
+}
+
+impl PartialEq for Color {
+    fn eq(&self, other: &Self) -> bool {
+        self.fuzzy_eq(other)
+    }
+}
+
+impl From<[f32; 4]> for Color {
+    fn from([r, g, b, a]: [f32; 4]) -> Self {
+        Self::new(r, g, b, a)
+    }
+}
+
+impl From<Color> for [f32; 4] {
+    fn from(color: Color) -> Self {
+        color.to_array()
+    }
+}
+
+impl From<Vector<4>> for Color {
+    fn from(vector: Vector<4>) -> Self {
+        vector.to_array().into()
+    }
+}
+
+impl From<Color> for Vector<4> {
+    fn from(color: Color) -> Self {
+        color.to_array().into()
+    }
+}
+
+/// wgpu takes linear components, encoded to sRGB by the surface when its format is sRGB
+impl From<Color> for wgpu::Color {
+    fn from(color: Color) -> Self {
+        wgpu::Color {
+            r: color.r as f64,
+            g: color.g as f64,
+            b: color.b as f64,
+            a: color.a as f64,
+        }
+    }
+}
+
+impl GpuLayout for Color {
+    type Gpu = GpuVec4;
+    const ALIGN: usize = 16;
+    fn to_gpu(&self) -> Self::Gpu {
+        self.to_array()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hex_round_trip() {
+        let color = Color::from_hex("#ff8000").unwrap();
+        assert_eq!(color.to_srgb8(), [255, 128, 0, 255]);
+        assert_eq!(color.to_hex(), "#ff8000");
+        assert_eq!(
+            Color::from_hex("f80c").unwrap().to_srgb8(),
+            [255, 136, 0, 204]
+        );
+        assert_eq!(Color::from_hex("#12345678").unwrap().to_hex(), "#12345678");
+    }
+
+    #[test]
+    fn invalid_hex() {
+        for hex in [
+            "", "#", "+f0", "#+f0f0f", "ff-", "12345", "#gg0000", "#ff00é",
+        ] {
+            assert_eq!(Color::from_hex(hex), Err(ColorError::InvalidHex), "{hex}");
+        }
+    }
+
+    fn close(a: f32, b: f32) -> bool {
+        (a - b).abs() < 1e-4
+    }
+
+    #[test]
+    fn srgb_round_trip() {
+        // the linear segment ends where the curve starts, 0.04045 encoded is 0.0031308 linear
+        assert!(close(srgb_to_linear(0.04045), 0.0031308));
+        assert!(close(linear_to_srgb(0.0031308), 0.04045));
+        for breakpoint in [0.04045f32, 0.0031308] {
+            let (below, above) = (breakpoint - 1e-6, breakpoint + 1e-6);
+            assert!((srgb_to_linear(below) - srgb_to_linear(above)).abs() < 1e-6);
+            // the encoding is 12.92 times steeper, a jump would still be far above that
+            assert!((linear_to_srgb(below) - linear_to_srgb(above)).abs() < 1e-4);
+        }
+        for value in [0.0, 0.0031308, 0.0031309, 0.04045, 0.04046, 0.5, 1.0] {
+            assert!(
+                close(linear_to_srgb(srgb_to_linear(value)), value),
+                "{value}"
+            );
+            assert!(
+                close(srgb_to_linear(linear_to_srgb(value)), value),
+                "{value}"
+            );
+        }
+        for i in 0..=1000 {
+            let value = i as f32 / 1000.0;
+            assert!(
+                close(linear_to_srgb(srgb_to_linear(value)), value),
+                "{value}"
+            );
+        }
+        assert_eq!(srgb_to_linear(0.0), 0.0);
+        assert!(close(srgb_to_linear(1.0), 1.0));
+        assert!(close(
+            Color::from_srgb8(128, 128, 128, 255).r,
+            Color::GRAY.r
+        ));
+        for byte in 0..=255 {
+            assert_eq!(
+                Color::from_srgb8(byte, byte, byte, byte).to_srgb8(),
+                [byte; 4]
+            );
+        }
+    }
+
+    #[test]
+    fn hsv_round_trip() {
+        // the middle and both ends of every sector
+        for hue in (0..72).map(|i| i as f32 * 5.0) {
+            for (saturation, value) in [(1.0, 1.0), (0.5, 0.8), (0.25, 0.3)] {
+                let color = Color::from_hsv(Deg(hue), saturation, value, 1.0);
+                let (h, s, v) = color.to_hsv();
+                assert!((h.0 - hue).abs() < 1e-2, "{hue} -> {}", h.0);
+                assert!(close(s, saturation) && close(v, value), "{hue}");
+            }
+        }
+        assert_eq!(Color::from_hsv(Deg(0.0), 1.0, 1.0, 1.0), Color::RED);
+        assert_eq!(Color::from_hsv(Deg(60.0), 1.0, 1.0, 1.0), Color::YELLOW);
+        assert_eq!(Color::from_hsv(Deg(120.0), 1.0, 1.0, 1.0), Color::GREEN);
+        assert_eq!(Color::from_hsv(Deg(180.0), 1.0, 1.0, 1.0), Color::CYAN);
+        assert_eq!(Color::from_hsv(Deg(240.0), 1.0, 1.0, 1.0), Color::BLUE);
+        assert_eq!(
+            Color::from_hsv(Deg(300.0), 1.0, 1.0, 0.5),
+            Color::MAGENTA.with_alpha(0.5)
+        );
+        // hue wraps
+        assert_eq!(
+            Color::from_hsv(Deg(390.0), 1.0, 1.0, 1.0),
+            Color::from_hsv(Deg(30.0), 1.0, 1.0, 1.0)
+        );
+        assert_eq!(
+            Color::from_hsv(Deg(-90.0), 1.0, 1.0, 1.0),
+            Color::from_hsv(Deg(270.0), 1.0, 1.0, 1.0)
+        );
+    }
+
+    #[test]
+    fn hsl_round_trip() {
+        for hue in (0..72).map(|i| i as f32 * 5.0) {
+            for (saturation, lightness) in [(1.0, 0.5), (0.5, 0.8), (0.75, 0.2)] {
+                let color = Color::from_hsl(Deg(hue), saturation, lightness, 1.0);
+                let (h, s, l) = color.to_hsl();
+                assert!((h.0 - hue).abs() < 1e-2, "{hue} -> {}", h.0);
+                assert!(close(s, saturation) && close(l, lightness), "{hue}");
+            }
+        }
+        assert_eq!(Color::from_hsl(Deg(0.0), 1.0, 0.5, 1.0), Color::RED);
+        assert_eq!(Color::from_hsl(Deg(240.0), 1.0, 0.5, 1.0), Color::BLUE);
+        assert_eq!(Color::from_hsl(Deg(90.0), 1.0, 1.0, 1.0), Color::WHITE);
+        assert_eq!(Color::from_hsl(Deg(90.0), 1.0, 0.0, 1.0), Color::BLACK);
+        let purple = Color::from_hsl(Deg(300.0), 1.0, 0.25098, 1.0);
+        assert_eq!(purple.to_srgb8(), Color::PURPLE.to_srgb8());
+    }
+
+    #[test]
+    fn grays_have_no_hue() {
+        for gray in [
+            Color::BLACK,
+            Color::GRAY,
+            Color::WHITE,
+            Color::rgb(0.7, 0.7, 0.7),
+        ] {
+            let (hue, saturation, _) = gray.to_hsv();
+            assert_eq!((hue.0, saturation), (0.0, 0.0));
+            let (hue, saturation, _) = gray.to_hsl();
+            assert_eq!((hue.0, saturation), (0.0, 0.0));
+        }
+        assert!(close(Color::GRAY.to_hsv().2, 0.50196));
+        assert!(close(Color::GRAY.to_hsl().2, 0.50196));
+        // without saturation the hue does not matter
+        assert_eq!(
+            Color::from_hsv(Deg(200.0), 0.0, 0.5, 1.0),
+            Color::from_hsv(Deg(0.0), 0.0, 0.5, 1.0)
+        );
+    }
+
+    #[test]
+    fn premultiplied_alpha() {
+        let color = Color::new(0.8, 0.4, 0.2, 0.5);
+        let premultiplied = color.premultiply();
+        assert_eq!(premultiplied, Color::new(0.4, 0.2, 0.1, 0.5));
+        assert_eq!(premultiplied.unpremultiply(), color);
+        assert_eq!(Color::RED.premultiply(), Color::RED);
+        assert_eq!(
+            Color::new(0.8, 0.4, 0.2, 0.0).premultiply().unpremultiply(),
+            Color::TRANSPARENT
+        );
+        assert_eq!(
+            Color::new(1.0, 1.0, 1.0, 0.0).unpremultiply(),
+            Color::TRANSPARENT
+        );
+    }
+}
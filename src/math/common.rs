@@ -1,4 +1,10 @@
-use crate::constant::EPSILON;
+use std::{
+    fmt::Debug,
+    hash::{Hash, Hasher},
+    ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign},
+};
+
+use crate::constant::{EPSILON, EPSILON_F64};
 
 pub trait FuzzyEq {
     fn fuzzy_eq(&self, other: &Self) -> bool;
@@ -10,6 +16,297 @@ impl FuzzyEq for f32 {
     }
 }
 
+impl FuzzyEq for f64 {
+    fn fuzzy_eq(&self, other: &Self) -> bool {
+        (*self - *other).abs() <= EPSILON_F64
+    }
+}
+
+/// how close two floats have to be for `ApproxEq`
+///
+/// NaN never matches and an infinity only matches itself, whatever the tolerance.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Tolerance<T> {
+    /// |a - b| <= epsilon, good near zero, useless for large magnitudes
+    Absolute(T),
+    /// |a - b| <= epsilon * max(|a|, |b|), never matches a nonzero value against zero
+    Relative(T),
+    /// at most this many representable floats apart
+    Ulps(u32),
+}
+
+/// comparison with an explicit tolerance, `FuzzyEq` is the absolute `Scalar::EPSILON` case
+pub trait ApproxEq<T> {
+    fn approx_eq_with(&self, other: &Self, tolerance: Tolerance<T>) -> bool;
+}
+
+impl ApproxEq<f32> for f32 {
+    fn approx_eq_with(&self, other: &Self, tolerance: Tolerance<f32>) -> bool {
+        scalar_approx_eq(*self, *other, tolerance)
+    }
+}
+
+impl ApproxEq<f64> for f64 {
+    fn approx_eq_with(&self, other: &Self, tolerance: Tolerance<f64>) -> bool {
+        scalar_approx_eq(*self, *other, tolerance)
+    }
+}
+
+fn scalar_approx_eq<T: Scalar>(a: T, b: T, tolerance: Tolerance<T>) -> bool {
+    if a == b {
+        // also covers infinities of the same sign
+        return true;
+    }
+    if a.abs() == T::INFINITY || b.abs() == T::INFINITY {
+        // a relative tolerance would scale to infinity and accept anything
+        return false;
+    }
+    match tolerance {
+        Tolerance::Absolute(epsilon) => (a - b).abs() <= epsilon,
+        Tolerance::Relative(epsilon) => (a - b).abs() <= epsilon * a.abs().max(b.abs()),
+        Tolerance::Ulps(ulps) => a.ulps(b).is_some_and(|distance| distance <= ulps as u64),
+    }
+}
+
+/// bit pattern of a float value, used for exact comparison and hashing
+pub trait ToBits {
+    type Bits: Eq + Hash;
+    fn to_bits(&self) -> Self::Bits;
+}
+
+impl ToBits for f32 {
+    type Bits = u32;
+    fn to_bits(&self) -> Self::Bits {
+        f32::to_bits(*self)
+    }
+}
+
+impl ToBits for f64 {
+    type Bits = u64;
+    fn to_bits(&self) -> Self::Bits {
+        f64::to_bits(*self)
+    }
+}
+
+/// strict bitwise equality and hashing, e.g. to use a `Vector` as a `HashMap` key
+///
+/// `PartialEq` on the math types is fuzzy, which makes it unfit for hashing. Here 0.0 and
+/// -0.0 are different while a NaN equals itself.
+#[derive(Debug, Clone, Copy)]
+pub struct Exact<V>(pub V);
+
+impl<V: ToBits> PartialEq for Exact<V> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.to_bits() == other.0.to_bits()
+    }
+}
+
+impl<V: ToBits> Eq for Exact<V> {}
+
+impl<V: ToBits> Hash for Exact<V> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.to_bits().hash(state);
+    }
+}
+
+/// floating point element type of `Vector` and `Matrix`
+pub trait Scalar:
+    'static
+    + Copy
+    + Debug
+    + Default
+    + PartialOrd
+    + FuzzyEq
+    + ApproxEq<Self>
+    + ToBits
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + Neg<Output = Self>
+    + AddAssign
+    + SubAssign
+    + MulAssign
+    + DivAssign
+{
+    const ZERO: Self;
+    const ONE: Self;
+    const TWO: Self;
+    const HALF: Self;
+    const PI: Self;
+    const INFINITY: Self;
+    /// smallest positive normal value, anything below is zero or denormal
+    const MIN_POSITIVE: Self;
+    /// tolerance used by `FuzzyEq`
+    const EPSILON: Self;
+    fn sqrt(self) -> Self;
+    fn abs(self) -> Self;
+    fn min(self, other: Self) -> Self;
+    fn max(self, other: Self) -> Self;
+    fn clamp(self, min: Self, max: Self) -> Self;
+    fn rem_euclid(self, rhs: Self) -> Self;
+    fn sin(self) -> Self;
+    fn cos(self) -> Self;
+    fn tan(self) -> Self;
+    fn sin_cos(self) -> (Self, Self);
+    fn asin(self) -> Self;
+    fn acos(self) -> Self;
+    fn atan2(self, other: Self) -> Self;
+    fn to_radians(self) -> Self;
+    fn to_degrees(self) -> Self;
+    /// rounds to the nearest value when Self is narrower than f64
+    fn from_f64(value: f64) -> Self;
+    fn to_f64(self) -> f64;
+    /// number of representable values between self and other, None if either is NaN
+    fn ulps(self, other: Self) -> Option<u64>;
+}
+
+impl Scalar for f32 {
+    const ZERO: Self = 0.0;
+    const ONE: Self = 1.0;
+    const TWO: Self = 2.0;
+    const HALF: Self = 0.5;
+    const PI: Self = std::f32::consts::PI;
+    const INFINITY: Self = f32::INFINITY;
+    const MIN_POSITIVE: Self = f32::MIN_POSITIVE;
+    const EPSILON: Self = EPSILON;
+    fn sqrt(self) -> Self {
+        f32::sqrt(self)
+    }
+    fn abs(self) -> Self {
+        f32::abs(self)
+    }
+    fn min(self, other: Self) -> Self {
+        f32::min(self, other)
+    }
+    fn max(self, other: Self) -> Self {
+        f32::max(self, other)
+    }
+    fn clamp(self, min: Self, max: Self) -> Self {
+        f32::clamp(self, min, max)
+    }
+    fn rem_euclid(self, rhs: Self) -> Self {
+        f32::rem_euclid(self, rhs)
+    }
+    fn sin(self) -> Self {
+        f32::sin(self)
+    }
+    fn cos(self) -> Self {
+        f32::cos(self)
+    }
+    fn tan(self) -> Self {
+        f32::tan(self)
+    }
+    fn sin_cos(self) -> (Self, Self) {
+        f32::sin_cos(self)
+    }
+    fn asin(self) -> Self {
+        f32::asin(self)
+    }
+    fn acos(self) -> Self {
+        f32::acos(self)
+    }
+    fn atan2(self, other: Self) -> Self {
+        f32::atan2(self, other)
+    }
+    fn to_radians(self) -> Self {
+        f32::to_radians(self)
+    }
+    fn to_degrees(self) -> Self {
+        f32::to_degrees(self)
+    }
+    fn from_f64(value: f64) -> Self {
+        value as f32
+    }
+    fn to_f64(self) -> f64 {
+        self as f64
+    }
+    fn ulps(self, other: Self) -> Option<u64> {
+        if self.is_nan() || other.is_nan() {
+            return None;
+        }
+        // map the sign-magnitude bits onto a monotonic integer line, -0.0 and 0.0 meet at 0
+        let ordered = |value: f32| {
+            let bits = f32::to_bits(value) as i32;
+            (if bits < 0 { i32::MIN - bits } else { bits }) as i64
+        };
+        Some((ordered(self) - ordered(other)).unsigned_abs())
+    }
+}
+
+impl Scalar for f64 {
+    const ZERO: Self = 0.0;
+    const ONE: Self = 1.0;
+    const TWO: Self = 2.0;
+    const HALF: Self = 0.5;
+    const PI: Self = std::f64::consts::PI;
+    const INFINITY: Self = f64::INFINITY;
+    const MIN_POSITIVE: Self = f64::MIN_POSITIVE;
+    const EPSILON: Self = EPSILON_F64;
+    fn sqrt(self) -> Self {
+        f64::sqrt(self)
+    }
+    fn abs(self) -> Self {
+        f64::abs(self)
+    }
+    fn min(self, other: Self) -> Self {
+        f64::min(self, other)
+    }
+    fn max(self, other: Self) -> Self {
+        f64::max(self, other)
+    }
+    fn clamp(self, min: Self, max: Self) -> Self {
+        f64::clamp(self, min, max)
+    }
+    fn rem_euclid(self, rhs: Self) -> Self {
+        f64::rem_euclid(self, rhs)
+    }
+    fn sin(self) -> Self {
+        f64::sin(self)
+    }
+    fn cos(self) -> Self {
+        f64::cos(self)
+    }
+    fn tan(self) -> Self {
+        f64::tan(self)
+    }
+    fn sin_cos(self) -> (Self, Self) {
+        f64::sin_cos(self)
+    }
+    fn asin(self) -> Self {
+        f64::asin(self)
+    }
+    fn acos(self) -> Self {
+        f64::acos(self)
+    }
+    fn atan2(self, other: Self) -> Self {
+        f64::atan2(self, other)
+    }
+    fn to_radians(self) -> Self {
+        f64::to_radians(self)
+    }
+    fn to_degrees(self) -> Self {
+        f64::to_degrees(self)
+    }
+    fn from_f64(value: f64) -> Self {
+        value
+    }
+    fn to_f64(self) -> f64 {
+        self
+    }
+    fn ulps(self, other: Self) -> Option<u64> {
+        if self.is_nan() || other.is_nan() {
+            return None;
+        }
+        let ordered = |value: f64| {
+            let bits = f64::to_bits(value) as i64;
+            (if bits < 0 { i64::MIN - bits } else { bits }) as i128
+        };
+        Some((ordered(self) - ordered(other)).unsigned_abs() as u64)
+    }
+}
+
 pub trait Dimension4 {
     type Value;
     fn new(x: Self::Value, y: Self::Value, z: Self::Value, w: Self::Value) -> Self;
@@ -27,6 +324,115 @@ pub trait Dimension3 {
     fn get_z(&self) -> Self::Value;
 }
 
+pub trait Dimension2 {
+    type Value;
+    fn new(x: Self::Value, y: Self::Value) -> Self;
+    fn get_x(&self) -> Self::Value;
+    fn get_y(&self) -> Self::Value;
+}
+
 pub trait Determinant {
-    fn det(&self) -> f32;
+    type Value;
+    fn det(&self) -> Self::Value;
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+    use crate::math::vector::Vector;
+
+    #[test]
+    fn ulp_distance() {
+        assert_eq!(1.0f32.ulps(1.0), Some(0));
+        assert_eq!(1.0f32.ulps(f32::from_bits(1.0f32.to_bits() + 3)), Some(3));
+        assert_eq!(0.0f32.ulps(-0.0), Some(0));
+
+        // across zero, the smallest denormals on either side are two steps apart
+        let tiny = f32::from_bits(1);
+        assert_eq!(tiny.ulps(-tiny), Some(2));
+        assert_eq!((-tiny).ulps(0.0), Some(1));
+        assert_eq!(f64::from_bits(1).ulps(-f64::from_bits(1)), Some(2));
+
+        // across sign the distance is the sum of both sides
+        let one = 1.0f32.to_bits() as u64;
+        assert_eq!(1.0f32.ulps(-1.0), Some(2 * one));
+        assert_eq!((-1.0f32).ulps(1.0), Some(2 * one));
+        assert_eq!(
+            f64::MAX.ulps(-f64::MAX),
+            Some(2 * f64::MAX.to_bits()),
+            "no overflow at the ends"
+        );
+
+        assert_eq!(f32::NAN.ulps(1.0), None);
+        assert_eq!(1.0f64.ulps(f64::NAN), None);
+    }
+
+    #[test]
+    fn ulps_tolerance() {
+        let next = f32::from_bits(1.0f32.to_bits() + 1);
+        assert!(1.0f32.approx_eq_with(&next, Tolerance::Ulps(1)));
+        assert!(!1.0f32.approx_eq_with(&next, Tolerance::Ulps(0)));
+        let tiny = f32::from_bits(1);
+        assert!(tiny.approx_eq_with(&-tiny, Tolerance::Ulps(2)));
+        assert!(!tiny.approx_eq_with(&-tiny, Tolerance::Ulps(1)));
+        assert!(!1.0f32.approx_eq_with(&-1.0, Tolerance::Ulps(1 << 20)));
+    }
+
+    #[test]
+    fn relative_tolerance() {
+        let relative = Tolerance::Relative(1e-6);
+        assert!(1e20f32.approx_eq_with(&(1e20 * (1.0 + 5e-7)), relative));
+        assert!(!1e20f32.approx_eq_with(&(1e20 * 1.00001), relative));
+        assert!(1e-20f64.approx_eq_with(&(1e-20 * (1.0 + 1e-7)), Tolerance::Relative(1e-6)));
+
+        // near zero only zero itself matches, absolute tolerance is the tool there
+        assert!(0.0f32.approx_eq_with(&-0.0, relative));
+        assert!(!0.0f32.approx_eq_with(&1e-30, relative));
+        assert!(!f32::from_bits(1).approx_eq_with(&0.0, relative));
+        assert!(0.0f32.approx_eq_with(&1e-30, Tolerance::Absolute(1e-6)));
+        assert!(!1e20f32.approx_eq_with(&(1e20 * (1.0 + 5e-7)), Tolerance::Absolute(1e-6)));
+    }
+
+    #[test]
+    fn non_finite_values() {
+        let tolerances = [
+            Tolerance::Absolute(1e-6),
+            Tolerance::Relative(1e-6),
+            Tolerance::Ulps(4),
+        ];
+        for tolerance in tolerances {
+            assert!(f32::INFINITY.approx_eq_with(&f32::INFINITY, tolerance));
+            assert!(f32::NEG_INFINITY.approx_eq_with(&f32::NEG_INFINITY, tolerance));
+            assert!(!f32::INFINITY.approx_eq_with(&f32::NEG_INFINITY, tolerance));
+            assert!(!f32::INFINITY.approx_eq_with(&f32::MAX, tolerance));
+            assert!(!f32::MAX.approx_eq_with(&f32::INFINITY, tolerance));
+            assert!(!f32::INFINITY.approx_eq_with(&1.0, tolerance));
+            assert!(!f32::NAN.approx_eq_with(&f32::NAN, tolerance));
+            assert!(!f32::NAN.approx_eq_with(&1.0, tolerance));
+        }
+        assert!(!f64::INFINITY.approx_eq_with(&1.0, Tolerance::Relative(0.5)));
+        assert!(!f32::NAN.fuzzy_eq(&f32::NAN));
+    }
+
+    #[test]
+    fn exact_hash_matches_eq() {
+        let mut set = HashSet::new();
+        assert!(set.insert(Exact(0.0f32)));
+        assert!(set.insert(Exact(-0.0f32)), "0.0 and -0.0 differ");
+        assert!(set.insert(Exact(f32::NAN)));
+        assert!(!set.insert(Exact(f32::NAN)), "a NaN equals itself");
+        assert!(!set.insert(Exact(0.0f32)));
+        assert_eq!(set.len(), 3);
+
+        // fuzzy equal vectors are still different keys
+        let a = Vector::<2>::from([1.0, 2.0]);
+        let b = Vector::<2>::from([1.0, 2.0 + 3e-7]);
+        assert_eq!(a, b);
+        assert_ne!(Exact(a), Exact(b));
+        let keys: HashSet<_> = [Exact(a), Exact(b), Exact(a)].into_iter().collect();
+        assert_eq!(keys.len(), 2);
+        assert!(keys.contains(&Exact(Vector::<2>::from([1.0, 2.0]))));
+    }
 }
@@ -0,0 +1,761 @@
+// Parametric curves over `Vector<D, T>`, usually `Vector<2>` in pixels or `Vector<3>`.
+//
+// Every curve is parameterized over t in 0..1. Catmull-Rom and B-splines are evaluated as a
+// chain of cubic Bezier segments, `segments` exposes that chain for splitting or for uses that
+// want plain Beziers. Flattening is adaptive: a Bezier is subdivided until its control points
+// are within the tolerance of the chord, so straight parts cost two points and tight bends get
+// as many as they need.
+
+use super::{common::Scalar, vector::Vector};
+
+/// subdivision depth at which flattening gives up and emits the chord, 2^16 pieces per segment
+const MAX_DEPTH: u32 = 16;
+
+/// Gauss-Legendre nodes and weights on -1..1
+const GAUSS_LEGENDRE: [(f64, f64); 5] = [
+    (0.0, 0.568_888_888_888_888_9),
+    (-0.538_469_310_105_683_1, 0.478_628_670_499_366_5),
+    (0.538_469_310_105_683_1, 0.478_628_670_499_366_5),
+    (-0.906_179_845_938_664, 0.236_926_885_056_189_1),
+    (0.906_179_845_938_664, 0.236_926_885_056_189_1),
+];
+
+/// intervals of the arc length quadrature per curve segment
+const LENGTH_INTERVALS: usize = 8;
+
+pub trait Curve<const D: usize, T: Scalar = f32> {
+    fn point(&self, t: T) -> Vector<D, T>;
+
+    /// dp/dt
+    fn derivative(&self, t: T) -> Vector<D, T>;
+
+    /// d²p/dt²
+    fn second_derivative(&self, t: T) -> Vector<D, T>;
+
+    /// polyline no further than `tolerance` from the curve, both end points included
+    ///
+    /// Coordinates only resolve to about `Scalar::EPSILON` times their magnitude, smaller,
+    /// negative or NaN tolerances are raised to that so far away curves still terminate early.
+    fn flatten(&self, tolerance: T) -> Vec<Vector<D, T>>;
+
+    /// number of pieces with a smooth parameterization, arc length is integrated per piece
+    fn piece_count(&self) -> usize {
+        1
+    }
+
+    /// unit tangent, zero where the curve stands still
+    fn tangent(&self, t: T) -> Vector<D, T> {
+        self.derivative(t).normalize()
+    }
+
+    /// arc length from 0 to t
+    fn length_to(&self, t: T) -> T {
+        let t = t.clamp(T::ZERO, T::ONE);
+        let intervals = self.piece_count() * LENGTH_INTERVALS;
+        let step = t / T::from_f64(intervals as f64);
+        let mut length = T::ZERO;
+        for interval in 0..intervals {
+            let center = (T::from_f64(interval as f64) + T::HALF) * step;
+            for (node, weight) in GAUSS_LEGENDRE {
+                let (node, weight) = (T::from_f64(node), T::from_f64(weight));
+                let speed = self.derivative(center + node * step * T::HALF).length();
+                length += weight * speed * step * T::HALF;
+            }
+        }
+        length
+    }
+
+    fn length(&self) -> T {
+        self.length_to(T::ONE)
+    }
+
+    /// parameter at which the arc length from the start reaches `distance`, clamped to the curve
+    fn t_at_length(&self, distance: T) -> T {
+        let total = self.length();
+        if distance <= T::ZERO || total <= T::ZERO {
+            return T::ZERO;
+        }
+        if distance >= total {
+            return T::ONE;
+        }
+
+        // Newton on the arc length, kept inside a shrinking bracket
+        let (mut low, mut high) = (T::ZERO, T::ONE);
+        let mut t = distance / total;
+        for _ in 0..32 {
+            let error = self.length_to(t) - distance;
+            if error.abs() < T::from_f64(1e-4) * total {
+                break;
+            }
+            if error < T::ZERO {
+                low = t;
+            } else {
+                high = t;
+            }
+            let speed = self.derivative(t).length();
+            let next = t - error / speed;
+            t = if speed > T::ZERO && next > low && next < high {
+                next
+            } else {
+                (low + high) * T::HALF
+            };
+        }
+        t
+    }
+
+    /// point at an arc length from the start, for evenly spaced samples
+    fn point_at_length(&self, distance: T) -> Vector<D, T> {
+        self.point(self.t_at_length(distance))
+    }
+
+    /// flattened polyline as f32 vertex positions, `Vector<2>` curves fit the `Float32x2` layout
+    fn vertices(&self, tolerance: T) -> Vec<[f32; D]> {
+        self.flatten(tolerance)
+            .into_iter()
+            .map(|point| point.cast::<f32>().to_array())
+            .collect()
+    }
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct QuadraticBezier<const D: usize, T: Scalar = f32> {
+    pub from: Vector<D, T>,
+    pub control: Vector<D, T>,
+    pub to: Vector<D, T>,
+}
+
+impl<const D: usize, T: Scalar> QuadraticBezier<D, T> {
+    pub fn new(from: Vector<D, T>, control: Vector<D, T>, to: Vector<D, T>) -> Self {
+        Self { from, control, to }
+    }
+
+    /// de Casteljau, the halves cover 0..t and t..1
+    pub fn split(&self, t: T) -> (Self, Self) {
+        let a = self.from.lerp(&self.control, t);
+        let b = self.control.lerp(&self.to, t);
+        let mid = a.lerp(&b, t);
+        (Self::new(self.from, a, mid), Self::new(mid, b, self.to))
+    }
+
+    /// exact degree elevation
+    pub fn to_cubic(self) -> CubicBezier<D, T> {
+        let two_thirds = T::TWO / T::from_f64(3.0);
+        CubicBezier::new(
+            self.from,
+            self.from.lerp(&self.control, two_thirds),
+            self.to.lerp(&self.control, two_thirds),
+            self.to,
+        )
+    }
+}
+
+impl<const D: usize, T: Scalar> Curve<D, T> for QuadraticBezier<D, T> {
+    fn point(&self, t: T) -> Vector<D, T> {
+        let inv = T::ONE - t;
+        self.from * (inv * inv) + self.control * (T::TWO * inv * t) + self.to * (t * t)
+    }
+
+    fn derivative(&self, t: T) -> Vector<D, T> {
+        ((self.control - self.from) * (T::ONE - t) + (self.to - self.control) * t) * T::TWO
+    }
+
+    fn second_derivative(&self, _: T) -> Vector<D, T> {
+        (self.to - self.control * T::TWO + self.from) * T::TWO
+    }
+
+    fn flatten(&self, tolerance: T) -> Vec<Vector<D, T>> {
+        self.to_cubic().flatten(tolerance)
+    }
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CubicBezier<const D: usize, T: Scalar = f32> {
+    pub from: Vector<D, T>,
+    pub control1: Vector<D, T>,
+    pub control2: Vector<D, T>,
+    pub to: Vector<D, T>,
+}
+
+impl<const D: usize, T: Scalar> CubicBezier<D, T> {
+    pub fn new(
+        from: Vector<D, T>,
+        control1: Vector<D, T>,
+        control2: Vector<D, T>,
+        to: Vector<D, T>,
+    ) -> Self {
+        Self {
+            from,
+            control1,
+            control2,
+            to,
+        }
+    }
+
+    /// de Casteljau, the halves cover 0..t and t..1
+    pub fn split(&self, t: T) -> (Self, Self) {
+        let a = self.from.lerp(&self.control1, t);
+        let b = self.control1.lerp(&self.control2, t);
+        let c = self.control2.lerp(&self.to, t);
+        let ab = a.lerp(&b, t);
+        let bc = b.lerp(&c, t);
+        let mid = ab.lerp(&bc, t);
+        (
+            Self::new(self.from, a, ab, mid),
+            Self::new(mid, bc, c, self.to),
+        )
+    }
+
+    /// the curve lies inside the hull of its control points, so this bounds its distance
+    /// from the chord, measured to the chord segment to catch curves that overshoot an end
+    fn flatness(&self) -> T {
+        distance_to_segment(self.control1, self.from, self.to).max(distance_to_segment(
+            self.control2,
+            self.from,
+            self.to,
+        ))
+    }
+
+    /// `tolerance` raised to what the control point coordinates can resolve
+    fn clamp_tolerance(&self, tolerance: T) -> T {
+        let magnitude = [self.from, self.control1, self.control2, self.to]
+            .iter()
+            .flat_map(|point| point.to_array())
+            .fold(T::ZERO, |max, value| max.max(value.abs()));
+        let min = magnitude * T::EPSILON;
+        if tolerance > min { tolerance } else { min }
+    }
+
+    /// appends the points after `from`
+    fn flatten_into(&self, tolerance: T, depth: u32, points: &mut Vec<Vector<D, T>>) {
+        if depth >= MAX_DEPTH || self.flatness() <= tolerance {
+            points.push(self.to);
+            return;
+        }
+        let (first, second) = self.split(T::HALF);
+        first.flatten_into(tolerance, depth + 1, points);
+        second.flatten_into(tolerance, depth + 1, points);
+    }
+}
+
+impl<const D: usize, T: Scalar> Curve<D, T> for CubicBezier<D, T> {
+    fn point(&self, t: T) -> Vector<D, T> {
+        let inv = T::ONE - t;
+        let three = T::from_f64(3.0);
+        self.from * (inv * inv * inv)
+            + self.control1 * (three * inv * inv * t)
+            + self.control2 * (three * inv * t * t)
+            + self.to * (t * t * t)
+    }
+
+    fn derivative(&self, t: T) -> Vector<D, T> {
+        let inv = T::ONE - t;
+        ((self.control1 - self.from) * (inv * inv)
+            + (self.control2 - self.control1) * (T::TWO * inv * t)
+            + (self.to - self.control2) * (t * t))
+            * T::from_f64(3.0)
+    }
+
+    fn second_derivative(&self, t: T) -> Vector<D, T> {
+        ((self.control2 - self.control1 * T::TWO + self.from) * (T::ONE - t)
+            + (self.to - self.control2 * T::TWO + self.control1) * t)
+            * T::from_f64(6.0)
+    }
+
+    fn flatten(&self, tolerance: T) -> Vec<Vector<D, T>> {
+        let mut points = vec![self.from];
+        self.flatten_into(self.clamp_tolerance(tolerance), 0, &mut points);
+        points
+    }
+}
+
+/// uniform Catmull-Rom spline through every point, the end tangents mirror the neighbours
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq)]
+pub struct CatmullRom<const D: usize, T: Scalar = f32> {
+    pub points: Vec<Vector<D, T>>,
+    /// closes the loop from the last point back to the first
+    pub closed: bool,
+}
+
+impl<const D: usize, T: Scalar> CatmullRom<D, T> {
+    pub fn new(points: Vec<Vector<D, T>>) -> Self {
+        Self {
+            points,
+            closed: false,
+        }
+    }
+
+    pub fn closed(points: Vec<Vector<D, T>>) -> Self {
+        Self {
+            points,
+            closed: true,
+        }
+    }
+
+    /// one cubic Bezier between every pair of consecutive points
+    pub fn segments(&self) -> Vec<CubicBezier<D, T>> {
+        (0..self.segment_count()).map(|i| self.segment(i)).collect()
+    }
+
+    /// a single point still makes one (degenerate) segment
+    pub fn segment_count(&self) -> usize {
+        match self.points.len() {
+            0 => 0,
+            1 => 1,
+            count if self.closed => count,
+            count => count - 1,
+        }
+    }
+
+    /// segment from point `index` to the next one, `index` below `segment_count`
+    pub fn segment(&self, index: usize) -> CubicBezier<D, T> {
+        if self.points.len() < 2 {
+            let point = self.points[0];
+            return CubicBezier::new(point, point, point, point);
+        }
+        let i = index as isize;
+        let (p0, p1, p2, p3) = (self.at(i - 1), self.at(i), self.at(i + 1), self.at(i + 2));
+        let six = T::from_f64(6.0);
+        CubicBezier::new(p1, p1 + (p2 - p0) / six, p2 - (p3 - p1) / six, p2)
+    }
+
+    fn at(&self, index: isize) -> Vector<D, T> {
+        let points = &self.points;
+        let count = points.len();
+        if self.closed {
+            points[index.rem_euclid(count as isize) as usize]
+        } else if index < 0 {
+            // reflected so the end segments keep their direction
+            points[0] * T::TWO - points[1]
+        } else if index as usize >= count {
+            points[count - 1] * T::TWO - points[count - 2]
+        } else {
+            points[index as usize]
+        }
+    }
+}
+
+/// uniform cubic B-spline, smoother than Catmull-Rom but only approximating its control points
+///
+/// Open splines repeat their end points so the curve starts and ends on them.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq)]
+pub struct BSpline<const D: usize, T: Scalar = f32> {
+    pub points: Vec<Vector<D, T>>,
+    pub closed: bool,
+}
+
+impl<const D: usize, T: Scalar> BSpline<D, T> {
+    pub fn new(points: Vec<Vector<D, T>>) -> Self {
+        Self {
+            points,
+            closed: false,
+        }
+    }
+
+    pub fn closed(points: Vec<Vector<D, T>>) -> Self {
+        Self {
+            points,
+            closed: true,
+        }
+    }
+
+    /// the spline as a chain of cubic Beziers
+    pub fn segments(&self) -> Vec<CubicBezier<D, T>> {
+        (0..self.segment_count()).map(|i| self.segment(i)).collect()
+    }
+
+    /// a single point still makes one (degenerate) segment
+    pub fn segment_count(&self) -> usize {
+        match self.points.len() {
+            0 => 0,
+            1 => 1,
+            count if self.closed => count,
+            count => count + 1,
+        }
+    }
+
+    /// segment over the control points `index - 2..index + 2` of an open spline, or
+    /// `index..index + 4` wrapped around a closed one, `index` below `segment_count`
+    pub fn segment(&self, index: usize) -> CubicBezier<D, T> {
+        if self.points.len() < 2 {
+            let point = self.points[0];
+            return CubicBezier::new(point, point, point, point);
+        }
+        let [p0, p1, p2, p3] = std::array::from_fn(|k| self.control(index + k));
+        let (three, four, six) = (T::from_f64(3.0), T::from_f64(4.0), T::from_f64(6.0));
+        CubicBezier::new(
+            (p0 + p1 * four + p2) / six,
+            (p1 * T::TWO + p2) / three,
+            (p1 + p2 * T::TWO) / three,
+            (p1 + p2 * four + p3) / six,
+        )
+    }
+
+    /// open splines repeat each end point twice more
+    fn control(&self, index: usize) -> Vector<D, T> {
+        let count = self.points.len();
+        if self.closed {
+            self.points[index % count]
+        } else {
+            self.points[index.saturating_sub(2).min(count - 1)]
+        }
+    }
+}
+
+/// splines share their evaluation through the Bezier chain, t is spread evenly over the segments
+///
+/// Point queries build only the segment under t, so sampling a long spline stays linear.
+macro_rules! spline_curve {
+    ($spline:ident) => {
+        impl<const D: usize, T: Scalar> Curve<D, T> for $spline<D, T> {
+            fn point(&self, t: T) -> Vector<D, T> {
+                segment_at(self.segment_count(), t).map_or(Vector::zero(), |(index, local, _)| {
+                    self.segment(index).point(local)
+                })
+            }
+
+            fn derivative(&self, t: T) -> Vector<D, T> {
+                segment_at(self.segment_count(), t).map_or(Vector::zero(), |(index, local, n)| {
+                    self.segment(index).derivative(local) * n
+                })
+            }
+
+            fn second_derivative(&self, t: T) -> Vector<D, T> {
+                segment_at(self.segment_count(), t).map_or(Vector::zero(), |(index, local, n)| {
+                    self.segment(index).second_derivative(local) * (n * n)
+                })
+            }
+
+            fn flatten(&self, tolerance: T) -> Vec<Vector<D, T>> {
+                let segments = self.segments();
+                let Some(first) = segments.first() else {
+                    return Vec::new();
+                };
+                let mut points = vec![first.from];
+                for segment in &segments {
+                    segment.flatten_into(segment.clamp_tolerance(tolerance), 0, &mut points);
+                }
+                points
+            }
+
+            fn piece_count(&self) -> usize {
+                self.segment_count().max(1)
+            }
+        }
+    };
+}
+
+spline_curve!(CatmullRom);
+spline_curve!(BSpline);
+
+/// (segment index, local t, segment count) for a global t
+fn segment_at<T: Scalar>(count: usize, t: T) -> Option<(usize, T, T)> {
+    if count == 0 {
+        return None;
+    }
+    let n = T::from_f64(count as f64);
+    let scaled = t.clamp(T::ZERO, T::ONE) * n;
+    let index = (scaled.to_f64() as usize).min(count - 1);
+    Some((index, scaled - T::from_f64(index as f64), n))
+}
+
+fn distance_to_segment<const D: usize, T: Scalar>(
+    point: Vector<D, T>,
+    from: Vector<D, T>,
+    to: Vector<D, T>,
+) -> T {
+    let chord = to - from;
+    let offset = point - from;
+    let chord_squared = chord.length_squared();
+    if chord_squared == T::ZERO {
+        return offset.length();
+    }
+    let along = (offset.dot(&chord) / chord_squared).clamp(T::ZERO, T::ONE);
+    (offset - chord * along).length()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::common::{ApproxEq, Dimension2, Tolerance};
+
+    const TOLERANCE: Tolerance<f32> = Tolerance::Absolute(1e-3);
+
+    fn v(x: f32, y: f32) -> Vector<2> {
+        Vector::<2>::new(x, y)
+    }
+
+    fn zigzag() -> Vec<Vector<2>> {
+        (0..7)
+            .map(|i| v(i as f32 * 10.0, if i % 2 == 0 { 0.0 } else { 8.0 }))
+            .collect()
+    }
+
+    #[test]
+    fn single_segments_match_the_chain() {
+        for closed in [false, true] {
+            let catmull_rom = CatmullRom {
+                points: zigzag(),
+                closed,
+            };
+            let b_spline = BSpline {
+                points: zigzag(),
+                closed,
+            };
+            let chains = [
+                (catmull_rom.segments(), catmull_rom.segment_count()),
+                (b_spline.segments(), b_spline.segment_count()),
+            ];
+            for (segments, count) in chains {
+                assert_eq!(segments.len(), count);
+                for pair in segments.windows(2) {
+                    assert_eq!(pair[0].to, pair[1].from);
+                }
+            }
+            let count = catmull_rom.segment_count();
+            for i in 0..count {
+                assert_eq!(catmull_rom.point(i as f32 / count as f32), zigzag()[i]);
+            }
+        }
+        let b_spline = BSpline::new(zigzag());
+        assert_eq!(b_spline.point(0.0), zigzag()[0]);
+        assert_eq!(b_spline.point(1.0), zigzag()[6]);
+        assert_eq!(CatmullRom::new(vec![v(1.0, 2.0)]).point(0.5), v(1.0, 2.0));
+        assert_eq!(BSpline::<2>::new(Vec::new()).point(0.5), Vector::zero());
+    }
+
+    #[test]
+    fn degenerate_tolerance_is_clamped() {
+        // the largest coordinate is 100, f32 resolves about 100 * EPSILON = 1e-4 there
+        let curve = CubicBezier::new(v(0.0, 0.0), v(0.0, 50.0), v(100.0, 50.0), v(100.0, 0.0));
+        let clamped = curve.flatten(1e-4).len();
+        assert!(clamped < 1 << MAX_DEPTH);
+        for tolerance in [0.0, -1.0, f32::NAN, f32::NEG_INFINITY] {
+            assert_eq!(curve.flatten(tolerance).len(), clamped);
+        }
+        let spline = CatmullRom::new(zigzag());
+        assert_eq!(spline.flatten(0.0), spline.flatten(f32::NAN));
+    }
+
+    #[test]
+    fn far_away_curves_stop_early() {
+        // the same bend a million pixels away cannot be resolved as finely as near the origin
+        let offset = v(1e6, 1e6);
+        let near = CubicBezier::new(v(0.0, 0.0), v(0.0, 50.0), v(100.0, 50.0), v(100.0, 0.0));
+        let far = CubicBezier::new(
+            near.from + offset,
+            near.control1 + offset,
+            near.control2 + offset,
+            near.to + offset,
+        );
+        let points = far.flatten(0.0);
+        assert!(points.len() < 1000, "{} points", points.len());
+        assert_eq!(points.len(), far.flatten(1.0).len());
+        assert!(near.flatten(0.0).len() > points.len());
+
+        let spline = BSpline::new(zigzag().into_iter().map(|point| point + offset).collect());
+        assert!(spline.flatten(0.0).len() < 1000);
+    }
+
+    /// samples the curve and checks that every sample is within `tolerance` of the polyline
+    fn assert_flattened(curve: &impl Curve<2>, tolerance: f32) {
+        let polyline = curve.flatten(tolerance);
+        assert!(polyline.len() >= 2);
+        for i in 0..=500 {
+            let point = curve.point(i as f32 / 500.0);
+            let error = polyline
+                .windows(2)
+                .map(|pair| distance_to_segment(point, pair[0], pair[1]))
+                .fold(f32::INFINITY, f32::min);
+            assert!(error <= tolerance * 1.001, "{error} > {tolerance}");
+        }
+    }
+
+    #[test]
+    fn flatten_stays_within_tolerance() {
+        let cubic = CubicBezier::new(v(0.0, 0.0), v(20.0, 120.0), v(80.0, -60.0), v(100.0, 40.0));
+        let quadratic = QuadraticBezier::new(v(0.0, 0.0), v(50.0, 100.0), v(100.0, 0.0));
+        let catmull_rom = CatmullRom::closed(zigzag());
+        let b_spline = BSpline::new(zigzag());
+        for tolerance in [2.0, 0.5, 0.1, 0.01] {
+            assert_flattened(&cubic, tolerance);
+            assert_flattened(&quadratic, tolerance);
+            assert_flattened(&catmull_rom, tolerance);
+            assert_flattened(&b_spline, tolerance);
+        }
+        assert_eq!(cubic.flatten(1.0)[0], cubic.from);
+        assert_eq!(cubic.flatten(1.0).last(), Some(&cubic.to));
+        assert_eq!(catmull_rom.flatten(1.0).last(), Some(&zigzag()[0]));
+        assert!(cubic.flatten(0.01).len() > cubic.flatten(1.0).len());
+
+        // straight curves need only their end points
+        let line = CubicBezier::new(v(0.0, 0.0), v(10.0, 10.0), v(20.0, 20.0), v(30.0, 30.0));
+        assert_eq!(line.flatten(0.01), [line.from, line.to]);
+        assert_eq!(line.vertices(0.01), [[0.0, 0.0], [30.0, 30.0]]);
+    }
+
+    #[test]
+    fn arc_length() {
+        // control points evenly along a line give constant speed
+        let line = CubicBezier::new(v(0.0, 0.0), v(10.0, 0.0), v(20.0, 0.0), v(30.0, 0.0));
+        assert!((line.length() - 30.0).abs() < 1e-4);
+        assert!((line.length_to(0.5) - 15.0).abs() < 1e-4);
+        assert!(
+            line.point_at_length(12.0)
+                .approx_eq_with(&v(12.0, 0.0), TOLERANCE)
+        );
+
+        // a quarter circle of radius 100
+        let k = 55.228_474;
+        let arc = CubicBezier::new(v(100.0, 0.0), v(100.0, k), v(k, 100.0), v(0.0, 100.0));
+        assert!((arc.length() - 50.0 * std::f32::consts::PI).abs() < 0.05);
+
+        // closed form for the parabola y = x² on 0..1
+        let parabola = QuadraticBezier::new(v(0.0, 0.0), v(0.5, 0.0), v(1.0, 1.0));
+        let exact = 5.0f32.sqrt() / 2.0 + 2.0f32.asinh() / 4.0;
+        assert!(
+            (parabola.length() - exact).abs() < 1e-5,
+            "{}",
+            parabola.length()
+        );
+
+        // uneven speed, equal distances still land at equal arc lengths
+        let uneven = CubicBezier::new(v(0.0, 0.0), v(1.0, 0.0), v(2.0, 0.0), v(30.0, 0.0));
+        let total = uneven.length();
+        for step in 1..5 {
+            let distance = total * step as f32 / 5.0;
+            let t = uneven.t_at_length(distance);
+            assert!((uneven.length_to(t) - distance).abs() < 1e-3 * total);
+            assert!((uneven.point_at_length(distance)[0] - distance).abs() < 1e-2);
+        }
+        assert_eq!(uneven.t_at_length(-1.0), 0.0);
+        assert_eq!(uneven.t_at_length(total * 2.0), 1.0);
+
+        // splines integrate per segment
+        let spline = CatmullRom::new(vec![v(0.0, 0.0), v(10.0, 0.0), v(20.0, 0.0), v(30.0, 0.0)]);
+        assert!((spline.length() - 30.0).abs() < 1e-3);
+        assert_eq!(CatmullRom::<2>::new(Vec::new()).length(), 0.0);
+    }
+
+    #[test]
+    fn split() {
+        let cubic = CubicBezier::new(v(0.0, 0.0), v(20.0, 120.0), v(80.0, -60.0), v(100.0, 40.0));
+        let quadratic = QuadraticBezier::new(v(0.0, 0.0), v(50.0, 100.0), v(100.0, 0.0));
+        for t in [0.25, 0.5, 0.8] {
+            let (first, second) = cubic.split(t);
+            assert!(first.to.approx_eq_with(&cubic.point(t), TOLERANCE));
+            assert_eq!(first.from, cubic.from);
+            assert_eq!(second.to, cubic.to);
+            for s in [0.0, 0.3, 0.7, 1.0] {
+                assert!(
+                    first
+                        .point(s)
+                        .approx_eq_with(&cubic.point(s * t), TOLERANCE)
+                );
+                let global = t + s * (1.0 - t);
+                assert!(
+                    second
+                        .point(s)
+                        .approx_eq_with(&cubic.point(global), TOLERANCE)
+                );
+            }
+            // the tangent is continuous across the split
+            let incoming = first.derivative(1.0) / t;
+            let outgoing = second.derivative(0.0) / (1.0 - t);
+            assert!(incoming.approx_eq_with(&outgoing, TOLERANCE));
+
+            let (first, second) = quadratic.split(t);
+            for s in [0.0, 0.3, 0.7, 1.0] {
+                assert!(
+                    first
+                        .point(s)
+                        .approx_eq_with(&quadratic.point(s * t), TOLERANCE)
+                );
+                let global = t + s * (1.0 - t);
+                assert!(
+                    second
+                        .point(s)
+                        .approx_eq_with(&quadratic.point(global), TOLERANCE)
+                );
+            }
+        }
+        let elevated = quadratic.to_cubic();
+        for t in [0.0, 0.2, 0.5, 0.9, 1.0] {
+            assert!(
+                elevated
+                    .point(t)
+                    .approx_eq_with(&quadratic.point(t), TOLERANCE)
+            );
+        }
+    }
+
+    /// central differences of point and derivative, on f64 to keep rounding out of the way
+    fn check_derivatives(curve: &impl Curve<2, f64>, t: f64) {
+        let (h, tolerance) = (1e-4, Tolerance::Absolute(1e-3));
+        let first = (curve.point(t + h) - curve.point(t - h)) / (2.0 * h);
+        assert!(curve.derivative(t).approx_eq_with(&first, tolerance), "{t}");
+        let second = (curve.derivative(t + h) - curve.derivative(t - h)) / (2.0 * h);
+        assert!(
+            curve
+                .second_derivative(t)
+                .approx_eq_with(&second, tolerance),
+            "{t}"
+        );
+    }
+
+    #[test]
+    fn derivatives() {
+        let cubic = CubicBezier::<2, f64>::new(
+            Vector::from([0.0, 0.0]),
+            Vector::from([20.0, 120.0]),
+            Vector::from([80.0, -60.0]),
+            Vector::from([100.0, 40.0]),
+        );
+        let quadratic = QuadraticBezier::<2, f64>::new(
+            Vector::from([0.0, 0.0]),
+            Vector::from([50.0, 100.0]),
+            Vector::from([100.0, 0.0]),
+        );
+        let points: Vec<Vector<2, f64>> = zigzag().into_iter().map(Vector::cast).collect();
+        let b_spline = BSpline::new(points.clone());
+        let catmull_rom = CatmullRom::new(points);
+        // away from the knots of the splines, where the second derivative jumps
+        for t in [0.05, 0.3, 0.45, 0.7, 0.9] {
+            check_derivatives(&cubic, t);
+            check_derivatives(&quadratic, t);
+            check_derivatives(&b_spline, t);
+            check_derivatives(&catmull_rom, t);
+        }
+
+        let single = CubicBezier::new(v(0.0, 0.0), v(1.0, 0.0), v(2.0, 0.0), v(3.0, 0.0));
+        assert_eq!(single.tangent(0.5), v(1.0, 0.0));
+        let still = CubicBezier::new(v(1.0, 1.0), v(1.0, 1.0), v(1.0, 1.0), v(1.0, 1.0));
+        assert_eq!(still.tangent(0.5), Vector::zero());
+    }
+
+    #[test]
+    fn f64_curves() {
+        // a quarter circle approximated far from the origin, where f32 has no digits left
+        let offset = 1e9;
+        let k = 0.552_284_749_830_793_4;
+        let p = |x: f64, y: f64| Vector::<2, f64>::new(offset + x, y);
+        let curve = CubicBezier::new(p(1.0, 0.0), p(1.0, k), p(k, 1.0), p(0.0, 1.0));
+        let length = curve.length();
+        assert!(
+            (length - std::f64::consts::FRAC_PI_2).abs() < 1e-3,
+            "{length}"
+        );
+        let mid = curve.point(0.5);
+        assert!(((mid[0] - offset).hypot(mid[1]) - 1.0).abs() < 1e-3);
+
+        let spline = CatmullRom::<2, f64>::new(zigzag().into_iter().map(Vector::cast).collect());
+        let points = spline.flatten(0.01);
+        assert_eq!(points[0].to_f32(), zigzag()[0]);
+        let vertices = spline.vertices(0.01);
+        assert_eq!(vertices.len(), points.len());
+        assert_eq!(vertices.last(), Some(&[60.0f32, 0.0]));
+    }
+}
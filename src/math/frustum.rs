@@ -0,0 +1,264 @@
+use super::{
+    common::{Dimension3, Scalar},
+    geometry::{Aabb, Plane, Sphere},
+    matrix::Matrix,
+    vector::Vector,
+};
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Containment {
+    Inside,
+    Outside,
+    Intersecting,
+}
+
+/// six inward facing planes in the order left, right, bottom, top, near, far
+/// (near and far trade places for reversed-z projections)
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Frustum<T: Scalar = f32> {
+    pub planes: [Plane<T>; 6],
+}
+
+impl<T: Scalar> Frustum<T> {
+    /// extracts the planes of a view-projection matrix targeting wgpu clip space
+    /// (-w <= x, y <= w and 0 <= z <= w), see Gribb and Hartmann. Works for reversed-z
+    /// as well, the far plane of an infinite projection never rejects anything.
+    pub fn from_matrix(view_projection: &Matrix<4, T>) -> Self {
+        let row = |i: usize| {
+            Vector::from([
+                view_projection[i][0],
+                view_projection[i][1],
+                view_projection[i][2],
+                view_projection[i][3],
+            ])
+        };
+        let (x, y, z, w) = (row(0), row(1), row(2), row(3));
+        let sums = [
+            (w, x),
+            (w, -x),
+            (w, y),
+            (w, -y),
+            (Vector::zero(), z),
+            (w, -z),
+        ];
+        Self {
+            planes: sums.map(|(a, b)| Self::plane(a, b)),
+        }
+    }
+
+    /// the plane a + b, at infinity when its normal cancels down to rounding noise
+    ///
+    /// The normal is measured against the normals of the rows it is summed from: the far plane
+    /// of an orthographic box a million units deep has a tiny normal but no cancellation, the
+    /// far plane of an infinite perspective cancels to zero.
+    fn plane(a: Vector<4, T>, b: Vector<4, T>) -> Plane<T> {
+        let xyz = |row: Vector<4, T>| Vector::<3, T>::new(row[0], row[1], row[2]);
+        let coefficients = a + b;
+        let normal = xyz(coefficients);
+        let scale = xyz(a).length() + xyz(b).length();
+        match Plane::new(normal, coefficients[3]) {
+            Some(plane) if normal.length() > T::EPSILON * scale => plane,
+            // plane at infinity, every point and every finite sphere is inside
+            _ => Plane {
+                normal: Vector::zero(),
+                distance: T::INFINITY,
+            },
+        }
+    }
+
+    pub fn contains_point(&self, point: Vector<3, T>) -> bool {
+        self.planes
+            .iter()
+            .all(|plane| plane.signed_distance(point) >= T::ZERO)
+    }
+
+    pub fn contains_sphere(&self, sphere: &Sphere<T>) -> Containment {
+        let mut containment = Containment::Inside;
+        for plane in &self.planes {
+            let distance = plane.signed_distance(sphere.center);
+            if distance < -sphere.radius {
+                return Containment::Outside;
+            }
+            if distance < sphere.radius {
+                containment = Containment::Intersecting;
+            }
+        }
+        containment
+    }
+
+    /// conservative, a box near a frustum corner may report Intersecting while outside
+    pub fn contains_aabb(&self, aabb: &Aabb<T>) -> Containment {
+        let mut containment = Containment::Inside;
+        for plane in &self.planes {
+            // corners furthest along and against the plane normal
+            let mut positive = aabb.min;
+            let mut negative = aabb.max;
+            for i in 0..3 {
+                if plane.normal[i] >= T::ZERO {
+                    positive[i] = aabb.max[i];
+                    negative[i] = aabb.min[i];
+                }
+            }
+            if plane.signed_distance(positive) < T::ZERO {
+                return Containment::Outside;
+            }
+            if plane.signed_distance(negative) < T::ZERO {
+                containment = Containment::Intersecting;
+            }
+        }
+        containment
+    }
+}
+
+impl<T: Scalar> From<Matrix<4, T>> for Frustum<T> {
+    fn from(view_projection: Matrix<4, T>) -> Self {
+        Self::from_matrix(&view_projection)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::{
+        angle::Deg,
+        common::{ApproxEq, Tolerance},
+    };
+
+    fn v(x: f32, y: f32, z: f32) -> Vector<3> {
+        Vector::<3>::new(x, y, z)
+    }
+
+    /// 90 degree camera at z = 10 looking at the origin, near at z = 9, far at z = -90
+    fn camera() -> Frustum {
+        let view = Matrix::<4>::look_at(v(0.0, 0.0, 10.0), Vector::zero(), v(0.0, 1.0, 0.0));
+        Frustum::from(Matrix::<4>::perspective(Deg(90.0), 1.0, 1.0, 100.0) * view)
+    }
+
+    #[test]
+    fn planes_face_inwards() {
+        let frustum = camera();
+        assert_eq!(frustum.planes[4].normal, v(0.0, 0.0, -1.0));
+        let near = frustum.planes[4].distance;
+        assert!(near.approx_eq_with(&9.0, Tolerance::Relative(1e-6)));
+        assert_eq!(frustum.planes[5].normal, v(0.0, 0.0, 1.0));
+        let half = std::f32::consts::FRAC_1_SQRT_2;
+        assert_eq!(frustum.planes[0].normal, v(half, 0.0, -half));
+    }
+
+    #[test]
+    fn points() {
+        let frustum = camera();
+        assert!(frustum.contains_point(Vector::zero()));
+        assert!(frustum.contains_point(v(9.0, -9.0, 0.0)));
+        assert!(!frustum.contains_point(v(11.0, 0.0, 0.0)));
+        assert!(!frustum.contains_point(v(0.0, 0.0, 9.5)));
+        assert!(!frustum.contains_point(v(0.0, 0.0, 20.0)));
+        assert!(frustum.contains_point(v(0.0, 0.0, -89.0)));
+        assert!(!frustum.contains_point(v(0.0, 0.0, -91.0)));
+    }
+
+    #[test]
+    fn spheres() {
+        let frustum = camera();
+        let sphere = |x: f32, z: f32| Sphere::new(v(x, 0.0, z), 1.0);
+        assert_eq!(
+            frustum.contains_sphere(&sphere(0.0, 0.0)),
+            Containment::Inside
+        );
+        assert_eq!(
+            frustum.contains_sphere(&sphere(10.0, 0.0)),
+            Containment::Intersecting
+        );
+        assert_eq!(
+            frustum.contains_sphere(&sphere(15.0, 0.0)),
+            Containment::Outside
+        );
+        assert_eq!(
+            frustum.contains_sphere(&sphere(0.0, 9.0)),
+            Containment::Intersecting
+        );
+        assert_eq!(
+            frustum.contains_sphere(&sphere(0.0, 12.0)),
+            Containment::Outside
+        );
+        assert_eq!(
+            frustum.contains_sphere(&sphere(0.0, -92.0)),
+            Containment::Outside
+        );
+    }
+
+    #[test]
+    fn boxes() {
+        let frustum = camera();
+        let unit = Aabb::new(v(-1.0, -1.0, -1.0), v(1.0, 1.0, 1.0));
+        assert_eq!(frustum.contains_aabb(&unit), Containment::Inside);
+        let across_near = Aabb::new(v(0.0, 0.0, 8.5), v(1.0, 1.0, 9.5));
+        assert_eq!(
+            frustum.contains_aabb(&across_near),
+            Containment::Intersecting
+        );
+        let behind = Aabb::new(v(-1.0, -1.0, 11.0), v(1.0, 1.0, 12.0));
+        assert_eq!(frustum.contains_aabb(&behind), Containment::Outside);
+        let right = Aabb::new(v(20.0, -1.0, -1.0), v(22.0, 1.0, 1.0));
+        assert_eq!(frustum.contains_aabb(&right), Containment::Outside);
+    }
+
+    #[test]
+    fn infinite_reversed_z_keeps_far_points() {
+        let projection = Matrix::<4>::perspective_infinite_reversed_z(Deg(60.0), 1.5, 0.1);
+        let frustum = Frustum::from(projection);
+        assert!(frustum.contains_point(v(0.0, 0.0, -1e5)));
+        assert!(!frustum.contains_point(v(0.0, 0.0, -0.05)));
+        let distant = Sphere::new(v(0.0, 0.0, -1e4), 10.0);
+        assert_eq!(frustum.contains_sphere(&distant), Containment::Inside);
+    }
+
+    #[test]
+    fn orthographic_box() {
+        let frustum = Frustum::from(Matrix::<4>::orthographic(-5.0, 5.0, -2.0, 2.0, 0.5, 50.0));
+        assert!(frustum.contains_point(v(4.9, 1.9, -1.0)));
+        assert!(!frustum.contains_point(v(5.1, 0.0, -1.0)));
+        assert!(!frustum.contains_point(v(0.0, 2.1, -1.0)));
+        assert!(!frustum.contains_point(v(0.0, 0.0, -51.0)));
+        let wide = Aabb::new(v(-10.0, -1.0, -10.0), v(10.0, 1.0, -5.0));
+        assert_eq!(frustum.contains_aabb(&wide), Containment::Intersecting);
+    }
+
+    #[test]
+    fn deep_and_wide_orthographic_boxes() {
+        // far - near and right - left beyond 1e6, the planes are tiny but real
+        let frustum = Frustum::from(Matrix::<4>::orthographic(0.0, 4e6, 3e6, 0.0, 1.0, 1e8));
+        assert!(
+            frustum
+                .planes
+                .iter()
+                .all(|plane| plane.distance.is_finite())
+        );
+        assert!(frustum.contains_point(v(1e6, 1e6, -5e7)));
+        assert!(!frustum.contains_point(v(1e6, 1e6, -2e8)));
+        assert!(!frustum.contains_point(v(5e6, 1e6, -10.0)));
+        assert!(!frustum.contains_point(v(-1e3, 1e6, -10.0)));
+        assert!(!frustum.contains_point(v(1e6, 4e6, -10.0)));
+        let beyond_far = Sphere::new(v(0.0, 0.0, -1.5e8), 1e7);
+        assert_eq!(frustum.contains_sphere(&beyond_far), Containment::Outside);
+
+        let reversed = Matrix::<4>::orthographic_reversed_z(-1e7, 1e7, -1e7, 1e7, 0.0, 1e9);
+        let frustum = Frustum::from(reversed);
+        assert!(frustum.contains_point(v(9e6, -9e6, -9e8)));
+        assert!(!frustum.contains_point(v(0.0, 0.0, -1.1e9)));
+        assert!(!frustum.contains_point(v(1.1e7, 0.0, -1.0)));
+    }
+
+    #[test]
+    fn infinite_far_plane_survives_a_view_matrix() {
+        // the view rotation leaves rounding noise where the far plane cancels
+        let view = Matrix::<4>::look_at(v(3.0, 7.0, 11.0), v(-5.0, 1.0, -2.0), v(0.0, 1.0, 0.0));
+        let projection = Matrix::<4>::perspective_infinite(Deg(60.0), 1.5, 0.1);
+        let frustum = Frustum::from(projection * view);
+        assert_eq!(frustum.planes[5].distance, f32::INFINITY);
+        let direction = (v(-5.0, 1.0, -2.0) - v(3.0, 7.0, 11.0)).normalize();
+        assert!(frustum.contains_point(v(3.0, 7.0, 11.0) + direction * 1e6));
+    }
+}
@@ -0,0 +1,535 @@
+use super::{
+    common::{Dimension2, Dimension3, Scalar},
+    matrix::Matrix,
+    vector::Vector,
+};
+
+/// 2D rectangle with its origin at (x, y), e.g. a viewport in pixels
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Rect<T: Scalar = f32> {
+    pub x: T,
+    pub y: T,
+    pub width: T,
+    pub height: T,
+}
+
+impl<T: Scalar> Rect<T> {
+    pub fn new(x: T, y: T, width: T, height: T) -> Self {
+        Self {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    pub fn min(&self) -> Vector<2, T> {
+        Vector::<2, T>::new(self.x, self.y)
+    }
+
+    pub fn max(&self) -> Vector<2, T> {
+        Vector::<2, T>::new(self.x + self.width, self.y + self.height)
+    }
+
+    pub fn center(&self) -> Vector<2, T> {
+        Vector::<2, T>::new(
+            self.x + self.width * T::HALF,
+            self.y + self.height * T::HALF,
+        )
+    }
+
+    pub fn contains_point(&self, point: Vector<2, T>) -> bool {
+        point[0] >= self.x
+            && point[0] <= self.x + self.width
+            && point[1] >= self.y
+            && point[1] <= self.y + self.height
+    }
+}
+
+/// closest intersection in front of a ray, `distance` is measured in units of the ray direction
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Hit<T: Scalar = f32> {
+    pub distance: T,
+    /// unit surface normal facing back towards the ray origin
+    pub normal: Vector<3, T>,
+}
+
+pub trait RayIntersect<T: Scalar = f32> {
+    fn ray_intersect(&self, ray: &Ray<T>) -> Option<Hit<T>>;
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Ray<T: Scalar = f32> {
+    pub origin: Vector<3, T>,
+    pub direction: Vector<3, T>,
+}
+
+impl<T: Scalar> Ray<T> {
+    /// normalizes the direction, so hit distances are world distances
+    pub fn new(origin: Vector<3, T>, direction: Vector<3, T>) -> Self {
+        Self {
+            origin,
+            direction: direction.normalize(),
+        }
+    }
+
+    pub fn point_at(&self, distance: T) -> Vector<3, T> {
+        self.origin + self.direction * distance
+    }
+
+    /// the direction is not renormalized, so a distance keeps pointing at the same
+    /// point in both spaces
+    pub fn transform(&self, matrix: &Matrix<4, T>) -> Self {
+        Self {
+            origin: matrix.transform_point(self.origin),
+            direction: matrix.transform_vector(self.direction),
+        }
+    }
+
+    pub fn intersect<S: RayIntersect<T>>(&self, shape: &S) -> Option<Hit<T>> {
+        shape.ray_intersect(self)
+    }
+
+    fn facing(&self, normal: Vector<3, T>) -> Vector<3, T> {
+        if normal.dot(&self.direction) > T::ZERO {
+            -normal
+        } else {
+            normal
+        }
+    }
+}
+
+/// points p with normal · p + distance = 0
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Plane<T: Scalar = f32> {
+    pub normal: Vector<3, T>,
+    pub distance: T,
+}
+
+impl<T: Scalar> Plane<T> {
+    /// scales the equation so the normal has unit length, None for a zero normal
+    pub fn new(normal: Vector<3, T>, distance: T) -> Option<Self> {
+        let length = normal.length();
+        (length > T::ZERO).then(|| Self {
+            normal: normal / length,
+            distance: distance / length,
+        })
+    }
+
+    /// None for a zero normal
+    pub fn from_point_normal(point: Vector<3, T>, normal: Vector<3, T>) -> Option<Self> {
+        Self::new(normal, -normal.dot(&point))
+    }
+
+    /// the normal follows the counterclockwise order of a, b, c, None when they are collinear
+    pub fn from_points(a: Vector<3, T>, b: Vector<3, T>, c: Vector<3, T>) -> Option<Self> {
+        let (ab, ac) = (b - a, c - a);
+        let normal = ab.cross(&ac);
+        if normal.length() <= T::EPSILON * ab.length() * ac.length() {
+            return None;
+        }
+        Self::from_point_normal(a, normal)
+    }
+
+    /// positive on the side the normal points to
+    pub fn signed_distance(&self, point: Vector<3, T>) -> T {
+        self.normal.dot(&point) + self.distance
+    }
+
+    pub fn closest_point(&self, point: Vector<3, T>) -> Vector<3, T> {
+        point - self.normal * self.signed_distance(point)
+    }
+
+    /// planes transform with the inverse transpose, a singular matrix or a plane without
+    /// normal comes back unchanged
+    pub fn transform(&self, matrix: &Matrix<4, T>) -> Self {
+        let Ok(inverse) = matrix.inverse() else {
+            return *self;
+        };
+        let plane = Vector::from([
+            self.normal[0],
+            self.normal[1],
+            self.normal[2],
+            self.distance,
+        ]) * inverse;
+        Self::new(Vector::<3, T>::new(plane[0], plane[1], plane[2]), plane[3]).unwrap_or(*self)
+    }
+}
+
+impl<T: Scalar> RayIntersect<T> for Plane<T> {
+    fn ray_intersect(&self, ray: &Ray<T>) -> Option<Hit<T>> {
+        // the normal has unit length, so this compares the sine of the angle between them
+        let denominator = self.normal.dot(&ray.direction);
+        if denominator.abs() <= T::EPSILON * ray.direction.length() {
+            return None;
+        }
+        let distance = -self.signed_distance(ray.origin) / denominator;
+        (distance >= T::ZERO).then(|| Hit {
+            distance,
+            normal: ray.facing(self.normal),
+        })
+    }
+}
+
+/// axis aligned bounding box
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Aabb<T: Scalar = f32> {
+    pub min: Vector<3, T>,
+    pub max: Vector<3, T>,
+}
+
+impl<T: Scalar> Aabb<T> {
+    pub fn new(a: Vector<3, T>, b: Vector<3, T>) -> Self {
+        Self {
+            min: a.min(&b),
+            max: a.max(&b),
+        }
+    }
+
+    /// None for an empty point list
+    pub fn from_points(points: &[Vector<3, T>]) -> Option<Self> {
+        let (first, rest) = points.split_first()?;
+        Some(rest.iter().fold(Self::new(*first, *first), |aabb, point| {
+            Self::new(aabb.min.min(point), aabb.max.max(point))
+        }))
+    }
+
+    pub fn center(&self) -> Vector<3, T> {
+        (self.min + self.max) * T::HALF
+    }
+
+    /// half size along every axis
+    pub fn extents(&self) -> Vector<3, T> {
+        (self.max - self.min) * T::HALF
+    }
+
+    pub fn corners(&self) -> [Vector<3, T>; 8] {
+        let (min, max) = (self.min, self.max);
+        [
+            Vector::<3, T>::new(min[0], min[1], min[2]),
+            Vector::<3, T>::new(max[0], min[1], min[2]),
+            Vector::<3, T>::new(min[0], max[1], min[2]),
+            Vector::<3, T>::new(max[0], max[1], min[2]),
+            Vector::<3, T>::new(min[0], min[1], max[2]),
+            Vector::<3, T>::new(max[0], min[1], max[2]),
+            Vector::<3, T>::new(min[0], max[1], max[2]),
+            Vector::<3, T>::new(max[0], max[1], max[2]),
+        ]
+    }
+
+    pub fn contains_point(&self, point: Vector<3, T>) -> bool {
+        (0..3).all(|i| point[i] >= self.min[i] && point[i] <= self.max[i])
+    }
+
+    pub fn closest_point(&self, point: Vector<3, T>) -> Vector<3, T> {
+        point.max(&self.min).min(&self.max)
+    }
+
+    pub fn intersects_aabb(&self, other: &Aabb<T>) -> bool {
+        (0..3).all(|i| self.min[i] <= other.max[i] && self.max[i] >= other.min[i])
+    }
+
+    pub fn intersects_sphere(&self, sphere: &Sphere<T>) -> bool {
+        let closest = self.closest_point(sphere.center);
+        (closest - sphere.center).length_squared() <= sphere.radius * sphere.radius
+    }
+
+    /// box around the eight transformed corners, grows under rotation
+    pub fn transform(&self, matrix: &Matrix<4, T>) -> Self {
+        let corners = self.corners().map(|corner| matrix.transform_point(corner));
+        Self::from_points(&corners).unwrap_or(*self)
+    }
+}
+
+impl<T: Scalar> RayIntersect<T> for Aabb<T> {
+    fn ray_intersect(&self, ray: &Ray<T>) -> Option<Hit<T>> {
+        // slab test, remembering which axis produced the entry and exit
+        let (mut near, mut far) = (-T::INFINITY, T::INFINITY);
+        let (mut near_axis, mut far_axis) = (0, 0);
+        let parallel = T::EPSILON * ray.direction.length();
+        for i in 0..3 {
+            if ray.direction[i].abs() <= parallel {
+                if ray.origin[i] < self.min[i] || ray.origin[i] > self.max[i] {
+                    return None;
+                }
+                continue;
+            }
+            let inverse = T::ONE / ray.direction[i];
+            let t0 = (self.min[i] - ray.origin[i]) * inverse;
+            let t1 = (self.max[i] - ray.origin[i]) * inverse;
+            let (t0, t1) = if t0 > t1 { (t1, t0) } else { (t0, t1) };
+            if t0 > near {
+                near = t0;
+                near_axis = i;
+            }
+            if t1 < far {
+                far = t1;
+                far_axis = i;
+            }
+            if near > far {
+                return None;
+            }
+        }
+        if far < T::ZERO {
+            return None;
+        }
+        let (distance, axis) = if near >= T::ZERO {
+            (near, near_axis)
+        } else {
+            (far, far_axis)
+        };
+        let mut normal = Vector::<3, T>::zero();
+        normal[axis] = T::ONE;
+        Some(Hit {
+            distance,
+            normal: ray.facing(normal),
+        })
+    }
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Sphere<T: Scalar = f32> {
+    pub center: Vector<3, T>,
+    pub radius: T,
+}
+
+impl<T: Scalar> Sphere<T> {
+    pub fn new(center: Vector<3, T>, radius: T) -> Self {
+        Self { center, radius }
+    }
+
+    pub fn contains_point(&self, point: Vector<3, T>) -> bool {
+        (point - self.center).length_squared() <= self.radius * self.radius
+    }
+
+    /// points inside the sphere are their own closest point
+    pub fn closest_point(&self, point: Vector<3, T>) -> Vector<3, T> {
+        let offset = point - self.center;
+        if offset.length_squared() <= self.radius * self.radius {
+            point
+        } else {
+            self.center + offset.normalize() * self.radius
+        }
+    }
+
+    pub fn intersects_sphere(&self, other: &Sphere<T>) -> bool {
+        let radius = self.radius + other.radius;
+        (other.center - self.center).length_squared() <= radius * radius
+    }
+
+    pub fn intersects_aabb(&self, aabb: &Aabb<T>) -> bool {
+        aabb.intersects_sphere(self)
+    }
+
+    /// the radius is scaled by the largest axis scale, so the result still bounds the shape
+    pub fn transform(&self, matrix: &Matrix<4, T>) -> Self {
+        let scale = (0..3)
+            .map(|col| Vector::<3, T>::new(matrix[0][col], matrix[1][col], matrix[2][col]).length())
+            .fold(T::ZERO, T::max);
+        Self {
+            center: matrix.transform_point(self.center),
+            radius: self.radius * scale,
+        }
+    }
+}
+
+impl<T: Scalar> RayIntersect<T> for Sphere<T> {
+    fn ray_intersect(&self, ray: &Ray<T>) -> Option<Hit<T>> {
+        let offset = ray.origin - self.center;
+        let a = ray.direction.length_squared();
+        let half_b = offset.dot(&ray.direction);
+        let c = offset.length_squared() - self.radius * self.radius;
+        let discriminant = half_b * half_b - a * c;
+        // a is only zero for a zero direction, any other length divides fine
+        if discriminant < T::ZERO || a <= T::ZERO {
+            return None;
+        }
+        let root = discriminant.sqrt();
+        let near = (-half_b - root) / a;
+        let far = (-half_b + root) / a;
+        let distance = if near >= T::ZERO { near } else { far };
+        if distance < T::ZERO {
+            return None;
+        }
+        let normal = (ray.point_at(distance) - self.center).normalize();
+        Some(Hit {
+            distance,
+            normal: ray.facing(normal),
+        })
+    }
+}
+
+/// counterclockwise a, b, c is the front face
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Triangle<T: Scalar = f32> {
+    pub a: Vector<3, T>,
+    pub b: Vector<3, T>,
+    pub c: Vector<3, T>,
+}
+
+impl<T: Scalar> Triangle<T> {
+    pub fn new(a: Vector<3, T>, b: Vector<3, T>, c: Vector<3, T>) -> Self {
+        Self { a, b, c }
+    }
+
+    /// zero for a degenerate triangle, `normalize` would also zero the normal of a tiny one
+    pub fn normal(&self) -> Vector<3, T> {
+        let normal = (self.b - self.a).cross(&(self.c - self.a));
+        let length = normal.length();
+        if length > T::ZERO {
+            normal / length
+        } else {
+            normal
+        }
+    }
+
+    pub fn area(&self) -> T {
+        (self.b - self.a).cross(&(self.c - self.a)).length() * T::HALF
+    }
+
+    pub fn transform(&self, matrix: &Matrix<4, T>) -> Self {
+        Self {
+            a: matrix.transform_point(self.a),
+            b: matrix.transform_point(self.b),
+            c: matrix.transform_point(self.c),
+        }
+    }
+
+    /// Voronoi region test from Real-Time Collision Detection 5.1.5
+    pub fn closest_point(&self, point: Vector<3, T>) -> Vector<3, T> {
+        let (a, b, c) = (self.a, self.b, self.c);
+        let ab = b - a;
+        let ac = c - a;
+        let ap = point - a;
+        let d1 = ab.dot(&ap);
+        let d2 = ac.dot(&ap);
+        if d1 <= T::ZERO && d2 <= T::ZERO {
+            return a;
+        }
+
+        let bp = point - b;
+        let d3 = ab.dot(&bp);
+        let d4 = ac.dot(&bp);
+        if d3 >= T::ZERO && d4 <= d3 {
+            return b;
+        }
+
+        let vc = d1 * d4 - d3 * d2;
+        if vc <= T::ZERO && d1 >= T::ZERO && d3 <= T::ZERO {
+            return a + ab * (d1 / (d1 - d3));
+        }
+
+        let cp = point - c;
+        let d5 = ab.dot(&cp);
+        let d6 = ac.dot(&cp);
+        if d6 >= T::ZERO && d5 <= d6 {
+            return c;
+        }
+
+        let vb = d5 * d2 - d1 * d6;
+        if vb <= T::ZERO && d2 >= T::ZERO && d6 <= T::ZERO {
+            return a + ac * (d2 / (d2 - d6));
+        }
+
+        let va = d3 * d6 - d5 * d4;
+        if va <= T::ZERO && (d4 - d3) >= T::ZERO && (d5 - d6) >= T::ZERO {
+            return b + (c - b) * ((d4 - d3) / ((d4 - d3) + (d5 - d6)));
+        }
+
+        let denominator = T::ONE / (va + vb + vc);
+        a + ab * (vb * denominator) + ac * (vc * denominator)
+    }
+}
+
+/// Moller-Trumbore, hits both faces
+impl<T: Scalar> RayIntersect<T> for Triangle<T> {
+    fn ray_intersect(&self, ray: &Ray<T>) -> Option<Hit<T>> {
+        let ab = self.b - self.a;
+        let ac = self.c - self.a;
+        let p = ray.direction.cross(&ac);
+        let det = ab.dot(&p);
+        // relative to the edge and direction lengths, so tiny and huge triangles behave the same
+        if det.abs() <= T::EPSILON * ab.length() * ac.length() * ray.direction.length() {
+            return None;
+        }
+        let inverse = T::ONE / det;
+        let ao = ray.origin - self.a;
+        let u = ao.dot(&p) * inverse;
+        if !(T::ZERO..=T::ONE).contains(&u) {
+            return None;
+        }
+        let q = ao.cross(&ab);
+        let v = ray.direction.dot(&q) * inverse;
+        if v < T::ZERO || u + v > T::ONE {
+            return None;
+        }
+        let distance = ac.dot(&q) * inverse;
+        (distance >= T::ZERO).then(|| Hit {
+            distance,
+            normal: ray.facing(self.normal()),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::common::{ApproxEq, Tolerance};
+
+    fn v(x: f32, y: f32, z: f32) -> Vector<3> {
+        Vector::<3>::new(x, y, z)
+    }
+
+    fn distance<S: RayIntersect>(ray: &Ray, shape: &S) -> f32 {
+        ray.intersect(shape).unwrap().distance
+    }
+
+    fn close(a: f32, b: f32) -> bool {
+        a.approx_eq_with(&b, Tolerance::Relative(1e-5))
+    }
+
+    #[test]
+    fn tiny_triangle_is_hit() {
+        let triangle = Triangle::new(v(0.0, 0.0, 0.0), v(1e-4, 0.0, 0.0), v(0.0, 1e-4, 0.0));
+        let ray = Ray::new(v(2e-5, 2e-5, 1.0), v(0.0, 0.0, -1.0));
+        let hit = ray.intersect(&triangle).unwrap();
+        assert!(close(hit.distance, 1.0));
+        assert_eq!(hit.normal, v(0.0, 0.0, 1.0));
+        let parallel = Ray::new(v(2e-5, 2e-5, 0.0), v(1.0, 0.0, 0.0));
+        assert_eq!(parallel.intersect(&triangle), None);
+    }
+
+    #[test]
+    fn short_direction_still_hits() {
+        // a ray moved into a space scaled by 1e-4 keeps its distances
+        let ray = Ray::new(v(0.0, 0.0, 10.0), v(0.0, 0.0, -1.0))
+            .transform(&Matrix::<4>::scale(1e-4, 1e-4, 1e-4));
+        let plane = Plane::from_point_normal(v(0.0, 0.0, 0.0), v(0.0, 0.0, 1.0)).unwrap();
+        assert!(close(distance(&ray, &plane), 10.0));
+        let sphere = Sphere::new(v(0.0, 0.0, 0.0), 1e-4);
+        assert!(close(distance(&ray, &sphere), 9.0));
+        let aabb = Aabb::new(v(-1e-4, -1e-4, -1e-4), v(1e-4, 1e-4, 1e-4));
+        assert!(close(distance(&ray, &aabb), 9.0));
+    }
+
+    #[test]
+    fn degenerate_planes() {
+        assert_eq!(Plane::new(Vector::zero(), 1.0), None);
+        assert_eq!(
+            Plane::from_point_normal(v(1.0, 2.0, 3.0), Vector::zero()),
+            None
+        );
+        let collinear = Plane::from_points(v(0.0, 0.0, 0.0), v(1.0, 1.0, 1.0), v(3.0, 3.0, 3.0));
+        assert_eq!(collinear, None);
+        let plane = Plane::new(v(0.0, 2e-8, 0.0), -4e-8).unwrap();
+        assert_eq!(plane.normal, v(0.0, 1.0, 0.0));
+        assert_eq!(plane.signed_distance(v(5.0, 3.0, 0.0)), 1.0);
+    }
+}
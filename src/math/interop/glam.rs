@@ -0,0 +1,115 @@
+use glam::{DMat3, DMat4, DVec2, DVec3, DVec4, Mat3, Mat4, Vec2, Vec3, Vec4};
+
+use crate::math::{matrix::Matrix, vector::Vector};
+
+macro_rules! vector_conversion {
+    ($glam:ty, $d:literal, $t:ty) => {
+        impl From<Vector<$d, $t>> for $glam {
+            fn from(vector: Vector<$d, $t>) -> Self {
+                <$glam>::from_array(vector.to_array())
+            }
+        }
+
+        impl From<$glam> for Vector<$d, $t> {
+            fn from(vector: $glam) -> Self {
+                Vector::from(vector.to_array())
+            }
+        }
+    };
+}
+
+vector_conversion!(Vec2, 2, f32);
+vector_conversion!(Vec3, 3, f32);
+vector_conversion!(Vec4, 4, f32);
+vector_conversion!(DVec2, 2, f64);
+vector_conversion!(DVec3, 3, f64);
+vector_conversion!(DVec4, 4, f64);
+
+// glam takes and returns column-major arrays, so rows and columns are swapped on the way
+macro_rules! matrix_conversion {
+    ($glam:ty, $d:literal, $t:ty) => {
+        impl From<Matrix<$d, $t>> for $glam {
+            fn from(matrix: Matrix<$d, $t>) -> Self {
+                let columns = matrix.transpose();
+                <$glam>::from_cols_array_2d(&std::array::from_fn(|col| columns[col]))
+            }
+        }
+
+        impl From<$glam> for Matrix<$d, $t> {
+            fn from(matrix: $glam) -> Self {
+                let columns = matrix.to_cols_array_2d();
+                let mut res = Matrix::new();
+                for (col, column) in columns.iter().enumerate() {
+                    for (row, value) in column.iter().enumerate() {
+                        res[row][col] = *value;
+                    }
+                }
+                res
+            }
+        }
+    };
+}
+
+matrix_conversion!(Mat3, 3, f32);
+matrix_conversion!(Mat4, 4, f32);
+matrix_conversion!(DMat3, 3, f64);
+matrix_conversion!(DMat4, 4, f64);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::{angle::Deg, common::Dimension3};
+
+    /// element (row, col) holds 10 * row + col
+    fn numbered<const D: usize>() -> Matrix<D> {
+        let mut matrix = Matrix::new();
+        for row in 0..D {
+            for col in 0..D {
+                matrix[row][col] = (10 * row + col) as f32;
+            }
+        }
+        matrix
+    }
+
+    #[test]
+    fn matrices_keep_rows_and_columns() {
+        let mat = Mat4::from(numbered::<4>());
+        assert_eq!(mat.row(1), Vec4::new(10.0, 11.0, 12.0, 13.0));
+        assert_eq!(mat.col(1), Vec4::new(1.0, 11.0, 21.0, 31.0));
+        assert_eq!(Matrix::<4>::from(mat), numbered::<4>());
+        let mat = Mat3::from(numbered::<3>());
+        assert_eq!(mat.row(2), Vec3::new(20.0, 21.0, 22.0));
+        assert_eq!(Matrix::<3>::from(mat), numbered::<3>());
+        let wide = DMat4::from(numbered::<4>().cast::<f64>());
+        assert_eq!(Matrix::<4, f64>::from(wide), numbered::<4>().cast());
+    }
+
+    #[test]
+    fn transforms_agree() {
+        let translation = Mat4::from(Matrix::<4>::translate(1.0, 2.0, 3.0));
+        assert_eq!(
+            translation,
+            Mat4::from_translation(Vec3::new(1.0, 2.0, 3.0))
+        );
+
+        let m = Matrix::<4>::translate(1.0, 2.0, 3.0) * Matrix::<4>::rotate_z(Deg(90.0));
+        let mat = Mat4::from(m);
+        let point = Vector::<3>::new(1.0, 0.5, -2.0);
+        let expected = mat.transform_point3(Vec3::from(point));
+        assert_eq!(m.transform_point(point), Vector::from(expected));
+        assert!(mat.abs_diff_eq(
+            Mat4::from_translation(Vec3::new(1.0, 2.0, 3.0))
+                * Mat4::from_rotation_z(std::f32::consts::FRAC_PI_2),
+            1e-6
+        ));
+    }
+
+    #[test]
+    fn vectors() {
+        let vector = Vector::<3>::new(1.0, 2.0, 3.0);
+        assert_eq!(Vec3::from(vector), Vec3::new(1.0, 2.0, 3.0));
+        assert_eq!(Vector::<3>::from(Vec3::new(1.0, 2.0, 3.0)), vector);
+        let wide = DVec4::new(1.0, 2.0, 3.0, 4.0);
+        assert_eq!(DVec4::from(Vector::<4, f64>::from(wide)), wide);
+    }
+}
@@ -0,0 +1,112 @@
+use mint::{ColumnMatrix3, ColumnMatrix4, RowMatrix3, RowMatrix4, Vector2, Vector3, Vector4};
+
+use crate::math::{common::Scalar, matrix::Matrix, vector::Vector};
+
+macro_rules! vector_conversion {
+    ($mint:ident, $d:literal) => {
+        impl<T: Scalar> From<Vector<$d, T>> for $mint<T> {
+            fn from(vector: Vector<$d, T>) -> Self {
+                $mint::from(vector.to_array())
+            }
+        }
+
+        impl<T: Scalar> From<$mint<T>> for Vector<$d, T> {
+            fn from(vector: $mint<T>) -> Self {
+                Vector::from(<[T; $d]>::from(vector))
+            }
+        }
+    };
+}
+
+vector_conversion!(Vector2, 2);
+vector_conversion!(Vector3, 3);
+vector_conversion!(Vector4, 4);
+
+// the array form of a RowMatrix lists rows, the array form of a ColumnMatrix lists columns
+macro_rules! matrix_conversion {
+    ($row:ident, $column:ident, $d:literal) => {
+        impl<T: Scalar> From<Matrix<$d, T>> for $row<T> {
+            fn from(matrix: Matrix<$d, T>) -> Self {
+                $row::from(std::array::from_fn::<_, $d, _>(|row| matrix[row]))
+            }
+        }
+
+        impl<T: Scalar> From<$row<T>> for Matrix<$d, T> {
+            fn from(matrix: $row<T>) -> Self {
+                let rows = <[[T; $d]; $d]>::from(matrix);
+                let mut res = Matrix::new();
+                for row in 0..$d {
+                    res[row] = rows[row];
+                }
+                res
+            }
+        }
+
+        impl<T: Scalar> From<Matrix<$d, T>> for $column<T> {
+            fn from(matrix: Matrix<$d, T>) -> Self {
+                let columns = matrix.transpose();
+                $column::from(std::array::from_fn::<_, $d, _>(|col| columns[col]))
+            }
+        }
+
+        impl<T: Scalar> From<$column<T>> for Matrix<$d, T> {
+            fn from(matrix: $column<T>) -> Self {
+                let columns = <[[T; $d]; $d]>::from(matrix);
+                let mut res = Matrix::new();
+                for col in 0..$d {
+                    res[col] = columns[col];
+                }
+                res.transpose()
+            }
+        }
+    };
+}
+
+matrix_conversion!(RowMatrix3, ColumnMatrix3, 3);
+matrix_conversion!(RowMatrix4, ColumnMatrix4, 4);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// element (row, col) holds 10 * row + col
+    fn numbered<const D: usize>() -> Matrix<D> {
+        let mut matrix = Matrix::new();
+        for row in 0..D {
+            for col in 0..D {
+                matrix[row][col] = (10 * row + col) as f32;
+            }
+        }
+        matrix
+    }
+
+    #[test]
+    fn row_matrices_list_rows() {
+        let rows = RowMatrix4::from(numbered::<4>());
+        assert_eq!(rows.y, Vector4::from([10.0, 11.0, 12.0, 13.0]));
+        assert_eq!(Matrix::<4>::from(rows), numbered::<4>());
+        let rows = RowMatrix3::from(numbered::<3>());
+        assert_eq!(rows.z, Vector3::from([20.0, 21.0, 22.0]));
+        assert_eq!(Matrix::<3>::from(rows), numbered::<3>());
+    }
+
+    #[test]
+    fn column_matrices_list_columns() {
+        let columns = ColumnMatrix4::from(numbered::<4>());
+        assert_eq!(columns.y, Vector4::from([1.0, 11.0, 21.0, 31.0]));
+        assert_eq!(Matrix::<4>::from(columns), numbered::<4>());
+        let translation = ColumnMatrix4::from(Matrix::<4>::translate(1.0, 2.0, 3.0));
+        assert_eq!(translation.w, Vector4::from([1.0, 2.0, 3.0, 1.0]));
+        let columns = ColumnMatrix3::from(numbered::<3>().cast::<f64>());
+        assert_eq!(Matrix::<3, f64>::from(columns), numbered::<3>().cast());
+    }
+
+    #[test]
+    fn vectors() {
+        let vector = Vector::<2>::from([1.0, 2.0]);
+        assert_eq!(Vector2::from(vector), Vector2 { x: 1.0, y: 2.0 });
+        assert_eq!(Vector::<2>::from(Vector2 { x: 1.0, y: 2.0 }), vector);
+        let wide = Vector4::from([1.0, 2.0, 3.0, 4.0_f64]);
+        assert_eq!(Vector4::from(Vector::<4, f64>::from(wide)), wide);
+    }
+}
@@ -0,0 +1,14 @@
+// Conversions to and from ecosystem math crates, each behind the cargo feature of the same name.
+//
+// `Matrix` stores rows (`matrix[row][col]`) and multiplies column vectors, `M * v`.
+// glam and nalgebra use the same column vector convention, so the conversions keep the
+// mathematical matrix intact: element (row, col) here is element (row, col) there,
+// only the storage order differs (glam and nalgebra store columns). mint offers both
+// `RowMatrix*` and `ColumnMatrix*`, which are converted element for element as well.
+
+#[cfg(feature = "glam")]
+mod glam;
+#[cfg(feature = "mint")]
+mod mint;
+#[cfg(feature = "nalgebra")]
+mod nalgebra;
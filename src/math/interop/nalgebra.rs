@@ -0,0 +1,100 @@
+use nalgebra::{Matrix3, Matrix4, Vector2, Vector3, Vector4};
+
+use crate::math::{common::Scalar, matrix::Matrix, vector::Vector};
+
+macro_rules! vector_conversion {
+    ($nalgebra:ident, $d:literal) => {
+        impl<T: Scalar + nalgebra::Scalar> From<Vector<$d, T>> for $nalgebra<T> {
+            fn from(vector: Vector<$d, T>) -> Self {
+                $nalgebra::from(vector.to_array())
+            }
+        }
+
+        impl<T: Scalar + nalgebra::Scalar> From<$nalgebra<T>> for Vector<$d, T> {
+            fn from(vector: $nalgebra<T>) -> Self {
+                Vector::from(std::array::from_fn(|i| vector[i]))
+            }
+        }
+    };
+}
+
+vector_conversion!(Vector2, 2);
+vector_conversion!(Vector3, 3);
+vector_conversion!(Vector4, 4);
+
+// nalgebra indexes with (row, col) like `Matrix`, no transposition is needed
+macro_rules! matrix_conversion {
+    ($nalgebra:ident, $d:literal) => {
+        impl<T: Scalar + nalgebra::Scalar> From<Matrix<$d, T>> for $nalgebra<T> {
+            fn from(matrix: Matrix<$d, T>) -> Self {
+                $nalgebra::from_fn(|row, col| matrix[row][col])
+            }
+        }
+
+        impl<T: Scalar + nalgebra::Scalar> From<$nalgebra<T>> for Matrix<$d, T> {
+            fn from(matrix: $nalgebra<T>) -> Self {
+                let mut res = Matrix::new();
+                for row in 0..$d {
+                    for col in 0..$d {
+                        res[row][col] = matrix[(row, col)];
+                    }
+                }
+                res
+            }
+        }
+    };
+}
+
+matrix_conversion!(Matrix3, 3);
+matrix_conversion!(Matrix4, 4);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::{angle::Deg, common::Dimension3};
+
+    /// element (row, col) holds 10 * row + col
+    fn numbered<const D: usize>() -> Matrix<D> {
+        let mut matrix = Matrix::new();
+        for row in 0..D {
+            for col in 0..D {
+                matrix[row][col] = (10 * row + col) as f32;
+            }
+        }
+        matrix
+    }
+
+    #[test]
+    fn matrices_keep_rows_and_columns() {
+        let matrix = Matrix4::from(numbered::<4>());
+        assert_eq!(matrix[(1, 2)], 12.0);
+        assert_eq!(matrix[(2, 1)], 21.0);
+        assert_eq!(Matrix::<4>::from(matrix), numbered::<4>());
+        let matrix = Matrix3::from(numbered::<3>().cast::<f64>());
+        assert_eq!(matrix[(0, 2)], 2.0);
+        assert_eq!(Matrix::<3, f64>::from(matrix), numbered::<3>().cast());
+    }
+
+    #[test]
+    fn transforms_agree() {
+        let translation = Matrix4::from(Matrix::<4>::translate(1.0, 2.0, 3.0));
+        assert_eq!(
+            translation,
+            Matrix4::new_translation(&Vector3::new(1.0, 2.0, 3.0))
+        );
+
+        let m = Matrix::<4>::translate(1.0, 2.0, 3.0) * Matrix::<4>::rotate_x(Deg(30.0));
+        let point = Vector::<4>::from([1.0, 0.5, -2.0, 1.0]);
+        let expected = Matrix4::from(m) * Vector4::from(point);
+        assert_eq!(m * point, Vector::from(expected));
+    }
+
+    #[test]
+    fn vectors() {
+        let vector = Vector::<3>::new(1.0, 2.0, 3.0);
+        assert_eq!(Vector3::from(vector), Vector3::new(1.0, 2.0, 3.0));
+        assert_eq!(Vector::<3>::from(Vector3::new(1.0, 2.0, 3.0)), vector);
+        let wide = Vector2::new(1.0, 2.0_f64);
+        assert_eq!(Vector2::from(Vector::<2, f64>::from(wide)), wide);
+    }
+}
@@ -0,0 +1,323 @@
+use bytemuck::{Pod, bytes_of};
+
+use super::{matrix::Matrix, vector::Vector};
+
+// WGSL host-shareable types as plain arrays, matrices are stored column by column
+// and every column of a mat3x3 is padded to a vec4.
+pub type GpuVec2 = [f32; 2];
+pub type GpuVec3 = [f32; 3];
+pub type GpuVec4 = [f32; 4];
+pub type GpuMat2 = [[f32; 2]; 2];
+pub type GpuMat3 = [[f32; 4]; 3];
+pub type GpuMat4 = [[f32; 4]; 4];
+
+/// std140 is the layout of `var<uniform>`, std430 the layout of `var<storage>`
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Layout {
+    Std140,
+    Std430,
+}
+
+/// conversion of a CPU value into its WGSL memory representation
+///
+/// Implement it for a `#[repr(C)]` uniform struct by converting every field and spelling out
+/// the padding, or use `LayoutWriter` to let the padding be computed at runtime.
+pub trait GpuLayout {
+    type Gpu: Pod;
+    /// AlignOf in the WGSL spec
+    const ALIGN: usize;
+    fn to_gpu(&self) -> Self::Gpu;
+}
+
+pub const fn align_to(offset: usize, align: usize) -> usize {
+    offset.div_ceil(align) * align
+}
+
+impl GpuLayout for f32 {
+    type Gpu = f32;
+    const ALIGN: usize = 4;
+    fn to_gpu(&self) -> Self::Gpu {
+        *self
+    }
+}
+
+impl GpuLayout for u32 {
+    type Gpu = u32;
+    const ALIGN: usize = 4;
+    fn to_gpu(&self) -> Self::Gpu {
+        *self
+    }
+}
+
+impl GpuLayout for i32 {
+    type Gpu = i32;
+    const ALIGN: usize = 4;
+    fn to_gpu(&self) -> Self::Gpu {
+        *self
+    }
+}
+
+impl GpuLayout for Vector<2> {
+    type Gpu = GpuVec2;
+    const ALIGN: usize = 8;
+    fn to_gpu(&self) -> Self::Gpu {
+        self.to_array()
+    }
+}
+
+/// a vec3 is 12 bytes but 16 aligned, a following scalar can fill the gap
+impl GpuLayout for Vector<3> {
+    type Gpu = GpuVec3;
+    const ALIGN: usize = 16;
+    fn to_gpu(&self) -> Self::Gpu {
+        self.to_array()
+    }
+}
+
+impl GpuLayout for Vector<4> {
+    type Gpu = GpuVec4;
+    const ALIGN: usize = 16;
+    fn to_gpu(&self) -> Self::Gpu {
+        self.to_array()
+    }
+}
+
+impl GpuLayout for Matrix<2> {
+    type Gpu = GpuMat2;
+    const ALIGN: usize = 8;
+    fn to_gpu(&self) -> Self::Gpu {
+        let mut gpu = [[0.0; 2]; 2];
+        for (col, column) in gpu.iter_mut().enumerate() {
+            for (row, value) in column.iter_mut().enumerate() {
+                *value = self[row][col];
+            }
+        }
+        gpu
+    }
+}
+
+impl GpuLayout for Matrix<3> {
+    type Gpu = GpuMat3;
+    const ALIGN: usize = 16;
+    fn to_gpu(&self) -> Self::Gpu {
+        let mut gpu = [[0.0; 4]; 3];
+        for (col, column) in gpu.iter_mut().enumerate() {
+            for (row, value) in column.iter_mut().take(3).enumerate() {
+                *value = self[row][col];
+            }
+        }
+        gpu
+    }
+}
+
+impl GpuLayout for Matrix<4> {
+    type Gpu = GpuMat4;
+    const ALIGN: usize = 16;
+    fn to_gpu(&self) -> Self::Gpu {
+        let mut gpu = [[0.0; 4]; 4];
+        for (col, column) in gpu.iter_mut().enumerate() {
+            for (row, value) in column.iter_mut().enumerate() {
+                *value = self[row][col];
+            }
+        }
+        gpu
+    }
+}
+
+/// builds a WGSL struct field by field, inserting the padding required by the layout
+pub struct LayoutWriter {
+    layout: Layout,
+    bytes: Vec<u8>,
+    align: usize,
+}
+
+impl LayoutWriter {
+    pub fn new(layout: Layout) -> Self {
+        Self {
+            layout,
+            bytes: Vec::new(),
+            align: 4,
+        }
+    }
+
+    pub fn write<T: GpuLayout>(&mut self, value: &T) -> &mut Self {
+        self.pad_to(T::ALIGN);
+        self.bytes.extend_from_slice(bytes_of(&value.to_gpu()));
+        self
+    }
+
+    /// std140 rounds the array stride up to 16 bytes, std430 only to the element alignment
+    pub fn write_array<T: GpuLayout>(&mut self, values: &[T]) -> &mut Self {
+        let align = match self.layout {
+            Layout::Std140 => align_to(T::ALIGN, 16),
+            Layout::Std430 => T::ALIGN,
+        };
+        let stride = align_to(size_of::<T::Gpu>(), align);
+        self.pad_to(align);
+        for value in values {
+            let start = self.bytes.len();
+            self.bytes.extend_from_slice(bytes_of(&value.to_gpu()));
+            self.bytes.resize(start + stride, 0);
+        }
+        self
+    }
+
+    pub fn offset(&self) -> usize {
+        self.bytes.len()
+    }
+
+    /// pads the struct size to a multiple of its alignment
+    pub fn finish(&mut self) -> Vec<u8> {
+        let align = match self.layout {
+            Layout::Std140 => align_to(self.align, 16),
+            Layout::Std430 => self.align,
+        };
+        self.bytes.resize(align_to(self.bytes.len(), align), 0);
+        std::mem::take(&mut self.bytes)
+    }
+
+    fn pad_to(&mut self, align: usize) {
+        self.align = self.align.max(align);
+        self.bytes.resize(align_to(self.bytes.len(), align), 0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::common::{Dimension2, Dimension3};
+
+    /// the written bytes as f32 words, padding reads as 0.0
+    fn words(bytes: &[u8]) -> Vec<f32> {
+        bytes
+            .chunks_exact(4)
+            .map(|word| f32::from_ne_bytes(word.try_into().unwrap()))
+            .collect()
+    }
+
+    fn v3(x: f32, y: f32, z: f32) -> Vector<3> {
+        Vector::<3>::new(x, y, z)
+    }
+
+    #[test]
+    fn vec3_followed_by_a_scalar() {
+        for layout in [Layout::Std140, Layout::Std430] {
+            let mut writer = LayoutWriter::new(layout);
+            writer.write(&v3(1.0, 2.0, 3.0));
+            assert_eq!(writer.offset(), 12);
+            writer.write(&4.0f32);
+            assert_eq!(words(&writer.finish()), [1.0, 2.0, 3.0, 4.0]);
+        }
+    }
+
+    #[test]
+    fn vec3_after_a_scalar_is_aligned() {
+        for layout in [Layout::Std140, Layout::Std430] {
+            let mut writer = LayoutWriter::new(layout);
+            writer.write(&1.0f32).write(&v3(2.0, 3.0, 4.0));
+            let bytes = writer.finish();
+            assert_eq!(bytes.len(), 32);
+            assert_eq!(words(&bytes), [1.0, 0.0, 0.0, 0.0, 2.0, 3.0, 4.0, 0.0]);
+        }
+    }
+
+    #[test]
+    fn matrix3_columns_are_padded() {
+        let mut m = Matrix::<3>::identity();
+        m[0][2] = 5.0;
+        m[1][2] = 6.0;
+        assert_eq!(size_of::<GpuMat3>(), 48);
+        for layout in [Layout::Std140, Layout::Std430] {
+            let mut writer = LayoutWriter::new(layout);
+            writer.write(&2.0f32).write(&m).write(&7.0f32);
+            let bytes = writer.finish();
+            let words = words(&bytes);
+            // the matrix starts at 16, its columns at 16, 32 and 48, the scalar follows at 64
+            assert_eq!(words[0..4], [2.0, 0.0, 0.0, 0.0]);
+            assert_eq!(words[4..8], [1.0, 0.0, 0.0, 0.0]);
+            assert_eq!(words[8..12], [0.0, 1.0, 0.0, 0.0]);
+            assert_eq!(words[12..16], [5.0, 6.0, 1.0, 0.0]);
+            assert_eq!(words[16], 7.0);
+            assert_eq!(bytes.len(), 80);
+        }
+    }
+
+    #[test]
+    fn matrices_are_column_major() {
+        let mut m = Matrix::<4>::identity();
+        m[0][3] = 10.0;
+        m[1][3] = 20.0;
+        assert_eq!(m.to_gpu()[3], [10.0, 20.0, 0.0, 1.0]);
+        let mut m = Matrix::<2>::identity();
+        m[0][1] = 3.0;
+        assert_eq!(m.to_gpu(), [[1.0, 0.0], [3.0, 1.0]]);
+    }
+
+    #[test]
+    fn scalar_array_stride() {
+        let values = [1.0f32, 2.0, 3.0];
+
+        let mut std140 = LayoutWriter::new(Layout::Std140);
+        std140.write(&0.5f32).write_array(&values).write(&9.0f32);
+        let bytes = std140.finish();
+        // array aligned to 16 with a 16 byte stride, the struct rounds up to 16
+        assert_eq!(bytes.len(), 16 + 3 * 16 + 16);
+        let words = words(&bytes);
+        assert_eq!([words[4], words[8], words[12]], values);
+        assert_eq!(words[5..8], [0.0; 3]);
+        assert_eq!(words[16], 9.0);
+
+        let mut std430 = LayoutWriter::new(Layout::Std430);
+        std430.write(&0.5f32).write_array(&values).write(&9.0f32);
+        assert_eq!(
+            std430.finish(),
+            bytemuck::bytes_of(&[0.5f32, 1.0, 2.0, 3.0, 9.0])
+        );
+    }
+
+    #[test]
+    fn vec3_array_stride() {
+        let values = [v3(1.0, 2.0, 3.0), v3(4.0, 5.0, 6.0)];
+        for layout in [Layout::Std140, Layout::Std430] {
+            let mut writer = LayoutWriter::new(layout);
+            writer.write_array(&values);
+            assert_eq!(
+                words(&writer.finish()),
+                [1.0, 2.0, 3.0, 0.0, 4.0, 5.0, 6.0, 0.0]
+            );
+        }
+    }
+
+    #[test]
+    fn struct_end_padding() {
+        let mut std140 = LayoutWriter::new(Layout::Std140);
+        std140.write(&1.0f32);
+        assert_eq!(std140.finish().len(), 16);
+
+        let mut std430 = LayoutWriter::new(Layout::Std430);
+        std430.write(&1.0f32);
+        assert_eq!(std430.finish().len(), 4);
+
+        // a vec2 makes the struct 8 aligned in std430
+        let mut std430 = LayoutWriter::new(Layout::Std430);
+        std430.write(&Vector::<2>::new(1.0, 2.0)).write(&3.0f32);
+        assert_eq!(std430.finish().len(), 16);
+
+        // a vec3 makes it 16 aligned in both
+        let mut std430 = LayoutWriter::new(Layout::Std430);
+        std430
+            .write(&v3(1.0, 2.0, 3.0))
+            .write(&4.0f32)
+            .write(&5.0f32);
+        assert_eq!(std430.finish().len(), 32);
+    }
+
+    #[test]
+    fn finish_resets_the_writer() {
+        let mut writer = LayoutWriter::new(Layout::Std430);
+        writer.write(&1u32).write(&-2i32);
+        assert_eq!(writer.finish(), bytemuck::bytes_of(&[1u32, (-2i32) as u32]));
+        assert_eq!(writer.offset(), 0);
+    }
+}
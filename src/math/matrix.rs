@@ -1,33 +1,94 @@
-use std::ops::{Index, IndexMut, Mul};
+use std::{
+    error::Error,
+    fmt::{self, Display},
+    ops::{Index, IndexMut, Mul},
+};
 
-use super::common::{Determinant, FuzzyEq};
+use super::{
+    angle::Rad,
+    common::{ApproxEq, Determinant, Dimension2, Dimension3, FuzzyEq, Scalar, ToBits, Tolerance},
+    geometry::Rect,
+    quaternion::Quaternion,
+    simd,
+    vector::Vector,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MatrixError {
+    NotInvertible,
+}
+
+impl Display for MatrixError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MatrixError::NotInvertible => write!(f, "matrix is not invertible"),
+        }
+    }
+}
+
+impl Error for MatrixError {}
+
+/// packed LU decomposition with partial pivoting, P * A = L * U
+/// L has an implicit unit diagonal and is stored below the diagonal of `lu`
+struct Lu<const D: usize, T: Scalar> {
+    lu: Matrix<D, T>,
+    permutation: [usize; D],
+    sign: T,
+    singular: bool,
+}
+
+/// parts of an affine Matrix<4>, recomposed as T * R * S * H
+///
+/// A negative determinant (mirroring) is always put on the x scale so the rotation stays
+/// proper, e.g. a scale of (1, -1, 1) comes back as (-1, 1, 1) with a 180 degree turn around z.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Decomposition<T: Scalar = f32> {
+    pub translation: Vector<3, T>,
+    pub rotation: Quaternion<T>,
+    /// same rotation as `rotation`, angles as taken by `Quaternion::from_euler`
+    pub euler: (Rad<T>, Rad<T>, Rad<T>),
+    pub scale: Vector<3, T>,
+    /// unit upper-triangular shear H as (xy, xz, yz), zero for plain TRS matrices
+    pub shear: Vector<3, T>,
+}
+
+impl<T: Scalar> Decomposition<T> {
+    pub fn to_matrix(self) -> Matrix<4, T> {
+        let mut shear = Matrix::<4, T>::identity();
+        shear[0][1] = self.shear[0];
+        shear[0][2] = self.shear[1];
+        shear[1][2] = self.shear[2];
+        Matrix::<4, T>::from_trs(self.translation, self.rotation, self.scale) * shear
+    }
+}
 
 #[derive(Debug, Clone, Copy)]
-pub struct Matrix<const D: usize> {
-    data: [[f32; D]; D],
+pub struct Matrix<const D: usize, T: Scalar = f32> {
+    data: [[T; D]; D],
 }
 
-impl<const D: usize> Index<usize> for Matrix<D> {
-    type Output = [f32; D];
+impl<const D: usize, T: Scalar> Index<usize> for Matrix<D, T> {
+    type Output = [T; D];
     fn index(&self, index: usize) -> &Self::Output {
         &self.data[index]
     }
 }
 
-impl<const D: usize> IndexMut<usize> for Matrix<D> {
+impl<const D: usize, T: Scalar> IndexMut<usize> for Matrix<D, T> {
     fn index_mut(&mut self, index: usize) -> &mut Self::Output {
         &mut self.data[index]
     }
 }
 
-impl<const D: usize> Matrix<D> {
+impl<const D: usize, T: Scalar> Matrix<D, T> {
     pub fn new() -> Self {
         Self {
-            data: [[0.0; D]; D],
+            data: [[T::ZERO; D]; D],
         }
     }
 
-    pub fn diagonal(value: f32) -> Self {
+    pub fn diagonal(value: T) -> Self {
         let mut matrix = Self::new();
         for i in 0..D {
             matrix[i][i] = value;
@@ -36,7 +97,7 @@ impl<const D: usize> Matrix<D> {
     }
 
     pub fn identity() -> Self {
-        Self::diagonal(1.0)
+        Self::diagonal(T::ONE)
     }
 
     pub fn transpose(&self) -> Self {
@@ -48,19 +109,42 @@ impl<const D: usize> Matrix<D> {
         }
         matrix
     }
+
+    /// converts to another scalar type, rounding when narrowing
+    pub fn cast<U: Scalar>(self) -> Matrix<D, U> {
+        Matrix {
+            data: self
+                .data
+                .map(|row| row.map(|value| U::from_f64(value.to_f64()))),
+        }
+    }
 }
 
-impl<const D: usize> Default for Matrix<D> {
+impl<const D: usize> Matrix<D, f64> {
+    /// lossy conversion for GPU upload
+    pub fn to_f32(self) -> Matrix<D, f32> {
+        self.cast()
+    }
+}
+
+/// lossless widening
+impl<const D: usize> From<Matrix<D, f32>> for Matrix<D, f64> {
+    fn from(matrix: Matrix<D, f32>) -> Self {
+        matrix.cast()
+    }
+}
+
+impl<const D: usize, T: Scalar> Default for Matrix<D, T> {
     fn default() -> Self {
         Self::new()
     }
 }
 
-impl<const D: usize> FuzzyEq for Matrix<D> {
+impl<const D: usize, T: Scalar> FuzzyEq for Matrix<D, T> {
     fn fuzzy_eq(&self, other: &Self) -> bool {
         for row in 0..D {
             for col in 0..D {
-                if self[row][col].fuzzy_eq(&other[row][col]) == false {
+                if !self[row][col].fuzzy_eq(&other[row][col]) {
                     return false;
                 }
             }
@@ -69,20 +153,38 @@ impl<const D: usize> FuzzyEq for Matrix<D> {
     }
 }
 
-impl<const D: usize> PartialEq for Matrix<D> {
+impl<const D: usize, T: Scalar> ApproxEq<T> for Matrix<D, T> {
+    fn approx_eq_with(&self, other: &Self, tolerance: Tolerance<T>) -> bool {
+        (0..D)
+            .all(|row| (0..D).all(|col| self[row][col].approx_eq_with(&other[row][col], tolerance)))
+    }
+}
+
+impl<const D: usize, T: Scalar> ToBits for Matrix<D, T> {
+    type Bits = [[T::Bits; D]; D];
+    fn to_bits(&self) -> Self::Bits {
+        self.data.map(|row| row.map(|value| value.to_bits()))
+    }
+}
+
+/// fuzzy, see `FuzzyEq`, wrap in `Exact` for bitwise equality
+impl<const D: usize, T: Scalar> PartialEq for Matrix<D, T> {
     fn eq(&self, other: &Self) -> bool {
         self.fuzzy_eq(other)
     }
 }
 
-impl<const D: usize> Mul for Matrix<D> {
+impl<const D: usize, T: Scalar> Mul for Matrix<D, T> {
     type Output = Self;
     fn mul(self, rhs: Self) -> Self::Output {
+        if let Some(res) = simd::mul(&self, &rhs) {
+            return res;
+        }
         let mut res = Self::new();
         for row in 0..D {
             for col in 0..D {
                 for inter in 0..D {
-                    res[row][col] = res[row][col] + self[row][inter] * rhs[inter][col];
+                    res[row][col] += self[row][inter] * rhs[inter][col];
                 }
             }
         }
@@ -90,171 +192,1138 @@ impl<const D: usize> Mul for Matrix<D> {
     }
 }
 
-impl Determinant for Matrix<2> {
-    fn det(&self) -> f32 {
-        self[0][0] * self[1][1] - self[0][1] * self[1][0]
+impl<const D: usize, T: Scalar> Matrix<D, T> {
+    fn lu(&self) -> Lu<D, T> {
+        let mut lu = *self;
+        let mut permutation = [0; D];
+        for (i, p) in permutation.iter_mut().enumerate() {
+            *p = i;
+        }
+        let mut sign = T::ONE;
+        let mut singular = false;
+        // pivots are compared relative to the largest entry of their row outside the last
+        // column, so scaling rows keeps the rank however small the scale, and the
+        // translation in the last column of a homogeneous matrix does not hide it either
+        let mut scales = self.data.map(|row| {
+            row[..D.saturating_sub(1)]
+                .iter()
+                .fold(T::ZERO, |largest, value| largest.max(value.abs()))
+        });
+
+        for k in 0..D {
+            // pick the largest remaining value in column k as pivot
+            let mut pivot = k;
+            for row in (k + 1)..D {
+                if lu[row][k].abs() > lu[pivot][k].abs() {
+                    pivot = row;
+                }
+            }
+            if pivot != k {
+                lu.data.swap(pivot, k);
+                scales.swap(pivot, k);
+                permutation.swap(pivot, k);
+                sign = -sign;
+            }
+            if lu[k][k].abs() <= scales[k] * T::EPSILON {
+                singular = true;
+                continue;
+            }
+            for row in (k + 1)..D {
+                let factor = lu[row][k] / lu[k][k];
+                lu[row][k] = factor;
+                for col in (k + 1)..D {
+                    let eliminated = factor * lu[k][col];
+                    lu[row][col] -= eliminated;
+                }
+            }
+        }
+
+        Lu {
+            lu,
+            permutation,
+            sign,
+            singular,
+        }
+    }
+
+    pub fn is_invertible(&self) -> bool {
+        !self.lu().singular
+    }
+
+    pub fn inverse(&self) -> Result<Self, MatrixError> {
+        if let Some(inverse) = simd::inverse(self) {
+            return inverse;
+        }
+        let Lu {
+            lu,
+            permutation,
+            singular,
+            ..
+        } = self.lu();
+        if singular {
+            return Err(MatrixError::NotInvertible);
+        }
+
+        // solve L * U * x = P * e_col for every column of the identity
+        let mut inverse = Self::new();
+        for col in 0..D {
+            let mut x = [T::ZERO; D];
+            for row in 0..D {
+                let mut sum = if permutation[row] == col {
+                    T::ONE
+                } else {
+                    T::ZERO
+                };
+                for k in 0..row {
+                    sum -= lu[row][k] * x[k];
+                }
+                x[row] = sum;
+            }
+            for row in (0..D).rev() {
+                let mut sum = x[row];
+                for k in (row + 1)..D {
+                    sum -= lu[row][k] * x[k];
+                }
+                x[row] = sum / lu[row][row];
+            }
+            for row in 0..D {
+                inverse[row][col] = x[row];
+            }
+        }
+        Ok(inverse)
+    }
+}
+
+impl<const D: usize, T: Scalar> Determinant for Matrix<D, T> {
+    type Value = T;
+    fn det(&self) -> T {
+        let Lu { lu, sign, .. } = self.lu();
+        let mut det = sign;
+        for i in 0..D {
+            det *= lu[i][i];
+        }
+        det
     }
 }
 
-impl Matrix<3> {
-    pub fn translate(tx: f32, ty: f32) -> Self {
+/// column vector product, M * v
+impl<const D: usize, T: Scalar> Mul<Vector<D, T>> for Matrix<D, T> {
+    type Output = Vector<D, T>;
+    fn mul(self, rhs: Vector<D, T>) -> Self::Output {
+        let mut res = Vector::zero();
+        for row in 0..D {
+            for col in 0..D {
+                res[row] += self[row][col] * rhs[col];
+            }
+        }
+        res
+    }
+}
+
+/// row vector product, v * M
+impl<const D: usize, T: Scalar> Mul<Matrix<D, T>> for Vector<D, T> {
+    type Output = Vector<D, T>;
+    fn mul(self, rhs: Matrix<D, T>) -> Self::Output {
+        let mut res = Vector::zero();
+        for col in 0..D {
+            for row in 0..D {
+                res[col] += self[row] * rhs[row][col];
+            }
+        }
+        res
+    }
+}
+
+impl<T: Scalar> Matrix<3, T> {
+    pub fn translate(tx: T, ty: T) -> Self {
         let mut trans = Self::identity();
         trans.data[0][2] = tx;
         trans.data[1][2] = ty;
         trans
     }
 
-    pub fn scale(sx: f32, sy: f32) -> Self {
+    pub fn scale(sx: T, sy: T) -> Self {
         let mut scale = Self::identity();
         scale[0][0] = sx;
         scale[1][1] = sy;
         scale
     }
 
-    pub fn rotate(deg: f32) -> Self {
+    /// transforms a 2D point (w = 1), dividing by w when the matrix is projective,
+    /// points sent to infinity (w = 0) are returned undivided
+    pub fn transform_point(&self, point: Vector<2, T>) -> Vector<2, T> {
+        let res = *self * Vector::from([point[0], point[1], T::ONE]);
+        if res[2].fuzzy_eq(&T::ONE) || res[2].fuzzy_eq(&T::ZERO) {
+            Vector::<2, T>::new(res[0], res[1])
+        } else {
+            Vector::<2, T>::new(res[0] / res[2], res[1] / res[2])
+        }
+    }
+
+    /// transforms a 2D direction (w = 0), translation has no effect
+    pub fn transform_vector(&self, vector: Vector<2, T>) -> Vector<2, T> {
+        let res = *self * Vector::from([vector[0], vector[1], T::ZERO]);
+        Vector::<2, T>::new(res[0], res[1])
+    }
+}
+
+impl<T: Scalar> Matrix<3, T> {
+    /// counterclockwise with y up, which shows as clockwise in the y-down pixel space of the shader
+    pub fn rotate(angle: impl Into<Rad<T>>) -> Self {
         let mut rotation = Self::identity();
-        let (cos, sin) = (deg.cos(), deg.sin());
+        let (sin, cos) = angle.into().sin_cos();
         rotation[0][0] = cos;
         rotation[1][1] = cos;
         rotation[0][1] = -sin;
         rotation[1][0] = sin;
         rotation
     }
+
+    /// x' = x + x_by_y * y, y' = y + y_by_x * x
+    pub fn shear(x_by_y: T, y_by_x: T) -> Self {
+        let mut shear = Self::identity();
+        shear[0][1] = x_by_y;
+        shear[1][0] = y_by_x;
+        shear
+    }
+
+    /// mirrors across the x axis, y' = -y
+    pub fn reflect_x() -> Self {
+        Self::scale(T::ONE, -T::ONE)
+    }
+
+    /// mirrors across the y axis, x' = -x
+    pub fn reflect_y() -> Self {
+        Self::scale(-T::ONE, T::ONE)
+    }
+
+    /// mirrors across the line through the origin along `direction`
+    pub fn reflect(direction: Vector<2, T>) -> Self {
+        let d = direction.normalize();
+        let mut reflection = Self::identity();
+        reflection[0][0] = T::TWO * d[0] * d[0] - T::ONE;
+        reflection[0][1] = T::TWO * d[0] * d[1];
+        reflection[1][0] = T::TWO * d[0] * d[1];
+        reflection[1][1] = T::TWO * d[1] * d[1] - T::ONE;
+        reflection
+    }
+
+    /// mirrors across the line through `point` along `direction`
+    pub fn reflect_line(point: Vector<2, T>, direction: Vector<2, T>) -> Self {
+        Self::about(point, Self::reflect(direction))
+    }
+
+    pub fn rotate_about(angle: impl Into<Rad<T>>, pivot: Vector<2, T>) -> Self {
+        Self::about(pivot, Self::rotate(angle))
+    }
+
+    pub fn scale_about(sx: T, sy: T, pivot: Vector<2, T>) -> Self {
+        Self::about(pivot, Self::scale(sx, sy))
+    }
+
+    /// stretches `from` onto `to`, corner to corner
+    pub fn rect_to_rect(from: Rect<T>, to: Rect<T>) -> Self {
+        Self::translate(to.x, to.y)
+            * Self::scale(to.width / from.width, to.height / from.height)
+            * Self::translate(-from.x, -from.y)
+    }
+
+    /// fits `from` inside `to` keeping its aspect ratio, centered with bars on the short sides
+    pub fn letterbox(from: Rect<T>, to: Rect<T>) -> Self {
+        let scale = (to.width / from.width).min(to.height / from.height);
+        let (width, height) = (from.width * scale, from.height * scale);
+        let fitted = Rect::new(
+            to.x + (to.width - width) * T::HALF,
+            to.y + (to.height - height) * T::HALF,
+            width,
+            height,
+        );
+        Self::rect_to_rect(from, fitted)
+    }
+
+    /// inverse of a matrix whose last row is (0, 0, 1), cheaper than the general `inverse`
+    ///
+    /// The determinant of the 2x2 part is compared with the product of its row lengths, so
+    /// however small a scale it stays invertible.
+    pub fn affine_inverse(&self) -> Result<Self, MatrixError> {
+        let det = self[0][0] * self[1][1] - self[0][1] * self[1][0];
+        let row = |row: usize| Vector::<2, T>::new(self[row][0], self[row][1]).length();
+        if det.abs() <= T::EPSILON * row(0) * row(1) {
+            return Err(MatrixError::NotInvertible);
+        }
+        let mut inverse = Self::identity();
+        inverse[0][0] = self[1][1] / det;
+        inverse[0][1] = -self[0][1] / det;
+        inverse[1][0] = -self[1][0] / det;
+        inverse[1][1] = self[0][0] / det;
+        inverse[0][2] = -(inverse[0][0] * self[0][2] + inverse[0][1] * self[1][2]);
+        inverse[1][2] = -(inverse[1][0] * self[0][2] + inverse[1][1] * self[1][2]);
+        Ok(inverse)
+    }
+
+    fn about(pivot: Vector<2, T>, transform: Self) -> Self {
+        Self::translate(pivot[0], pivot[1]) * transform * Self::translate(-pivot[0], -pivot[1])
+    }
 }
 
-impl Determinant for Matrix<3> {
-    fn det(&self) -> f32 {
-        self[0][0] * self[1][1] * self[2][2]
-            + self[0][1] * self[1][2] * self[2][1]
-            + self[0][2] * self[1][0] * self[2][1]
-            - self[0][2] * self[1][1] * self[2][1]
-            - self[0][1] * self[1][0] * self[2][2]
-            - self[0][0] * self[1][2] * self[2][1]
+impl<T: Scalar> Matrix<4, T> {
+    /// transforms a 3D point (w = 1), applying the perspective divide when w != 1,
+    /// points sent to infinity (w = 0) are returned undivided
+    pub fn transform_point(&self, point: Vector<3, T>) -> Vector<3, T> {
+        let res = *self * Vector::from([point[0], point[1], point[2], T::ONE]);
+        if res[3].fuzzy_eq(&T::ONE) || res[3].fuzzy_eq(&T::ZERO) {
+            Vector::<3, T>::new(res[0], res[1], res[2])
+        } else {
+            Vector::<3, T>::new(res[0] / res[3], res[1] / res[3], res[2] / res[3])
+        }
+    }
+
+    /// transforms a 3D direction (w = 0), translation has no effect
+    pub fn transform_vector(&self, vector: Vector<3, T>) -> Vector<3, T> {
+        let res = *self * Vector::from([vector[0], vector[1], vector[2], T::ZERO]);
+        Vector::<3, T>::new(res[0], res[1], res[2])
+    }
+
+    pub fn translate(x: T, y: T, z: T) -> Self {
+        let mut trans = Self::identity();
+        trans[0][3] = x;
+        trans[1][3] = y;
+        trans[2][3] = z;
+        trans
+    }
+
+    pub fn scale(x: T, y: T, z: T) -> Self {
+        let mut scale = Self::identity();
+        scale[0][0] = x;
+        scale[1][1] = y;
+        scale[2][2] = z;
+        scale
     }
 }
 
-impl Matrix<4> {
-    fn sub(&self, row: usize, column: usize) -> Matrix<3> {
-        let mut matrix: Matrix<3> = Matrix::new();
-        let mut source_row: usize = 0;
-        let mut source_column: usize = 0;
-        let mut target_row: usize = 0;
-        let mut target_column: usize = 0;
-
-        while target_row < 3 {
-            if source_row == row {
-                // Skip row to be removed
-                source_row += 1;
-            }
-            while target_column < 3 {
-                if source_column == column {
-                    // Skip column to be removed
-                    source_column += 1;
-                }
-                matrix[target_row][target_column] = self[source_row][source_column];
+impl<T: Scalar> Matrix<4, T> {
+    /// T * R * S
+    pub fn from_trs(
+        translation: Vector<3, T>,
+        rotation: Quaternion<T>,
+        scale: Vector<3, T>,
+    ) -> Self {
+        Self::translate(translation[0], translation[1], translation[2])
+            * Self::from(rotation)
+            * Self::scale(scale[0], scale[1], scale[2])
+    }
+
+    /// splits the upper 3x3 into rotation, scale and shear with Gram-Schmidt,
+    /// the projective bottom row is ignored
+    ///
+    /// An axis counts as zero, and the matrix as not invertible, when its length is within
+    /// `Scalar::EPSILON` of the longest column, so tiny but uniform scales still decompose.
+    pub fn decompose(&self) -> Result<Decomposition<T>, MatrixError> {
+        let column = |col: usize| Vector::<3, T>::new(self[0][col], self[1][col], self[2][col]);
+        let (c0, c1, c2) = (column(0), column(1), column(2));
+        let tolerance = c0.length().max(c1.length()).max(c2.length()) * T::EPSILON;
+
+        let mut scale_x = c0.length();
+        if scale_x <= tolerance {
+            return Err(MatrixError::NotInvertible);
+        }
+        let mut x = c0 / scale_x;
+
+        let mut shear_xy = x.dot(&c1);
+        let y = c1 - x * shear_xy;
+        let scale_y = y.length();
+        if scale_y <= tolerance {
+            return Err(MatrixError::NotInvertible);
+        }
+        let y = y / scale_y;
+
+        let mut shear_xz = x.dot(&c2);
+        let shear_yz = y.dot(&c2);
+        let z = c2 - x * shear_xz - y * shear_yz;
+        let scale_z = z.length();
+        if scale_z <= tolerance {
+            return Err(MatrixError::NotInvertible);
+        }
+        let z = z / scale_z;
+
+        if x.cross(&y).dot(&z) < T::ZERO {
+            x = -x;
+            scale_x = -scale_x;
+            shear_xy = -shear_xy;
+            shear_xz = -shear_xz;
+        }
 
-                source_column += 1;
-                target_column += 1;
+        let mut rotation_matrix = Matrix::<3, T>::identity();
+        for (col, axis) in [x, y, z].iter().enumerate() {
+            for row in 0..3 {
+                rotation_matrix[row][col] = axis[row];
             }
-            source_row += 1;
-            source_column = 0;
-            target_row += 1;
-            target_column = 0;
         }
+        let rotation = Quaternion::from(rotation_matrix);
 
-        matrix
+        Ok(Decomposition {
+            translation: column(3),
+            rotation,
+            euler: rotation.to_euler(),
+            scale: Vector::<3, T>::new(scale_x, scale_y, scale_z),
+            shear: Vector::<3, T>::new(shear_xy / scale_x, shear_xz / scale_x, shear_yz / scale_y),
+        })
     }
 
-    fn co_factor(&self, row: usize, col: usize) -> f32 {
-        if (row + col) % 2 == 0 {
-            self.sub(row, col).det()
-        } else {
-            -self.sub(row, col).det()
+    pub fn rotate_x(r: impl Into<Rad<T>>) -> Self {
+        let (sin, cos) = r.into().sin_cos();
+        let (zero, one) = (T::ZERO, T::ONE);
+        Self {
+            data: [
+                [one, zero, zero, zero],
+                [zero, cos, -sin, zero],
+                [zero, sin, cos, zero],
+                [zero, zero, zero, one],
+            ],
         }
     }
 
-    pub fn is_invertible(&self) -> bool {
-        !self.det().fuzzy_eq(&0.0)
+    pub fn rotate_y(r: impl Into<Rad<T>>) -> Self {
+        let (sin, cos) = r.into().sin_cos();
+        let (zero, one) = (T::ZERO, T::ONE);
+        Self {
+            data: [
+                [cos, zero, sin, zero],
+                [zero, one, zero, zero],
+                [-sin, zero, cos, zero],
+                [zero, zero, zero, one],
+            ],
+        }
     }
 
-    pub fn inverse(&self) -> Result<Matrix<4>, String> {
-        if self.is_invertible() {
-            let det = self.det();
-            let mut inverse: Matrix<4> = Matrix::new();
-            for row in 0..4 {
-                for col in 0..4 {
-                    let cofactor = self.co_factor(row, col);
-                    inverse[col][row] = cofactor / det;
-                }
-            }
-            Ok(inverse)
-        } else {
-            Err(String::from("not invertible"))
+    pub fn rotate_z(r: impl Into<Rad<T>>) -> Self {
+        let (sin, cos) = r.into().sin_cos();
+        let (zero, one) = (T::ZERO, T::ONE);
+        Self {
+            data: [
+                [cos, -sin, zero, zero],
+                [sin, cos, zero, zero],
+                [zero, zero, one, zero],
+                [zero, zero, zero, one],
+            ],
         }
     }
 
-    pub fn translate(x: f32, y: f32, z: f32) -> Matrix<4> {
-        Matrix::<4> {
+    // Projections below target wgpu clip space: right-handed view space looking down -z,
+    // x and y in -1..1, depth in 0..1 (or 1..0 for the reversed-z variants).
+
+    /// `fov_y` is the vertical field of view, near maps to depth 0 and far to 1
+    pub fn perspective(fov_y: impl Into<Rad<T>>, aspect: T, near: T, far: T) -> Self {
+        let f = T::ONE / (fov_y.into() * T::HALF).tan();
+        let zero = T::ZERO;
+        Self {
+            data: [
+                [f / aspect, zero, zero, zero],
+                [zero, f, zero, zero],
+                [zero, zero, far / (near - far), near * far / (near - far)],
+                [zero, zero, -T::ONE, zero],
+            ],
+        }
+    }
+
+    /// perspective with the far plane at infinity
+    pub fn perspective_infinite(fov_y: impl Into<Rad<T>>, aspect: T, near: T) -> Self {
+        let f = T::ONE / (fov_y.into() * T::HALF).tan();
+        let zero = T::ZERO;
+        Self {
             data: [
-                [1.0, 0.0, 0.0, x],
-                [0.0, 1.0, 0.0, y],
-                [0.0, 0.0, 1.0, z],
-                [0.0, 0.0, 0.0, 1.0],
+                [f / aspect, zero, zero, zero],
+                [zero, f, zero, zero],
+                [zero, zero, -T::ONE, -near],
+                [zero, zero, -T::ONE, zero],
             ],
         }
     }
 
-    pub fn scale(x: f32, y: f32, z: f32) -> Matrix<4> {
-        Matrix::<4> {
+    /// near maps to depth 1 and far to 0, pair with `CompareFunction::Greater` and a depth clear of 0
+    pub fn perspective_reversed_z(fov_y: impl Into<Rad<T>>, aspect: T, near: T, far: T) -> Self {
+        let f = T::ONE / (fov_y.into() * T::HALF).tan();
+        let zero = T::ZERO;
+        Self {
             data: [
-                [x, 0.0, 0.0, 0.0],
-                [0.0, y, 0.0, 0.0],
-                [0.0, 0.0, z, 0.0],
-                [0.0, 0.0, 0.0, 1.0],
+                [f / aspect, zero, zero, zero],
+                [zero, f, zero, zero],
+                [zero, zero, near / (far - near), near * far / (far - near)],
+                [zero, zero, -T::ONE, zero],
             ],
         }
     }
 
-    pub fn rotate_x(r: f32) -> Matrix<4> {
-        Matrix::<4> {
+    /// reversed-z perspective with the far plane at infinity, depth goes from 1 at near to 0
+    pub fn perspective_infinite_reversed_z(fov_y: impl Into<Rad<T>>, aspect: T, near: T) -> Self {
+        let f = T::ONE / (fov_y.into() * T::HALF).tan();
+        let zero = T::ZERO;
+        Self {
             data: [
-                [1.0, 0.0, 0.0, 0.0],
-                [0.0, r.cos(), -r.sin(), 0.0],
-                [0.0, r.sin(), r.cos(), 0.0],
-                [0.0, 0.0, 0.0, 1.0],
+                [f / aspect, zero, zero, zero],
+                [zero, f, zero, zero],
+                [zero, zero, zero, near],
+                [zero, zero, -T::ONE, zero],
             ],
         }
     }
 
-    pub fn rotate_y(r: f32) -> Matrix<4> {
-        Matrix::<4> {
+    /// maps the box left..right, bottom..top, -near..-far onto clip space
+    pub fn orthographic(left: T, right: T, bottom: T, top: T, near: T, far: T) -> Self {
+        let zero = T::ZERO;
+        Self {
             data: [
-                [r.cos(), 0.0, r.sin(), 0.0],
-                [0.0, 1.0, 0.0, 0.0],
-                [-r.sin(), 0.0, r.cos(), 0.0],
-                [0.0, 0.0, 0.0, 1.0],
+                [
+                    T::TWO / (right - left),
+                    zero,
+                    zero,
+                    -(right + left) / (right - left),
+                ],
+                [
+                    zero,
+                    T::TWO / (top - bottom),
+                    zero,
+                    -(top + bottom) / (top - bottom),
+                ],
+                [zero, zero, T::ONE / (near - far), near / (near - far)],
+                [zero, zero, zero, T::ONE],
             ],
         }
     }
 
-    pub fn rotate_z(r: f32) -> Matrix<4> {
-        Matrix::<4> {
+    /// orthographic with near at depth 1 and far at 0
+    pub fn orthographic_reversed_z(left: T, right: T, bottom: T, top: T, near: T, far: T) -> Self {
+        let zero = T::ZERO;
+        Self {
             data: [
-                [r.cos(), -r.sin(), 0.0, 0.0],
-                [r.sin(), r.cos(), 0.0, 0.0],
-                [0.0, 0.0, 1.0, 0.0],
-                [0.0, 0.0, 0.0, 1.0],
+                [
+                    T::TWO / (right - left),
+                    zero,
+                    zero,
+                    -(right + left) / (right - left),
+                ],
+                [
+                    zero,
+                    T::TWO / (top - bottom),
+                    zero,
+                    -(top + bottom) / (top - bottom),
+                ],
+                [zero, zero, T::ONE / (far - near), far / (far - near)],
+                [zero, zero, zero, T::ONE],
+            ],
+        }
+    }
+
+    /// right-handed view matrix, the camera at `eye` looks towards `target` along -z
+    ///
+    /// When `up` is zero or parallel to the view direction the world axis least aligned with
+    /// the view is used instead, -z for a camera looking straight along y, so the result is
+    /// always a rotation. A camera with `eye == target` looks down -z.
+    pub fn look_at(eye: Vector<3, T>, target: Vector<3, T>, up: Vector<3, T>) -> Self {
+        let (zero, one) = (T::ZERO, T::ONE);
+        let mut forward = (target - eye).normalize();
+        if forward.length_squared() == zero {
+            forward = Vector::<3, T>::new(zero, zero, -one);
+        }
+        let mut side = forward.cross(&up);
+        if side.length() <= T::EPSILON * up.length() {
+            let along = forward.to_array().map(|value| value.abs());
+            let fallback = if along[2] <= along[0] && along[2] <= along[1] {
+                Vector::<3, T>::new(zero, zero, -one)
+            } else if along[0] <= along[1] {
+                Vector::<3, T>::new(one, zero, zero)
+            } else {
+                Vector::<3, T>::new(zero, one, zero)
+            };
+            side = forward.cross(&fallback);
+        }
+        let side = side.normalize();
+        let up = side.cross(&forward);
+        Self {
+            data: [
+                [side[0], side[1], side[2], -side.dot(&eye)],
+                [up[0], up[1], up[2], -up.dot(&eye)],
+                [-forward[0], -forward[1], -forward[2], forward.dot(&eye)],
+                [zero, zero, zero, T::ONE],
             ],
         }
     }
 }
 
-impl Determinant for Matrix<4> {
-    fn det(&self) -> f32 {
-        let mut det: f32 = 0.0;
-        for col in 0..4 {
-            det = det + self.co_factor(0, col) * self[0][col]
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::{angle::Deg, common::Exact};
+
+    const TOLERANCE: Tolerance<f64> = Tolerance::Absolute(1e-9);
+
+    fn matrix<const D: usize>(data: [[f64; D]; D]) -> Matrix<D, f64> {
+        Matrix { data }
+    }
+
+    fn assert_inverse<const D: usize>(m: Matrix<D, f64>, det: f64, inverse: Matrix<D, f64>) {
+        assert!(
+            m.det().approx_eq_with(&det, TOLERANCE),
+            "{} != {det}",
+            m.det()
+        );
+        let actual = m.inverse().unwrap();
+        assert!(actual.approx_eq_with(&inverse, TOLERANCE), "{actual:?}");
+        assert!((m * actual).approx_eq_with(&Matrix::identity(), TOLERANCE));
+    }
+
+    #[test]
+    fn inverse_2x2() {
+        let m = matrix([[4.0, 7.0], [2.0, 6.0]]);
+        assert_inverse(m, 10.0, matrix([[0.6, -0.7], [-0.2, 0.4]]));
+    }
+
+    #[test]
+    fn inverse_3x3() {
+        let m = matrix([[1.0, 2.0, 3.0], [0.0, 1.0, 4.0], [5.0, 6.0, 0.0]]);
+        let expected = matrix([[-24.0, 18.0, 5.0], [20.0, -15.0, -4.0], [-5.0, 4.0, 1.0]]);
+        assert_inverse(m, 1.0, expected);
+    }
+
+    #[test]
+    fn inverse_4x4() {
+        let m = matrix([
+            [2.0, 0.0, 0.0, 1.0],
+            [0.0, 3.0, 0.0, 0.0],
+            [1.0, 0.0, 1.0, 0.0],
+            [0.0, 1.0, 0.0, 4.0],
+        ]);
+        let expected = matrix([
+            [0.5, 1.0 / 24.0, 0.0, -0.125],
+            [0.0, 1.0 / 3.0, 0.0, 0.0],
+            [-0.5, -1.0 / 24.0, 1.0, 0.125],
+            [0.0, -1.0 / 12.0, 0.0, 0.25],
+        ]);
+        assert_inverse(m, 24.0, expected);
+    }
+
+    #[test]
+    fn det_tracks_row_swaps() {
+        let m = matrix([
+            [1.0, 1.0, 1.0, -1.0],
+            [1.0, 1.0, -1.0, 1.0],
+            [1.0, -1.0, 1.0, 1.0],
+            [-1.0, 1.0, 1.0, 1.0],
+        ]);
+        assert_eq!(m.det(), -16.0);
+        assert_eq!(
+            m.inverse(),
+            Ok(matrix(m.data.map(|row| row.map(|v| v / 4.0))))
+        );
+    }
+
+    #[test]
+    fn singular() {
+        let m = Matrix::<3> {
+            data: [[1.0, 2.0, 3.0], [4.0, 5.0, 6.0], [7.0, 8.0, 9.0]],
+        };
+        assert_eq!(m.inverse(), Err(MatrixError::NotInvertible));
+        assert!(!m.is_invertible());
+        assert!(!Matrix::<4>::new().is_invertible());
+    }
+
+    #[test]
+    fn small_scale_is_invertible() {
+        let m = Matrix::<4>::scale(1e-4, 1e-4, 1e-4);
+        let inverse = m.inverse().unwrap();
+        let expected = Matrix::<4>::scale(1e4, 1e4, 1e4);
+        assert!(inverse.approx_eq_with(&expected, Tolerance::Relative(1e-6)));
+        assert!(Matrix::<4>::diagonal(1e-8).is_invertible());
+    }
+
+    #[test]
+    fn large_translation_is_invertible() {
+        let m = Matrix::<3>::translate(1e6, 0.0);
+        assert!(m.is_invertible());
+        assert_eq!(m.inverse(), Ok(Matrix::<3>::translate(-1e6, 0.0)));
+        let m =
+            Matrix::<4, f64>::translate(1e9, -1e9, 1e9) * Matrix::<4, f64>::scale(1e-3, 2.0, 1.0);
+        let expected =
+            Matrix::<4, f64>::scale(1e3, 0.5, 1.0) * Matrix::<4, f64>::translate(-1e9, 1e9, -1e9);
+        assert!(
+            m.inverse()
+                .unwrap()
+                .approx_eq_with(&expected, Tolerance::Relative(1e-12))
+        );
+        // a translation does not make a flat matrix invertible
+        let flat = Matrix::<3>::translate(1e6, 5.0) * Matrix::<3>::scale(1.0, 0.0);
+        assert!(!flat.is_invertible());
+    }
+
+    fn p2(x: f32, y: f32) -> Vector<2> {
+        Vector::<2>::new(x, y)
+    }
+
+    #[test]
+    fn rotate_and_scale_about_a_pivot() {
+        let pivot = p2(3.0, -2.0);
+        let rotation = Matrix::<3>::rotate_about(Deg(90.0), pivot);
+        assert_eq!(rotation.transform_point(pivot), pivot);
+        assert_eq!(rotation.transform_point(p2(4.0, -2.0)), p2(3.0, -1.0));
+        assert_eq!(rotation.transform_vector(p2(1.0, 0.0)), p2(0.0, 1.0));
+        let scale = Matrix::<3>::scale_about(2.0, 3.0, p2(1.0, 1.0));
+        assert_eq!(scale.transform_point(p2(1.0, 1.0)), p2(1.0, 1.0));
+        assert_eq!(scale.transform_point(p2(2.0, 2.0)), p2(3.0, 4.0));
+    }
+
+    #[test]
+    fn shear() {
+        assert_eq!(
+            Matrix::<3>::shear(2.0, 0.0).transform_point(p2(1.0, 1.0)),
+            p2(3.0, 1.0)
+        );
+        assert_eq!(
+            Matrix::<3>::shear(0.0, 0.5).transform_point(p2(2.0, 1.0)),
+            p2(2.0, 2.0)
+        );
+    }
+
+    #[test]
+    fn reflections() {
+        assert_eq!(
+            Matrix::<3>::reflect_x().transform_point(p2(2.0, 3.0)),
+            p2(2.0, -3.0)
+        );
+        assert_eq!(
+            Matrix::<3>::reflect_y().transform_point(p2(2.0, 3.0)),
+            p2(-2.0, 3.0)
+        );
+        let diagonal = Matrix::<3>::reflect(p2(2.0, 2.0));
+        assert_eq!(diagonal.transform_point(p2(1.0, 0.0)), p2(0.0, 1.0));
+        assert_eq!(diagonal.transform_point(p2(1.0, 1.0)), p2(1.0, 1.0));
+        assert_eq!(diagonal * diagonal, Matrix::identity());
+        assert!(diagonal.det().fuzzy_eq(&-1.0));
+        let line = Matrix::<3>::reflect_line(p2(0.0, 2.0), p2(1.0, 0.0));
+        assert_eq!(line.transform_point(p2(5.0, 3.0)), p2(5.0, 1.0));
+    }
+
+    #[test]
+    fn rects() {
+        let from = Rect::new(0.0, 0.0, 10.0, 20.0);
+        let to = Rect::new(100.0, 50.0, 5.0, 40.0);
+        let m = Matrix::<3>::rect_to_rect(from, to);
+        assert_eq!(m.transform_point(p2(0.0, 0.0)), p2(100.0, 50.0));
+        assert_eq!(m.transform_point(p2(10.0, 20.0)), p2(105.0, 90.0));
+        assert_eq!(m.transform_point(p2(5.0, 10.0)), p2(102.5, 70.0));
+
+        let wide = Rect::new(0.0, 0.0, 16.0, 9.0);
+        let square = Rect::new(0.0, 0.0, 100.0, 100.0);
+        let m = Matrix::<3>::letterbox(wide, square);
+        assert_eq!(m.transform_point(p2(0.0, 0.0)), p2(0.0, 21.875));
+        assert_eq!(m.transform_point(p2(16.0, 9.0)), p2(100.0, 78.125));
+    }
+
+    #[test]
+    fn affine_inverse() {
+        let m = Matrix::<3>::rotate_about(Deg(30.0), p2(4.0, -1.0))
+            * Matrix::<3>::scale_about(2.0, 0.5, p2(-3.0, 2.0))
+            * Matrix::<3>::shear(0.25, 0.0);
+        let inverse = m.affine_inverse().unwrap();
+        assert_eq!(inverse, m.inverse().unwrap());
+        assert_eq!(m * inverse, Matrix::identity());
+
+        let small = Matrix::<3>::scale(1e-4, 1e-4).affine_inverse().unwrap();
+        let expected = Matrix::<3>::scale(1e4, 1e4);
+        assert!(small.approx_eq_with(&expected, Tolerance::Relative(1e-6)));
+        let far = Matrix::<3>::translate(1e6, -1e6).affine_inverse();
+        assert_eq!(far, Ok(Matrix::<3>::translate(-1e6, 1e6)));
+
+        let flat = Matrix::<3>::scale(1.0, 0.0);
+        assert_eq!(flat.affine_inverse(), Err(MatrixError::NotInvertible));
+        let collapsed = Matrix::<3>::shear(1.0, 1.0);
+        assert_eq!(collapsed.affine_inverse(), Err(MatrixError::NotInvertible));
+    }
+
+    /// clip space position divided by w
+    fn ndc(m: Matrix<4>, x: f32, y: f32, z: f32) -> Vector<3> {
+        let clip = m * Vector::from([x, y, z, 1.0]);
+        Vector::<3>::new(clip[0] / clip[3], clip[1] / clip[3], clip[2] / clip[3])
+    }
+
+    #[test]
+    fn vector_products() {
+        let m = matrix([[1.0, 2.0, 3.0], [4.0, 5.0, 6.0], [7.0, 8.0, 10.0]]);
+        let v = Vector::<3, f64>::from([1.0, -1.0, 2.0]);
+        assert_eq!(Exact(m * v), Exact(Vector::from([5.0, 11.0, 19.0])));
+        assert_eq!(Exact(v * m), Exact(Vector::from([11.0, 13.0, 17.0])));
+        assert_eq!(Exact(v * m), Exact(m.transpose() * v));
+        assert_eq!(Exact(Matrix::<3, f64>::identity() * v), Exact(v));
+
+        let m = matrix([[0.0, -1.0], [1.0, 0.0]]);
+        assert_eq!(
+            Exact(m * Vector::from([2.0, 3.0])),
+            Exact(Vector::from([-3.0, 2.0]))
+        );
+
+        let m = Matrix::<4>::translate(1.0, 2.0, 3.0) * Matrix::<4>::scale(2.0, 2.0, 2.0);
+        let point = Vector::from([1.0, 1.0, 1.0, 1.0]);
+        assert_eq!(Exact(m * point), Exact(Vector::from([3.0, 4.0, 5.0, 1.0])));
+        let direction = Vector::from([1.0, 1.0, 1.0, 0.0]);
+        assert_eq!(
+            Exact(m * direction),
+            Exact(Vector::from([2.0, 2.0, 2.0, 0.0]))
+        );
+    }
+
+    #[test]
+    fn transform_points_and_vectors_2d() {
+        let m = Matrix::<3>::translate(10.0, -5.0) * Matrix::<3>::scale(2.0, 3.0);
+        let p = Vector::<2>::new(1.0, 1.0);
+        assert_eq!(
+            Exact(m.transform_point(p)),
+            Exact(Vector::<2>::new(12.0, -2.0))
+        );
+        assert_eq!(
+            Exact(m.transform_vector(p)),
+            Exact(Vector::<2>::new(2.0, 3.0))
+        );
+
+        // projective, the bottom row makes w = 1 + x
+        let mut projective = Matrix::<3>::identity();
+        projective[2][0] = 1.0;
+        assert_eq!(
+            projective.transform_point(Vector::<2>::new(1.0, 4.0)),
+            Vector::<2>::new(0.5, 2.0)
+        );
+        assert_eq!(
+            projective.transform_point(Vector::<2>::new(3.0, -8.0)),
+            Vector::<2>::new(0.75, -2.0)
+        );
+        // sent to infinity, w = 0 is left undivided
+        assert_eq!(
+            projective.transform_point(Vector::<2>::new(-1.0, 2.0)),
+            Vector::<2>::new(-1.0, 2.0)
+        );
+        // directions ignore w
+        assert_eq!(
+            projective.transform_vector(Vector::<2>::new(1.0, 4.0)),
+            Vector::<2>::new(1.0, 4.0)
+        );
+    }
+
+    #[test]
+    fn transform_points_and_vectors_3d() {
+        let m = Matrix::<4>::translate(1.0, 2.0, 3.0) * Matrix::<4>::rotate_z(Deg(90.0));
+        let x = Vector::<3>::new(1.0, 0.0, 0.0);
+        assert_eq!(m.transform_point(x), Vector::<3>::new(1.0, 3.0, 3.0));
+        assert_eq!(m.transform_vector(x), Vector::<3>::new(0.0, 1.0, 0.0));
+        assert_eq!(
+            m.transform_point(Vector::zero()),
+            Vector::<3>::new(1.0, 2.0, 3.0)
+        );
+        assert_eq!(m.transform_vector(Vector::zero()), Vector::zero());
+
+        // a perspective divides by w = -z
+        let projection = Matrix::<4>::perspective(Deg(90.0), 1.0, 1.0, 10.0);
+        let near = projection.transform_point(Vector::<3>::new(1.0, -1.0, -1.0));
+        assert_eq!(near, Vector::<3>::new(1.0, -1.0, 0.0));
+        let far = projection.transform_point(Vector::<3>::new(5.0, 2.5, -10.0));
+        assert_eq!(far, Vector::<3>::new(0.5, 0.25, 1.0));
+        let clip = projection * Vector::from([5.0, 2.5, -10.0, 1.0]);
+        assert_eq!(clip[3], 10.0);
+        assert_eq!(
+            far,
+            Vector::<3>::new(clip[0] / clip[3], clip[1] / clip[3], clip[2] / clip[3])
+        );
+
+        // w = 0 on the camera plane, returned undivided
+        let on_camera_plane = projection.transform_point(Vector::<3>::new(1.0, 1.0, 0.0));
+        assert_eq!(on_camera_plane[0], 1.0);
+        assert_eq!(on_camera_plane[1], 1.0);
+
+        // directions ignore translation and w
+        let scaled = Matrix::<4>::translate(9.0, 9.0, 9.0) * Matrix::<4>::scale(2.0, 3.0, 4.0);
+        assert_eq!(
+            scaled.transform_vector(Vector::<3>::new(1.0, 1.0, 1.0)),
+            Vector::<3>::new(2.0, 3.0, 4.0)
+        );
+    }
+
+    #[test]
+    fn f64_arithmetic() {
+        let a = Matrix::<4, f64>::translate(1e8, -2e8, 3.5) * Matrix::<4, f64>::rotate_y(Deg(30.0));
+        let b = Matrix::<4, f64>::scale(0.1, 3.0, -2.0) * Matrix::<4, f64>::rotate_x(Deg(-75.0));
+        let c = Matrix::<4, f64>::rotate_z(Deg(10.0));
+        assert!(((a * b) * c).approx_eq_with(&(a * (b * c)), TOLERANCE));
+        assert_eq!((a * b).transpose(), b.transpose() * a.transpose());
+
+        // 1e8 + 0.1 is out of reach of f32 but exact enough in f64
+        let point = a.transform_point(Vector::<3, f64>::new(0.0, 0.1, 0.0));
+        assert!(point.approx_eq_with(&Vector::<3, f64>::new(1e8, -2e8 + 0.1, 3.5), TOLERANCE));
+        let product = a * Vector::<4, f64>::from([0.0, 0.1, 0.0, 1.0]);
+        assert_eq!(product[3], 1.0);
+        let single =
+            Matrix::<4>::translate(1e8, 0.0, 0.0).transform_point(Vector::<3>::new(0.1, 0.0, 0.0));
+        assert_eq!(Exact(single[0]), Exact(1e8), "f32 drops the 0.1");
+
+        // the error scales with the 1e8 translation, in f32 it would be around 10
+        let inverse = (a * b).inverse().unwrap();
+        let tolerance = Tolerance::Absolute(1e-6);
+        assert!((a * b * inverse).approx_eq_with(&Matrix::identity(), tolerance));
+        assert!((inverse * a * b).approx_eq_with(&Matrix::identity(), tolerance));
+        assert!(a.det().approx_eq_with(&1.0, TOLERANCE));
+        assert!((a * b).det().approx_eq_with(&-0.6, TOLERANCE));
+
+        // widening is exact, narrowing rounds
+        let single = Matrix::<4>::rotate_y(Deg(30.0));
+        assert_eq!(
+            Exact(Matrix::<4, f64>::from(single).to_f32()),
+            Exact(single)
+        );
+        assert_eq!(Matrix::<4, f64>::rotate_y(Deg(30.0)).to_f32(), single);
+    }
+
+    #[test]
+    fn f64_projections() {
+        let ndc = |m: Matrix<4, f64>, z: f64| {
+            let clip = m * Vector::<4, f64>::from([0.0, 0.0, z, 1.0]);
+            clip[2] / clip[3]
+        };
+        // a 1e-3..1e7 depth range still resolves points near the far plane
+        let m = Matrix::<4, f64>::perspective(Deg(60.0), 1.5, 1e-3, 1e7);
+        assert!(ndc(m, -1e-3).approx_eq_with(&0.0, TOLERANCE));
+        assert!(ndc(m, -1e7).approx_eq_with(&1.0, TOLERANCE));
+        assert!(ndc(m, -0.9e7) < ndc(m, -1e7));
+        let inverse = m.inverse().unwrap();
+        assert!((m * inverse).approx_eq_with(&Matrix::identity(), TOLERANCE));
+
+        let infinite = Matrix::<4, f64>::perspective_infinite_reversed_z(Deg(60.0), 1.5, 1e-3);
+        assert!(ndc(infinite, -1e12) > 0.0);
+        assert!(ndc(infinite, -1e-3).approx_eq_with(&1.0, TOLERANCE));
+
+        let ortho = Matrix::<4, f64>::orthographic(-1e6, 1e6, -1e6, 1e6, 0.0, 1e9);
+        let corner = ortho * Vector::<4, f64>::from([1e6, -1e6, -1e9, 1.0]);
+        assert!(corner.approx_eq_with(&Vector::<4, f64>::from([1.0, -1.0, 1.0, 1.0]), TOLERANCE));
+        assert!((ortho * ortho.inverse().unwrap()).approx_eq_with(&Matrix::identity(), TOLERANCE));
+    }
+
+    #[test]
+    fn perspective_clip_space() {
+        // 90 degrees and a square aspect put the frustum edges at x, y = +-z
+        let m = Matrix::<4>::perspective(Deg(90.0), 1.0, 1.0, 10.0);
+        assert_eq!(ndc(m, 0.0, 0.0, -1.0), Vector::<3>::new(0.0, 0.0, 0.0));
+        assert_eq!(ndc(m, 10.0, -10.0, -10.0), Vector::<3>::new(1.0, -1.0, 1.0));
+        assert_eq!((m * Vector::from([0.0, 0.0, -4.0, 1.0]))[3], 4.0);
+        let wide = Matrix::<4>::perspective(Deg(90.0), 2.0, 1.0, 10.0);
+        assert_eq!(ndc(wide, 2.0, 1.0, -1.0), Vector::<3>::new(1.0, 1.0, 0.0));
+
+        let reversed = Matrix::<4>::perspective_reversed_z(Deg(90.0), 1.0, 1.0, 10.0);
+        assert_eq!(
+            ndc(reversed, -1.0, 1.0, -1.0),
+            Vector::<3>::new(-1.0, 1.0, 1.0)
+        );
+        assert_eq!(ndc(reversed, 0.0, 0.0, -10.0)[2], 0.0);
+    }
+
+    #[test]
+    fn infinite_perspective_clip_space() {
+        let m = Matrix::<4>::perspective_infinite(Deg(90.0), 1.0, 0.5);
+        assert_eq!(ndc(m, 0.5, 0.0, -0.5), Vector::<3>::new(1.0, 0.0, 0.0));
+        let far = ndc(m, 0.0, 0.0, -1e6)[2];
+        assert!(far < 1.0 && far > 0.999_999);
+
+        let reversed = Matrix::<4>::perspective_infinite_reversed_z(Deg(90.0), 1.0, 0.5);
+        assert_eq!(
+            ndc(reversed, 0.0, -0.5, -0.5),
+            Vector::<3>::new(0.0, -1.0, 1.0)
+        );
+        let far = ndc(reversed, 0.0, 0.0, -1e6)[2];
+        assert!(far > 0.0 && far < 1e-6);
+    }
+
+    #[test]
+    fn orthographic_clip_space() {
+        let m = Matrix::<4>::orthographic(-2.0, 2.0, -1.0, 1.0, 0.5, 10.0);
+        assert_eq!(ndc(m, 2.0, 1.0, -0.5), Vector::<3>::new(1.0, 1.0, 0.0));
+        assert_eq!(ndc(m, -2.0, -1.0, -10.0), Vector::<3>::new(-1.0, -1.0, 1.0));
+        assert_eq!(ndc(m, 0.0, 0.0, -5.25), Vector::<3>::new(0.0, 0.0, 0.5));
+
+        let reversed = Matrix::<4>::orthographic_reversed_z(-2.0, 2.0, -1.0, 1.0, 0.5, 10.0);
+        assert_eq!(
+            ndc(reversed, 2.0, 1.0, -0.5),
+            Vector::<3>::new(1.0, 1.0, 1.0)
+        );
+        assert_eq!(
+            ndc(reversed, -2.0, -1.0, -10.0),
+            Vector::<3>::new(-1.0, -1.0, 0.0)
+        );
+    }
+
+    #[test]
+    fn look_at_view_space() {
+        let eye = Vector::<3>::new(0.0, 0.0, 5.0);
+        let up = Vector::<3>::new(0.0, 1.0, 0.0);
+        let view = Matrix::<4>::look_at(eye, Vector::zero(), up);
+        assert_eq!(view.transform_point(eye), Vector::zero());
+        assert_eq!(
+            view.transform_point(Vector::zero()),
+            Vector::<3>::new(0.0, 0.0, -5.0)
+        );
+        assert_eq!(
+            view.transform_point(Vector::<3>::new(1.0, 2.0, 0.0)),
+            Vector::<3>::new(1.0, 2.0, -5.0)
+        );
+
+        // looking down -x from the side, -z ends up on the right
+        let side = Matrix::<4>::look_at(Vector::<3>::new(5.0, 0.0, 0.0), Vector::zero(), up);
+        let m = Matrix::<4>::perspective(Deg(90.0), 1.0, 1.0, 10.0) * side;
+        assert_eq!(ndc(m, 0.0, 0.0, -5.0)[0], 1.0);
+        assert_eq!(ndc(m, 0.0, 2.5, 0.0)[1], 0.5);
+        assert_eq!(ndc(m, 4.0, 0.0, 0.0)[2], 0.0);
+    }
+
+    #[test]
+    fn look_at_parallel_up() {
+        let up = Vector::<3>::new(0.0, 1.0, 0.0);
+        let is_view = |view: Matrix<4>, eye: Vector<3>, target: Vector<3>| {
+            let mut linear = Matrix::<3>::identity();
+            for row in 0..3 {
+                linear[row] = [view[row][0], view[row][1], view[row][2]];
+            }
+            assert_eq!(linear * linear.transpose(), Matrix::identity());
+            assert!(view.det().fuzzy_eq(&1.0));
+            let distance = (target - eye).length();
+            assert_eq!(
+                view.transform_point(target),
+                Vector::<3>::new(0.0, 0.0, -distance)
+            );
+            assert!(view.inverse().is_ok());
+        };
+
+        // straight down keeps x on the right and -z at the top of the screen
+        let eye = Vector::<3>::new(1.0, 10.0, 2.0);
+        let below = Vector::<3>::new(1.0, 0.0, 2.0);
+        let down = Matrix::<4>::look_at(eye, below, up);
+        is_view(down, eye, below);
+        assert_eq!(
+            down.transform_vector(Vector::<3>::new(1.0, 0.0, 0.0)),
+            Vector::<3>::new(1.0, 0.0, 0.0)
+        );
+        assert_eq!(
+            down.transform_vector(Vector::<3>::new(0.0, 0.0, -1.0)),
+            Vector::<3>::new(0.0, 1.0, 0.0)
+        );
+
+        let above = Vector::<3>::new(1.0, 30.0, 2.0);
+        is_view(Matrix::<4>::look_at(eye, above, up), eye, above);
+        is_view(Matrix::<4>::look_at(eye, below, -up * 4.0), eye, below);
+        is_view(
+            Matrix::<4>::look_at(eye, below, Vector::<3>::new(0.0, 1.0, 1e-8)),
+            eye,
+            below,
+        );
+        is_view(Matrix::<4>::look_at(eye, below, Vector::zero()), eye, below);
+
+        // along z and x the fallback picks another axis
+        let origin = Vector::<3>::zero();
+        let z = Vector::<3>::new(0.0, 0.0, 1.0);
+        is_view(Matrix::<4>::look_at(z * 5.0, origin, z), z * 5.0, origin);
+        let x = Vector::<3>::new(1.0, 0.0, 0.0);
+        is_view(Matrix::<4>::look_at(origin, x * 3.0, x), origin, x * 3.0);
+
+        // no direction at all looks down -z
+        let same = Matrix::<4>::look_at(eye, eye, up);
+        assert_eq!(same, Matrix::<4>::translate(-1.0, -10.0, -2.0));
+    }
+
+    fn v3(x: f32, y: f32, z: f32) -> Vector<3> {
+        Vector::<3>::new(x, y, z)
+    }
+
+    /// compared as matrices, q and -q are the same rotation
+    fn same_rotation(a: Quaternion, b: Quaternion) -> bool {
+        Matrix::<3>::from(a) == Matrix::<3>::from(b)
+    }
+
+    #[test]
+    fn decompose_trs_round_trip() {
+        let rotation = Quaternion::from_euler(Deg(20.0), Deg(-35.0), Deg(60.0));
+        let m = Matrix::<4>::from_trs(v3(1.0, -2.0, 3.0), rotation, v3(2.0, 0.5, 3.0));
+        let parts = m.decompose().unwrap();
+        assert_eq!(parts.translation, v3(1.0, -2.0, 3.0));
+        assert_eq!(parts.scale, v3(2.0, 0.5, 3.0));
+        assert_eq!(parts.shear, Vector::zero());
+        assert!(same_rotation(parts.rotation, rotation));
+        let (x, y, z) = parts.euler;
+        assert!(same_rotation(Quaternion::from_euler(x, y, z), rotation));
+        assert_eq!(parts.to_matrix(), m);
+    }
+
+    #[test]
+    fn decompose_negative_scale() {
+        let m = Matrix::<4>::scale(1.0, -1.0, 1.0);
+        let parts = m.decompose().unwrap();
+        assert_eq!(parts.scale, v3(-1.0, 1.0, 1.0));
+        let half_turn = Quaternion::from_axis_angle(v3(0.0, 0.0, 1.0), Deg(180.0));
+        assert!(same_rotation(parts.rotation, half_turn));
+        assert_eq!(parts.to_matrix(), m);
+
+        let rotation = Quaternion::from_euler(Deg(-70.0), Deg(15.0), Deg(5.0));
+        let mirrored = Matrix::<4>::from_trs(v3(4.0, 0.0, -1.0), rotation, v3(2.0, 3.0, -0.5));
+        assert_eq!(mirrored.decompose().unwrap().to_matrix(), mirrored);
+    }
+
+    #[test]
+    fn decompose_shear_round_trip() {
+        let mut shear = Matrix::<4>::identity();
+        shear[0][1] = 0.5;
+        shear[0][2] = -0.25;
+        shear[1][2] = 0.75;
+        let rotation = Quaternion::from_euler(Deg(10.0), Deg(40.0), Deg(-25.0));
+        let m = Matrix::<4>::from_trs(v3(0.0, 5.0, -3.0), rotation, v3(1.5, 2.0, 0.5)) * shear;
+        let parts = m.decompose().unwrap();
+        assert_eq!(parts.shear, v3(0.5, -0.25, 0.75));
+        assert_eq!(parts.scale, v3(1.5, 2.0, 0.5));
+        assert!(same_rotation(parts.rotation, rotation));
+        assert_eq!(parts.to_matrix(), m);
+    }
+
+    #[test]
+    fn decompose_singular() {
+        let flat = Matrix::<4>::scale(1.0, 0.0, 1.0);
+        assert_eq!(flat.decompose(), Err(MatrixError::NotInvertible));
+        assert_eq!(
+            Matrix::<4>::scale(0.0, 0.0, 0.0).decompose(),
+            Err(MatrixError::NotInvertible)
+        );
+
+        // the second column repeats the first, rounding leaves more than the absolute
+        // EPSILON at this size but far less than the columns
+        let rotation = Quaternion::from_euler(Deg(10.0), Deg(40.0), Deg(-25.0));
+        let mut dependent = Matrix::<4>::from_trs(Vector::zero(), rotation, v3(1e3, 1e3, 1e3));
+        for row in 0..3 {
+            dependent[row][1] = dependent[row][0] * 3.0 + 1e-4;
         }
-        det
+        assert_eq!(dependent.decompose(), Err(MatrixError::NotInvertible));
+    }
+
+    #[test]
+    fn decompose_small_scale() {
+        let rotation = Quaternion::from_euler(Deg(30.0), Deg(-20.0), Deg(75.0));
+        let scale = v3(1e-7, 2e-7, 5e-7);
+        let m = Matrix::<4>::from_trs(v3(1.0, 2.0, 3.0), rotation, scale);
+        let parts = m.decompose().unwrap();
+        let relative = Tolerance::Relative(1e-4);
+        assert!(
+            parts.scale.approx_eq_with(&scale, relative),
+            "{:?}",
+            parts.scale
+        );
+        assert!(same_rotation(parts.rotation, rotation));
+        assert_eq!(parts.translation, v3(1.0, 2.0, 3.0));
+
+        // even smaller in f64
+        let tiny = Matrix::<4, f64>::scale(1e-9, 3e-9, 2e-9);
+        let parts = tiny.decompose().unwrap();
+        assert!(
+            parts
+                .scale
+                .approx_eq_with(&Vector::from([1e-9, 3e-9, 2e-9]), Tolerance::Relative(1e-9))
+        );
     }
 }
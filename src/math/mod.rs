@@ -1,3 +1,15 @@
+pub mod angle;
+pub mod color;
 pub mod common;
+pub mod curve;
+pub mod frustum;
+pub mod geometry;
+mod interop;
+pub mod layout;
 pub mod matrix;
+pub mod quaternion;
+#[cfg(feature = "serde")]
+mod serde;
+mod simd;
+pub mod transform;
 pub mod vector;
@@ -0,0 +1,498 @@
+use std::ops::{Mul, Neg};
+
+use super::{
+    angle::Rad,
+    common::{ApproxEq, Dimension3, Dimension4, FuzzyEq, Scalar, ToBits, Tolerance},
+    matrix::Matrix,
+    vector::Vector,
+};
+
+/// unit quaternions represent rotations, x/y/z is the vector part and w the scalar part
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Copy, Clone, Debug)]
+pub struct Quaternion<T: Scalar = f32> {
+    x: T,
+    y: T,
+    z: T,
+    w: T,
+}
+
+impl<T: Scalar> Quaternion<T> {
+    pub fn identity() -> Self {
+        Self::new(T::ZERO, T::ZERO, T::ZERO, T::ONE)
+    }
+
+    /// counterclockwise rotation around `axis`, the axis does not need to be normalized
+    pub fn from_axis_angle(axis: Vector<3, T>, angle: impl Into<Rad<T>>) -> Self {
+        let axis = axis.normalize();
+        let (sin, cos) = (angle.into() * T::HALF).sin_cos();
+        Self::new(axis[0] * sin, axis[1] * sin, axis[2] * sin, cos)
+    }
+
+    /// rotates around x first, then y, then z, same as `rotate_z(z) * rotate_y(y) * rotate_x(x)`
+    pub fn from_euler(x: impl Into<Rad<T>>, y: impl Into<Rad<T>>, z: impl Into<Rad<T>>) -> Self {
+        let (zero, one) = (T::ZERO, T::ONE);
+        let qx = Self::from_axis_angle(Vector::<3, T>::new(one, zero, zero), x);
+        let qy = Self::from_axis_angle(Vector::<3, T>::new(zero, one, zero), y);
+        let qz = Self::from_axis_angle(Vector::<3, T>::new(zero, zero, one), z);
+        qz * qy * qx
+    }
+
+    /// shortest rotation taking the direction of `from` onto the direction of `to`
+    pub fn from_rotation_arc(from: Vector<3, T>, to: Vector<3, T>) -> Self {
+        let from = from.normalize();
+        let to = to.normalize();
+        let dot = from.dot(&to);
+        let tolerance = T::from_f64(1e-6);
+        if dot >= T::ONE - tolerance {
+            return Self::identity();
+        }
+        if dot <= -T::ONE + tolerance {
+            // opposite directions, any axis perpendicular to `from` works
+            let (zero, one) = (T::ZERO, T::ONE);
+            let mut axis = Vector::<3, T>::new(one, zero, zero).cross(&from);
+            if axis.length_squared() < tolerance {
+                axis = Vector::<3, T>::new(zero, one, zero).cross(&from);
+            }
+            return Self::from_axis_angle(axis, Rad(T::PI));
+        }
+        let axis = from.cross(&to);
+        Self::new(axis[0], axis[1], axis[2], T::ONE + dot).normalize()
+    }
+
+    /// inverse of `from_euler`, returns the (x, y, z) angles
+    pub fn to_euler(self) -> (Rad<T>, Rad<T>, Rad<T>) {
+        let m = Matrix::<3, T>::from(self);
+        let sin_y = (-m[2][0]).clamp(-T::ONE, T::ONE);
+        // asin loses half the digits near the poles, atan2 of the column keeps them
+        let cos_y = (m[0][0] * m[0][0] + m[1][0] * m[1][0]).sqrt();
+        let y = sin_y.atan2(cos_y);
+        if sin_y.abs() > T::ONE - T::from_f64(1e-6) {
+            // gimbal lock, fold the x rotation into z
+            let z = (-m[0][1]).atan2(m[1][1]);
+            (Rad(T::ZERO), Rad(y), Rad(z))
+        } else {
+            let x = m[2][1].atan2(m[2][2]);
+            let z = m[1][0].atan2(m[0][0]);
+            (Rad(x), Rad(y), Rad(z))
+        }
+    }
+
+    pub fn vector(&self) -> Vector<3, T> {
+        Vector::<3, T>::new(self.x, self.y, self.z)
+    }
+
+    pub fn dot(&self, other: &Self) -> T {
+        self.x * other.x + self.y * other.y + self.z * other.z + self.w * other.w
+    }
+
+    pub fn length_squared(&self) -> T {
+        self.dot(self)
+    }
+
+    pub fn length(&self) -> T {
+        self.length_squared().sqrt()
+    }
+
+    /// returns the identity when the length is zero or denormal, short quaternions still normalize
+    pub fn normalize(&self) -> Self {
+        let length = self.length();
+        if length < T::MIN_POSITIVE {
+            Self::identity()
+        } else {
+            self.scaled(T::ONE / length)
+        }
+    }
+
+    pub fn conjugate(&self) -> Self {
+        Self::new(-self.x, -self.y, -self.z, self.w)
+    }
+
+    /// equals the conjugate for unit quaternions, the zero quaternion maps to the identity
+    pub fn inverse(&self) -> Self {
+        let length_squared = self.length_squared();
+        if length_squared.fuzzy_eq(&T::ZERO) {
+            Self::identity()
+        } else {
+            self.conjugate().scaled(T::ONE / length_squared)
+        }
+    }
+
+    /// normalized linear interpolation, cheap but not constant speed
+    pub fn nlerp(&self, other: &Self, t: T) -> Self {
+        let other = self.closest(other);
+        Self::new(
+            self.x + (other.x - self.x) * t,
+            self.y + (other.y - self.y) * t,
+            self.z + (other.z - self.z) * t,
+            self.w + (other.w - self.w) * t,
+        )
+        .normalize()
+    }
+
+    /// spherical linear interpolation along the shortest arc, constant angular speed
+    pub fn slerp(&self, other: &Self, t: T) -> Self {
+        let other = self.closest(other);
+        let cos = self.dot(&other).clamp(-T::ONE, T::ONE);
+        if cos > T::ONE - T::from_f64(1e-4) {
+            // nearly parallel, the sine below would vanish
+            return self.nlerp(&other, t);
+        }
+        let theta = cos.acos();
+        let sin = theta.sin();
+        let a = ((T::ONE - t) * theta).sin() / sin;
+        let b = (t * theta).sin() / sin;
+        Self::new(
+            self.x * a + other.x * b,
+            self.y * a + other.y * b,
+            self.z * a + other.z * b,
+            self.w * a + other.w * b,
+        )
+    }
+
+    pub fn rotate(&self, vector: Vector<3, T>) -> Vector<3, T> {
+        // v' = v + 2w(q x v) + 2q x (q x v)
+        let q = self.vector();
+        let t = q.cross(&vector) * T::TWO;
+        vector + t * self.w + q.cross(&t)
+    }
+
+    fn scaled(&self, factor: T) -> Self {
+        Self::new(
+            self.x * factor,
+            self.y * factor,
+            self.z * factor,
+            self.w * factor,
+        )
+    }
+
+    /// q and -q are the same rotation, pick the one on the same hemisphere as self
+    fn closest(&self, other: &Self) -> Self {
+        if self.dot(other) < T::ZERO {
+            -*other
+        } else {
+            *other
+        }
+    }
+}
+
+impl<T: Scalar> Default for Quaternion<T> {
+    fn default() -> Self {
+        Self::identity()
+    }
+}
+
+impl<T: Scalar> Dimension4 for Quaternion<T> {
+    type Value = T;
+
+    fn new(x: Self::Value, y: Self::Value, z: Self::Value, w: Self::Value) -> Self {
+        Self { x, y, z, w }
+    }
+    fn get_x(&self) -> Self::Value {
+        self.x
+    }
+
+    fn get_y(&self) -> Self::Value {
+        self.y
+    }
+    fn get_z(&self) -> Self::Value {
+        self.z
+    }
+
+    fn get_w(&self) -> Self::Value {
+        self.w
+    }
+}
+
+impl<T: Scalar> FuzzyEq for Quaternion<T> {
+    fn fuzzy_eq(&self, other: &Self) -> bool {
+        self.x.fuzzy_eq(&other.x)
+            && self.y.fuzzy_eq(&other.y)
+            && self.z.fuzzy_eq(&other.z)
+            && self.w.fuzzy_eq(&other.w)
+    }
+}
+
+impl<T: Scalar> ApproxEq<T> for Quaternion<T> {
+    fn approx_eq_with(&self, other: &Self, tolerance: Tolerance<T>) -> bool {
+        self.x.approx_eq_with(&other.x, tolerance)
+            && self.y.approx_eq_with(&other.y, tolerance)
+            && self.z.approx_eq_with(&other.z, tolerance)
+            && self.w.approx_eq_with(&other.w, tolerance)
+    }
+}
+
+impl<T: Scalar> ToBits for Quaternion<T> {
+    type Bits = [T::Bits; 4];
+    fn to_bits(&self) -> Self::Bits {
+        [self.x, self.y, self.z, self.w].map(|value| value.to_bits())
+    }
+}
+
+/// fuzzy, see `FuzzyEq`, wrap in `Exact` for bitwise equality
+impl<T: Scalar> PartialEq for Quaternion<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.fuzzy_eq(other)
+    }
+}
+
+impl<T: Scalar> Neg for Quaternion<T> {
+    type Output = Self;
+    fn neg(self) -> Self::Output {
+        self.scaled(-T::ONE)
+    }
+}
+
+/// Hamilton product, `a * b` applies b first and then a
+impl<T: Scalar> Mul for Quaternion<T> {
+    type Output = Self;
+    fn mul(self, rhs: Self) -> Self::Output {
+        Self::new(
+            self.w * rhs.x + self.x * rhs.w + self.y * rhs.z - self.z * rhs.y,
+            self.w * rhs.y - self.x * rhs.z + self.y * rhs.w + self.z * rhs.x,
+            self.w * rhs.z + self.x * rhs.y - self.y * rhs.x + self.z * rhs.w,
+            self.w * rhs.w - self.x * rhs.x - self.y * rhs.y - self.z * rhs.z,
+        )
+    }
+}
+
+impl<T: Scalar> Mul<Vector<3, T>> for Quaternion<T> {
+    type Output = Vector<3, T>;
+    fn mul(self, rhs: Vector<3, T>) -> Self::Output {
+        self.rotate(rhs)
+    }
+}
+
+/// 3D rotation matrix, the quaternion is expected to be normalized
+impl<T: Scalar> From<Quaternion<T>> for Matrix<3, T> {
+    fn from(q: Quaternion<T>) -> Self {
+        let (x, y, z, w) = (q.x, q.y, q.z, q.w);
+        let (one, two) = (T::ONE, T::TWO);
+        let mut m = Matrix::<3, T>::identity();
+        m[0][0] = one - two * (y * y + z * z);
+        m[0][1] = two * (x * y - w * z);
+        m[0][2] = two * (x * z + w * y);
+        m[1][0] = two * (x * y + w * z);
+        m[1][1] = one - two * (x * x + z * z);
+        m[1][2] = two * (y * z - w * x);
+        m[2][0] = two * (x * z - w * y);
+        m[2][1] = two * (y * z + w * x);
+        m[2][2] = one - two * (x * x + y * y);
+        m
+    }
+}
+
+impl<T: Scalar> From<Quaternion<T>> for Matrix<4, T> {
+    fn from(q: Quaternion<T>) -> Self {
+        let rotation = Matrix::<3, T>::from(q);
+        let mut m = Matrix::<4, T>::identity();
+        for row in 0..3 {
+            for col in 0..3 {
+                m[row][col] = rotation[row][col];
+            }
+        }
+        m
+    }
+}
+
+/// expects a pure rotation matrix (orthonormal, determinant 1)
+impl<T: Scalar> From<Matrix<3, T>> for Quaternion<T> {
+    fn from(m: Matrix<3, T>) -> Self {
+        let (one, two, quarter) = (T::ONE, T::TWO, T::HALF * T::HALF);
+        let trace = m[0][0] + m[1][1] + m[2][2];
+        let q = if trace > T::ZERO {
+            let s = (trace + one).sqrt() * two;
+            Self::new(
+                (m[2][1] - m[1][2]) / s,
+                (m[0][2] - m[2][0]) / s,
+                (m[1][0] - m[0][1]) / s,
+                quarter * s,
+            )
+        } else if m[0][0] > m[1][1] && m[0][0] > m[2][2] {
+            let s = (one + m[0][0] - m[1][1] - m[2][2]).sqrt() * two;
+            Self::new(
+                quarter * s,
+                (m[0][1] + m[1][0]) / s,
+                (m[0][2] + m[2][0]) / s,
+                (m[2][1] - m[1][2]) / s,
+            )
+        } else if m[1][1] > m[2][2] {
+            let s = (one + m[1][1] - m[0][0] - m[2][2]).sqrt() * two;
+            Self::new(
+                (m[0][1] + m[1][0]) / s,
+                quarter * s,
+                (m[1][2] + m[2][1]) / s,
+                (m[0][2] - m[2][0]) / s,
+            )
+        } else {
+            let s = (one + m[2][2] - m[0][0] - m[1][1]).sqrt() * two;
+            Self::new(
+                (m[0][2] + m[2][0]) / s,
+                (m[1][2] + m[2][1]) / s,
+                quarter * s,
+                (m[1][0] - m[0][1]) / s,
+            )
+        };
+        q.normalize()
+    }
+}
+
+/// uses the upper-left 3x3 rotation part, translation is ignored
+impl<T: Scalar> From<Matrix<4, T>> for Quaternion<T> {
+    fn from(m: Matrix<4, T>) -> Self {
+        let mut rotation = Matrix::<3, T>::identity();
+        for row in 0..3 {
+            for col in 0..3 {
+                rotation[row][col] = m[row][col];
+            }
+        }
+        Self::from(rotation)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::angle::Deg;
+
+    fn v3(x: f32, y: f32, z: f32) -> Vector<3> {
+        Vector::<3>::new(x, y, z)
+    }
+
+    /// q and -q are the same rotation
+    fn same_rotation(a: Quaternion, b: Quaternion) -> bool {
+        a == b || a == -b
+    }
+
+    #[test]
+    fn axis_angle_matches_matrix_rotations() {
+        let angle = Deg(30.0);
+        let x = Quaternion::from_axis_angle(v3(1.0, 0.0, 0.0), angle);
+        let y = Quaternion::from_axis_angle(v3(0.0, 1.0, 0.0), angle);
+        let z = Quaternion::from_axis_angle(v3(0.0, 0.0, 5.0), angle);
+        assert_eq!(Matrix::<4>::from(x), Matrix::<4>::rotate_x(angle));
+        assert_eq!(Matrix::<4>::from(y), Matrix::<4>::rotate_y(angle));
+        assert_eq!(Matrix::<4>::from(z), Matrix::<4>::rotate_z(angle));
+        assert_eq!(
+            z * v3(1.0, 0.0, 0.0),
+            Matrix::<4>::rotate_z(angle).transform_vector(v3(1.0, 0.0, 0.0))
+        );
+        assert_eq!(
+            Quaternion::<f32>::from_axis_angle(v3(0.0, 1.0, 0.0), Deg(0.0)),
+            Quaternion::identity()
+        );
+    }
+
+    #[test]
+    fn euler_matches_matrix_rotations() {
+        let (x, y, z) = (Deg(20.0), Deg(-35.0), Deg(110.0));
+        let q: Quaternion = Quaternion::from_euler(x, y, z);
+        let expected =
+            Matrix::<4>::rotate_z(z) * Matrix::<4>::rotate_y(y) * Matrix::<4>::rotate_x(x);
+        assert_eq!(Matrix::<4>::from(q), expected);
+
+        let (ex, ey, ez) = q.to_euler();
+        assert_eq!((ex, ey, ez), (x.into(), y.into(), z.into()));
+
+        // gimbal lock folds x into z, the rotation itself survives
+        let locked: Quaternion = Quaternion::from_euler(Deg(30.0), Deg(90.0), Deg(10.0));
+        let (lx, ly, lz) = locked.to_euler();
+        assert_eq!(lx, Rad(0.0));
+        assert!(same_rotation(Quaternion::from_euler(lx, ly, lz), locked));
+    }
+
+    #[test]
+    fn rotation_arc() {
+        let from = v3(1.0, 2.0, -0.5);
+        let to = v3(-3.0, 0.5, 2.0);
+        let q = Quaternion::from_rotation_arc(from, to);
+        assert_eq!(q * from.normalize(), to.normalize());
+        assert!(q.length().fuzzy_eq(&1.0));
+
+        // parallel, whatever the lengths
+        assert_eq!(
+            Quaternion::from_rotation_arc(from, from * 3.0),
+            Quaternion::identity()
+        );
+
+        // antiparallel needs a perpendicular axis, including along x where the first guess fails
+        for from in [
+            v3(1.0, 0.0, 0.0),
+            v3(0.0, 1.0, 0.0),
+            v3(0.0, 0.0, 1.0),
+            v3(1.0, 2.0, -0.5),
+        ] {
+            let q = Quaternion::from_rotation_arc(from, -from * 2.0);
+            assert!(q.length().fuzzy_eq(&1.0));
+            assert!(q.w.abs() < 1e-6, "a half turn has w = 0");
+            assert_eq!(q * from, -from);
+        }
+    }
+
+    #[test]
+    fn matrix_round_trip() {
+        // positive trace, then half turns that pick each of the other branches
+        let rotations: [Quaternion; 5] = [
+            Quaternion::from_euler(Deg(20.0), Deg(-35.0), Deg(110.0)),
+            Quaternion::from_axis_angle(v3(1.0, 0.0, 0.0), Deg(180.0)),
+            Quaternion::from_axis_angle(v3(0.0, 1.0, 0.0), Deg(180.0)),
+            Quaternion::from_axis_angle(v3(0.0, 0.0, 1.0), Deg(180.0)),
+            Quaternion::from_axis_angle(v3(1.0, -1.0, 0.5), Deg(170.0)),
+        ];
+        for q in rotations {
+            assert!(
+                same_rotation(Quaternion::from(Matrix::<3>::from(q)), q),
+                "{q:?}"
+            );
+            let mut m = Matrix::<4>::from(q);
+            m[0][3] = 7.0;
+            assert!(
+                same_rotation(Quaternion::from(m), q),
+                "translation is ignored"
+            );
+        }
+    }
+
+    #[test]
+    fn slerp() {
+        let a = Quaternion::from_axis_angle(v3(0.0, 0.0, 1.0), Deg(10.0));
+        let b = Quaternion::from_axis_angle(v3(0.0, 0.0, 1.0), Deg(90.0));
+        assert_eq!(a.slerp(&b, 0.0), a);
+        assert_eq!(a.slerp(&b, 1.0), b);
+        assert_eq!(
+            a.slerp(&b, 0.25),
+            Quaternion::from_axis_angle(v3(0.0, 0.0, 1.0), Deg(30.0))
+        );
+        assert_eq!(a.nlerp(&b, 1.0), b);
+
+        // -b is the same rotation, the result must not take the long way round
+        assert_eq!(a.slerp(&-b, 0.25), a.slerp(&b, 0.25));
+        assert!(same_rotation(a.slerp(&-b, 1.0), b));
+        assert_eq!(a.nlerp(&-b, 0.5), a.nlerp(&b, 0.5));
+
+        // nearly parallel falls back to nlerp
+        let c = Quaternion::from_axis_angle(v3(0.0, 0.0, 1.0), Deg(10.1));
+        assert_eq!(
+            a.slerp(&c, 0.5),
+            Quaternion::from_axis_angle(v3(0.0, 0.0, 1.0), Deg(10.05))
+        );
+    }
+
+    #[test]
+    fn normalize_and_inverse() {
+        let q: Quaternion = Quaternion::new(1.0, 2.0, -2.0, 4.0);
+        assert!(q.normalize().length().fuzzy_eq(&1.0));
+        assert_eq!(q * q.inverse(), Quaternion::identity());
+
+        // short quaternions still normalize, only zero falls back to the identity
+        let short: Quaternion = Quaternion::new(0.0, 0.0, 3e-7, 4e-7);
+        assert_eq!(short.normalize(), Quaternion::new(0.0, 0.0, 0.6, 0.8));
+        assert_eq!(
+            Quaternion::<f32>::new(0.0, 0.0, 0.0, 0.0).normalize(),
+            Quaternion::identity()
+        );
+
+        let unit = q.normalize();
+        assert_eq!(unit.inverse(), unit.conjugate());
+    }
+}
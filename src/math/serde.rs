@@ -0,0 +1,172 @@
+// Vector<D> is written as [x, y, ...] and Matrix<D> as a list of rows, [[m00, m01, ...], ...].
+// serde has no const generic array support, so both are implemented by hand and reading
+// fails unless exactly D values (D rows of D values) are present.
+
+use std::{fmt, marker::PhantomData};
+
+use serde::{
+    Deserialize, Deserializer, Serialize, Serializer,
+    de::{self, SeqAccess, Visitor},
+    ser::SerializeTuple,
+};
+
+use super::{common::Scalar, matrix::Matrix, vector::Vector};
+
+impl<const D: usize, T: Scalar + Serialize> Serialize for Vector<D, T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_array(&self.to_array(), serializer)
+    }
+}
+
+impl<'de, const D: usize, T: Scalar + Deserialize<'de>> Deserialize<'de> for Vector<D, T> {
+    fn deserialize<De: Deserializer<'de>>(deserializer: De) -> Result<Self, De::Error> {
+        deserializer
+            .deserialize_tuple(D, ArrayVisitor::<D, T>(PhantomData))
+            .map(Vector::from)
+    }
+}
+
+impl<const D: usize, T: Scalar + Serialize> Serialize for Matrix<D, T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut tuple = serializer.serialize_tuple(D)?;
+        for row in 0..D {
+            tuple.serialize_element(&Row(&self[row]))?;
+        }
+        tuple.end()
+    }
+}
+
+impl<'de, const D: usize, T: Scalar + Deserialize<'de>> Deserialize<'de> for Matrix<D, T> {
+    fn deserialize<De: Deserializer<'de>>(deserializer: De) -> Result<Self, De::Error> {
+        let rows =
+            deserializer.deserialize_tuple(D, ArrayVisitor::<D, Vector<D, T>>(PhantomData))?;
+        let mut matrix = Matrix::new();
+        for (row, values) in rows.iter().enumerate() {
+            matrix[row] = values.to_array();
+        }
+        Ok(matrix)
+    }
+}
+
+struct Row<'a, const D: usize, T>(&'a [T; D]);
+
+impl<const D: usize, T: Serialize> Serialize for Row<'_, D, T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_array(self.0, serializer)
+    }
+}
+
+fn serialize_array<const D: usize, T: Serialize, S: Serializer>(
+    values: &[T; D],
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    let mut tuple = serializer.serialize_tuple(D)?;
+    for value in values {
+        tuple.serialize_element(value)?;
+    }
+    tuple.end()
+}
+
+struct ArrayVisitor<const D: usize, T>(PhantomData<T>);
+
+impl<'de, const D: usize, T: Deserialize<'de>> Visitor<'de> for ArrayVisitor<D, T> {
+    type Value = [T; D];
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "an array of length {D}")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut values = Vec::with_capacity(D);
+        while let Some(value) = seq.next_element()? {
+            if values.len() == D {
+                return Err(de::Error::invalid_length(D + 1, &self));
+            }
+            values.push(value);
+        }
+        values
+            .try_into()
+            .map_err(|values: Vec<T>| de::Error::invalid_length(values.len(), &self))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{from_str, to_string};
+
+    use super::*;
+    use crate::math::{
+        angle::Deg,
+        common::{Dimension3, Exact},
+        quaternion::Quaternion,
+    };
+
+    #[test]
+    fn vector_round_trip() {
+        let vector = Vector::<3>::new(1.5, -2.0, 0.25);
+        let json = to_string(&vector).unwrap();
+        assert_eq!(json, "[1.5,-2.0,0.25]");
+        assert_eq!(Exact(from_str::<Vector<3>>(&json).unwrap()), Exact(vector));
+
+        let precise = Vector::<2, f64>::from([0.1, 1e-300]);
+        let json = to_string(&precise).unwrap();
+        assert_eq!(
+            Exact(from_str::<Vector<2, f64>>(&json).unwrap()),
+            Exact(precise)
+        );
+    }
+
+    #[test]
+    fn matrix_round_trip() {
+        let mut matrix = Matrix::<4>::translate(1.0, 2.0, 3.0);
+        matrix[2][1] = -0.5;
+        let json = to_string(&matrix).unwrap();
+        assert_eq!(
+            json,
+            "[[1.0,0.0,0.0,1.0],[0.0,1.0,0.0,2.0],[0.0,-0.5,1.0,3.0],[0.0,0.0,0.0,1.0]]"
+        );
+        assert_eq!(Exact(from_str::<Matrix<4>>(&json).unwrap()), Exact(matrix));
+
+        let identity = Matrix::<2, f64>::identity();
+        let json = to_string(&identity).unwrap();
+        assert_eq!(
+            Exact(from_str::<Matrix<2, f64>>(&json).unwrap()),
+            Exact(identity)
+        );
+    }
+
+    #[test]
+    fn wrong_lengths_are_rejected() {
+        assert!(from_str::<Vector<3>>("[1.0,2.0]").is_err());
+        assert!(from_str::<Vector<3>>("[1.0,2.0,3.0,4.0]").is_err());
+        assert!(from_str::<Vector<3>>("[]").is_err());
+        assert!(from_str::<Vector<3>>("1.0").is_err());
+        let error = from_str::<Vector<3>>("[1.0,2.0,3.0,4.0]").unwrap_err();
+        assert!(error.to_string().contains("length 3"), "{error}");
+
+        let rows = |rows: &[&str]| format!("[{}]", rows.join(","));
+        let row = "[1.0,0.0,0.0,0.0]";
+        assert!(from_str::<Matrix<4>>(&rows(&[row; 4])).is_ok());
+        // short and long
+        assert!(from_str::<Matrix<4>>(&rows(&[row; 3])).is_err());
+        assert!(from_str::<Matrix<4>>(&rows(&[row; 5])).is_err());
+        // ragged
+        assert!(from_str::<Matrix<4>>(&rows(&[row, row, "[1.0,0.0,0.0]", row])).is_err());
+        assert!(from_str::<Matrix<4>>(&rows(&[row, row, row, "[1.0,0.0,0.0,0.0,0.0]"])).is_err());
+        // flat
+        assert!(from_str::<Matrix<2>>("[1.0,0.0,0.0,1.0]").is_err());
+    }
+
+    #[test]
+    fn derived_types_round_trip() {
+        let angle = Deg(90.0f32);
+        assert_eq!(from_str::<Deg>(&to_string(&angle).unwrap()).unwrap(), angle);
+
+        let rotation: Quaternion = Quaternion::from_euler(Deg(10.0), Deg(20.0), Deg(30.0));
+        let json = to_string(&rotation).unwrap();
+        assert_eq!(
+            Exact(from_str::<Quaternion>(&json).unwrap()),
+            Exact(rotation)
+        );
+    }
+}
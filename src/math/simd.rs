@@ -0,0 +1,475 @@
+// Matrix<4> (f32) fast paths. x86_64 always has SSE2, other targets use a scalar version of
+// the exact same arithmetic, so both paths produce bit-identical results.
+//
+// The generic `Matrix<D, T>` operations cannot be specialized, so `*` and `inverse` ask
+// `mul` and `inverse` below first, which only answer for Matrix<4, f32>. The type check
+// compares constant TypeIds and folds away in optimized builds.
+
+use std::any::Any;
+
+use super::{
+    common::Scalar,
+    matrix::{Matrix, MatrixError},
+};
+use crate::constant::EPSILON;
+
+type Rows = [[f32; 4]; 4];
+
+/// fast path for `Matrix<D, T> * Matrix<D, T>`, None unless D is 4 and T is f32
+pub(super) fn mul<const D: usize, T: Scalar>(
+    lhs: &Matrix<D, T>,
+    rhs: &Matrix<D, T>,
+) -> Option<Matrix<D, T>> {
+    let lhs = (lhs as &dyn Any).downcast_ref::<Matrix<4>>()?;
+    let rhs = (rhs as &dyn Any).downcast_ref::<Matrix<4>>()?;
+    (&lhs.mul_simd(rhs) as &dyn Any)
+        .downcast_ref::<Matrix<D, T>>()
+        .copied()
+}
+
+/// fast path for `Matrix<D, T>::inverse`, None unless D is 4 and T is f32
+pub(super) fn inverse<const D: usize, T: Scalar>(
+    matrix: &Matrix<D, T>,
+) -> Option<Result<Matrix<D, T>, MatrixError>> {
+    let matrix = (matrix as &dyn Any).downcast_ref::<Matrix<4>>()?;
+    match matrix.inverse_simd() {
+        Ok(inverse) => (&inverse as &dyn Any)
+            .downcast_ref::<Matrix<D, T>>()
+            .copied()
+            .map(Ok),
+        Err(error) => Some(Err(error)),
+    }
+}
+
+impl Matrix<4> {
+    /// same result as `*`, which forwards here
+    pub fn mul_simd(&self, rhs: &Self) -> Self {
+        from_rows(imp::mul(&rows(self), &rows(rhs)))
+    }
+
+    /// same result as `transpose`
+    pub fn transpose_simd(&self) -> Self {
+        from_rows(imp::transpose(&rows(self)))
+    }
+
+    /// closed form adjugate over 2x2 sub-determinants instead of LU, `inverse` forwards here,
+    /// so only the Matrix<4, f64> inverse goes through LU
+    pub fn inverse_simd(&self) -> Result<Self, MatrixError> {
+        imp::inverse(&rows(self)).map(from_rows)
+    }
+}
+
+fn rows(matrix: &Matrix<4>) -> Rows {
+    [matrix[0], matrix[1], matrix[2], matrix[3]]
+}
+
+fn from_rows(rows: Rows) -> Matrix<4> {
+    let mut matrix = Matrix::new();
+    for (row, values) in rows.into_iter().enumerate() {
+        matrix[row] = values;
+    }
+    matrix
+}
+
+/// Singular when the first three columns are within `EPSILON` of spanning no volume,
+/// relative to the product of their lengths, or when the determinant is within rounding of
+/// zero. The cofactors of the last column have the length of that volume. The last column,
+/// where translations go, only enters the rounding check, so moving a matrix far from the
+/// origin does not make it singular.
+fn singular(rows: &Rows, cofactors: &[f32; 4], det: f32) -> bool {
+    let length = |values: [f32; 4]| values.iter().map(|value| value * value).sum::<f32>().sqrt();
+    let column = |j: usize| length(rows.map(|row| row[j]));
+    let volume = length(*cofactors);
+    volume <= EPSILON * column(0) * column(1) * column(2)
+        || det.abs() <= f32::EPSILON * volume * column(3)
+}
+
+// The inverse follows the Laplace expansion with the 2x2 determinants
+//   s0..s5 of rows 0 and 1, c0..c5 of rows 2 and 3,
+// every row of the adjugate being
+//   sign * (V_i * K_a - V_j * K_b + V_k * K_c)
+// where V_j = [a1j, a0j, a3j, a2j] gathers column j and K_n = [cn, cn, sn, sn].
+#[cfg(target_arch = "x86_64")]
+mod imp {
+    use std::arch::x86_64::*;
+
+    use super::{MatrixError, Rows, singular};
+
+    /// _MM_SHUFFLE, picks lanes a, b from the first operand and c, d from the second
+    const fn lanes(a: i32, b: i32, c: i32, d: i32) -> i32 {
+        a | (b << 2) | (c << 4) | (d << 6)
+    }
+
+    pub fn mul(lhs: &Rows, rhs: &Rows) -> Rows {
+        // SAFETY: SSE is part of the x86_64 baseline, loads and stores are unaligned
+        unsafe {
+            let b = rhs.map(|row| _mm_loadu_ps(row.as_ptr()));
+            let mut res = [[0.0; 4]; 4];
+            for (row, out) in lhs.iter().zip(res.iter_mut()) {
+                let mut sum = _mm_mul_ps(_mm_set1_ps(row[0]), b[0]);
+                sum = _mm_add_ps(sum, _mm_mul_ps(_mm_set1_ps(row[1]), b[1]));
+                sum = _mm_add_ps(sum, _mm_mul_ps(_mm_set1_ps(row[2]), b[2]));
+                sum = _mm_add_ps(sum, _mm_mul_ps(_mm_set1_ps(row[3]), b[3]));
+                _mm_storeu_ps(out.as_mut_ptr(), sum);
+            }
+            res
+        }
+    }
+
+    pub fn transpose(rows: &Rows) -> Rows {
+        // SAFETY: see `mul`
+        unsafe {
+            let [r0, r1, r2, r3] = rows.map(|row| _mm_loadu_ps(row.as_ptr()));
+            let t0 = _mm_unpacklo_ps(r0, r1);
+            let t1 = _mm_unpacklo_ps(r2, r3);
+            let t2 = _mm_unpackhi_ps(r0, r1);
+            let t3 = _mm_unpackhi_ps(r2, r3);
+            let columns = [
+                _mm_movelh_ps(t0, t1),
+                _mm_movehl_ps(t1, t0),
+                _mm_movelh_ps(t2, t3),
+                _mm_movehl_ps(t3, t2),
+            ];
+            let mut res = [[0.0; 4]; 4];
+            for (column, out) in columns.iter().zip(res.iter_mut()) {
+                _mm_storeu_ps(out.as_mut_ptr(), *column);
+            }
+            res
+        }
+    }
+
+    pub fn inverse(rows: &Rows) -> Result<Rows, MatrixError> {
+        // SAFETY: see `mul`
+        unsafe {
+            let [r0, r1, r2, r3] = rows.map(|row| _mm_loadu_ps(row.as_ptr()));
+
+            // [s0, s1, s2, s3] and [s4, s5, s4, s5]
+            let s_low = _mm_sub_ps(
+                _mm_mul_ps(
+                    _mm_shuffle_ps::<{ lanes(0, 0, 0, 1) }>(r0, r0),
+                    _mm_shuffle_ps::<{ lanes(1, 2, 3, 2) }>(r1, r1),
+                ),
+                _mm_mul_ps(
+                    _mm_shuffle_ps::<{ lanes(0, 0, 0, 1) }>(r1, r1),
+                    _mm_shuffle_ps::<{ lanes(1, 2, 3, 2) }>(r0, r0),
+                ),
+            );
+            let s_high = _mm_sub_ps(
+                _mm_mul_ps(
+                    _mm_shuffle_ps::<{ lanes(1, 2, 1, 2) }>(r0, r0),
+                    _mm_shuffle_ps::<{ lanes(3, 3, 3, 3) }>(r1, r1),
+                ),
+                _mm_mul_ps(
+                    _mm_shuffle_ps::<{ lanes(1, 2, 1, 2) }>(r1, r1),
+                    _mm_shuffle_ps::<{ lanes(3, 3, 3, 3) }>(r0, r0),
+                ),
+            );
+            // [c0, c1, c2, c3] and [c4, c5, c4, c5]
+            let c_low = _mm_sub_ps(
+                _mm_mul_ps(
+                    _mm_shuffle_ps::<{ lanes(0, 0, 0, 1) }>(r2, r2),
+                    _mm_shuffle_ps::<{ lanes(1, 2, 3, 2) }>(r3, r3),
+                ),
+                _mm_mul_ps(
+                    _mm_shuffle_ps::<{ lanes(0, 0, 0, 1) }>(r3, r3),
+                    _mm_shuffle_ps::<{ lanes(1, 2, 3, 2) }>(r2, r2),
+                ),
+            );
+            let c_high = _mm_sub_ps(
+                _mm_mul_ps(
+                    _mm_shuffle_ps::<{ lanes(1, 2, 1, 2) }>(r2, r2),
+                    _mm_shuffle_ps::<{ lanes(3, 3, 3, 3) }>(r3, r3),
+                ),
+                _mm_mul_ps(
+                    _mm_shuffle_ps::<{ lanes(1, 2, 1, 2) }>(r3, r3),
+                    _mm_shuffle_ps::<{ lanes(3, 3, 3, 3) }>(r2, r2),
+                ),
+            );
+
+            let k0 = _mm_shuffle_ps::<{ lanes(0, 0, 0, 0) }>(c_low, s_low);
+            let k1 = _mm_shuffle_ps::<{ lanes(1, 1, 1, 1) }>(c_low, s_low);
+            let k2 = _mm_shuffle_ps::<{ lanes(2, 2, 2, 2) }>(c_low, s_low);
+            let k3 = _mm_shuffle_ps::<{ lanes(3, 3, 3, 3) }>(c_low, s_low);
+            let k4 = _mm_shuffle_ps::<{ lanes(0, 0, 0, 0) }>(c_high, s_high);
+            let k5 = _mm_shuffle_ps::<{ lanes(1, 1, 1, 1) }>(c_high, s_high);
+
+            let column =
+                |t01: __m128, t23: __m128| _mm_shuffle_ps::<{ lanes(0, 2, 0, 2) }>(t01, t23);
+            let v0 = column(
+                _mm_shuffle_ps::<{ lanes(0, 0, 0, 0) }>(r1, r0),
+                _mm_shuffle_ps::<{ lanes(0, 0, 0, 0) }>(r3, r2),
+            );
+            let v1 = column(
+                _mm_shuffle_ps::<{ lanes(1, 1, 1, 1) }>(r1, r0),
+                _mm_shuffle_ps::<{ lanes(1, 1, 1, 1) }>(r3, r2),
+            );
+            let v2 = column(
+                _mm_shuffle_ps::<{ lanes(2, 2, 2, 2) }>(r1, r0),
+                _mm_shuffle_ps::<{ lanes(2, 2, 2, 2) }>(r3, r2),
+            );
+            let v3 = column(
+                _mm_shuffle_ps::<{ lanes(3, 3, 3, 3) }>(r1, r0),
+                _mm_shuffle_ps::<{ lanes(3, 3, 3, 3) }>(r3, r2),
+            );
+
+            let row = |va: __m128, ka: __m128, vb: __m128, kb: __m128, vc: __m128, kc: __m128| {
+                _mm_add_ps(
+                    _mm_sub_ps(_mm_mul_ps(va, ka), _mm_mul_ps(vb, kb)),
+                    _mm_mul_ps(vc, kc),
+                )
+            };
+            let even = _mm_setr_ps(1.0, -1.0, 1.0, -1.0);
+            let odd = _mm_setr_ps(-1.0, 1.0, -1.0, 1.0);
+            let adjugate = [
+                _mm_mul_ps(row(v1, k5, v2, k4, v3, k3), even),
+                _mm_mul_ps(row(v0, k5, v2, k2, v3, k1), odd),
+                _mm_mul_ps(row(v0, k4, v1, k2, v3, k0), even),
+                _mm_mul_ps(row(v0, k3, v1, k1, v2, k0), odd),
+            ];
+
+            let mut res = [[0.0; 4]; 4];
+            for (values, out) in adjugate.iter().zip(res.iter_mut()) {
+                _mm_storeu_ps(out.as_mut_ptr(), *values);
+            }
+            let det = rows[0][0] * res[0][0]
+                + rows[0][1] * res[1][0]
+                + rows[0][2] * res[2][0]
+                + rows[0][3] * res[3][0];
+            if singular(rows, &res[3], det) {
+                return Err(MatrixError::NotInvertible);
+            }
+            let scale = _mm_set1_ps(1.0 / det);
+            for out in res.iter_mut() {
+                _mm_storeu_ps(
+                    out.as_mut_ptr(),
+                    _mm_mul_ps(_mm_loadu_ps(out.as_ptr()), scale),
+                );
+            }
+            Ok(res)
+        }
+    }
+}
+
+#[cfg(not(target_arch = "x86_64"))]
+mod imp {
+    pub use super::scalar::{inverse, mul, transpose};
+}
+
+/// lane by lane mirror of the SIMD code above, x86_64 only uses it to test against
+#[cfg_attr(all(target_arch = "x86_64", not(test)), allow(dead_code))]
+mod scalar {
+    use super::{MatrixError, Rows, singular};
+
+    pub fn mul(lhs: &Rows, rhs: &Rows) -> Rows {
+        let mut res = [[0.0; 4]; 4];
+        for (row, out) in lhs.iter().zip(res.iter_mut()) {
+            for (col, value) in out.iter_mut().enumerate() {
+                *value = row[0] * rhs[0][col]
+                    + row[1] * rhs[1][col]
+                    + row[2] * rhs[2][col]
+                    + row[3] * rhs[3][col];
+            }
+        }
+        res
+    }
+
+    pub fn transpose(rows: &Rows) -> Rows {
+        let mut res = [[0.0; 4]; 4];
+        for (row, values) in rows.iter().enumerate() {
+            for (col, value) in values.iter().enumerate() {
+                res[col][row] = *value;
+            }
+        }
+        res
+    }
+
+    pub fn inverse(rows: &Rows) -> Result<Rows, MatrixError> {
+        let a = rows;
+        let s = [
+            a[0][0] * a[1][1] - a[1][0] * a[0][1],
+            a[0][0] * a[1][2] - a[1][0] * a[0][2],
+            a[0][0] * a[1][3] - a[1][0] * a[0][3],
+            a[0][1] * a[1][2] - a[1][1] * a[0][2],
+            a[0][1] * a[1][3] - a[1][1] * a[0][3],
+            a[0][2] * a[1][3] - a[1][2] * a[0][3],
+        ];
+        let c = [
+            a[2][0] * a[3][1] - a[3][0] * a[2][1],
+            a[2][0] * a[3][2] - a[3][0] * a[2][2],
+            a[2][0] * a[3][3] - a[3][0] * a[2][3],
+            a[2][1] * a[3][2] - a[3][1] * a[2][2],
+            a[2][1] * a[3][3] - a[3][1] * a[2][3],
+            a[2][2] * a[3][3] - a[3][2] * a[2][3],
+        ];
+        let k = |n: usize| [c[n], c[n], s[n], s[n]];
+        let v = |j: usize| [a[1][j], a[0][j], a[3][j], a[2][j]];
+        let row = |va: [f32; 4],
+                   ka: [f32; 4],
+                   vb: [f32; 4],
+                   kb: [f32; 4],
+                   vc: [f32; 4],
+                   kc: [f32; 4],
+                   sign: [f32; 4]| {
+            std::array::from_fn(|i| (va[i] * ka[i] - vb[i] * kb[i] + vc[i] * kc[i]) * sign[i])
+        };
+        let even = [1.0, -1.0, 1.0, -1.0];
+        let odd = [-1.0, 1.0, -1.0, 1.0];
+        let mut res = [
+            row(v(1), k(5), v(2), k(4), v(3), k(3), even),
+            row(v(0), k(5), v(2), k(2), v(3), k(1), odd),
+            row(v(0), k(4), v(1), k(2), v(3), k(0), even),
+            row(v(0), k(3), v(1), k(1), v(2), k(0), odd),
+        ];
+        let det =
+            a[0][0] * res[0][0] + a[0][1] * res[1][0] + a[0][2] * res[2][0] + a[0][3] * res[3][0];
+        if singular(a, &res[3], det) {
+            return Err(MatrixError::NotInvertible);
+        }
+        let scale = 1.0 / det;
+        for value in res.iter_mut().flatten() {
+            *value *= scale;
+        }
+        Ok(res)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::{
+        angle::Deg,
+        common::{ApproxEq, Dimension3, Exact, Tolerance},
+        quaternion::Quaternion,
+        vector::Vector,
+    };
+
+    fn samples() -> Vec<Matrix<4>> {
+        vec![
+            Matrix::identity(),
+            Matrix::<4>::from_trs(
+                Vector::<3>::new(1.5, -2.0, 7.25),
+                Quaternion::from_euler(Deg(30.0), Deg(-45.0), Deg(110.0)),
+                Vector::<3>::new(0.5, 2.0, -3.0),
+            ),
+            Matrix::<4>::perspective(Deg(60.0), 16.0 / 9.0, 0.1, 100.0)
+                * Matrix::<4>::look_at(
+                    Vector::<3>::new(3.0, 4.0, 5.0),
+                    Vector::<3>::zero(),
+                    Vector::<3>::new(0.0, 1.0, 0.0),
+                ),
+            from_rows([
+                [2.0, -1.0, 0.5, 3.0],
+                [0.25, 4.0, -2.0, 1.0],
+                [1.0, 1.0, 1.0, -1.0],
+                [-3.0, 0.5, 2.0, 0.125],
+            ]),
+            Matrix::<4>::scale(1e-4, 1e-4, 1e-4),
+            // translations far larger than the rest of the matrix
+            Matrix::<4>::translate(100.0, 100.0, 100.0),
+            Matrix::<4>::look_at(
+                Vector::<3>::new(200.0, 150.0, 300.0),
+                Vector::<3>::new(200.0, 150.0, 0.0),
+                Vector::<3>::new(0.0, 1.0, 0.0),
+            ),
+            Matrix::<4>::from_trs(
+                Vector::<3>::new(1e4, -2e4, 5e3),
+                Quaternion::from_euler(Deg(-20.0), Deg(75.0), Deg(5.0)),
+                Vector::<3>::new(0.25, 0.25, 4.0),
+            ),
+            Matrix::<4>::perspective(Deg(45.0), 1.5, 10.0, 2000.0)
+                * Matrix::<4>::look_at(
+                    Vector::<3>::new(500.0, 800.0, -600.0),
+                    Vector::<3>::new(400.0, 0.0, 0.0),
+                    Vector::<3>::new(0.0, 1.0, 0.0),
+                ),
+        ]
+    }
+
+    /// agrees up to rounding relative to the largest entry
+    fn close(a: Matrix<4>, reference: Matrix<4, f64>) -> bool {
+        let largest = (0..4)
+            .flat_map(|row| reference[row])
+            .fold(0.0, |largest: f64, value| largest.max(value.abs()));
+        a.cast::<f64>()
+            .approx_eq_with(&reference, Tolerance::Absolute(largest * 1e-5))
+    }
+
+    /// bitwise comparison of raw rows
+    fn exact(rows: Rows) -> Exact<Matrix<4>> {
+        Exact(from_rows(rows))
+    }
+
+    #[test]
+    fn simd_matches_scalar() {
+        for a in samples() {
+            let a_rows = rows(&a);
+            assert_eq!(
+                exact(imp::transpose(&a_rows)),
+                exact(scalar::transpose(&a_rows))
+            );
+            assert_eq!(
+                imp::inverse(&a_rows).map(exact),
+                scalar::inverse(&a_rows).map(exact)
+            );
+            for b in samples() {
+                let b_rows = rows(&b);
+                assert_eq!(
+                    exact(imp::mul(&a_rows, &b_rows)),
+                    exact(scalar::mul(&a_rows, &b_rows))
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn matches_generic() {
+        for a in samples() {
+            assert_eq!(a.transpose_simd(), a.transpose());
+            // LU in f64 as the reference
+            let inverse = a.inverse_simd().unwrap();
+            assert!(close(inverse, a.cast::<f64>().inverse().unwrap()));
+            assert_eq!(a.inverse(), Ok(inverse));
+            for b in samples() {
+                let product = a.cast::<f64>() * b.cast::<f64>();
+                assert!(close(a.mul_simd(&b), product));
+                assert_eq!(Exact(a * b), Exact(a.mul_simd(&b)));
+            }
+        }
+    }
+
+    #[test]
+    fn singular_matrices() {
+        let flat = Matrix::<4>::scale(1.0, 1.0, 0.0);
+        assert_eq!(flat.inverse_simd(), Err(MatrixError::NotInvertible));
+        assert_eq!(
+            scalar::inverse(&rows(&flat)),
+            Err(MatrixError::NotInvertible)
+        );
+        assert_eq!(
+            Matrix::<4>::new().inverse(),
+            Err(MatrixError::NotInvertible)
+        );
+        // the last column is the sum of the first two
+        let dependent = from_rows([
+            [1.0, 2.0, 0.0, 3.0],
+            [0.5, -1.0, 0.0, -0.5],
+            [0.0, 0.0, 1.0, 0.0],
+            [2.0, 0.25, 0.0, 2.25],
+        ]);
+        assert_eq!(dependent.inverse(), Err(MatrixError::NotInvertible));
+    }
+
+    #[test]
+    fn far_translations_stay_invertible() {
+        for distance in [1e2, 1e4, 1e6] {
+            let translate = Matrix::<4>::translate(distance, -distance, distance);
+            let inverse = translate.inverse().unwrap();
+            assert_eq!(
+                inverse,
+                Matrix::<4>::translate(-distance, distance, -distance)
+            );
+        }
+        let small = Matrix::<4>::translate(1e5, 0.0, 0.0) * Matrix::<4>::scale(1e-4, 1e-4, 1e-4);
+        assert!(small.inverse().is_ok());
+    }
+}
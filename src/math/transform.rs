@@ -0,0 +1,559 @@
+use std::{
+    error::Error,
+    fmt::{self, Display},
+    ops::Mul,
+};
+
+use super::{
+    angle::Rad,
+    common::{Dimension2, Dimension3, Scalar},
+    matrix::Matrix,
+    quaternion::Quaternion,
+    vector::Vector,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransformError {
+    /// a scale axis is zero
+    NotInvertible,
+    /// the result needs shear, e.g. a rotation after a non-uniform scale
+    Shear,
+}
+
+impl Display for TransformError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TransformError::NotInvertible => write!(f, "transform is not invertible"),
+            TransformError::Shear => write!(f, "result needs shear, which a transform cannot hold"),
+        }
+    }
+}
+
+impl Error for TransformError {}
+
+/// columns agree up to rounding relative to their largest entry
+fn same_matrix<const D: usize, T: Scalar>(a: &Matrix<D, T>, b: &Matrix<D, T>) -> bool {
+    (0..D).all(|col| {
+        let largest = (0..D).fold(T::ZERO, |largest, row| largest.max(b[row][col].abs()));
+        (0..D).all(|row| (a[row][col] - b[row][col]).abs() <= T::EPSILON * largest)
+    })
+}
+
+/// scale, then rotate, then translate, with scale and rotation applied around `pivot`
+///
+/// `compose` and `inverse` fail when the result has shear, which happens once a rotation
+/// meets a non-uniform scale. `a * b` gives the exact product as a matrix in every case.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Transform2D<T: Scalar = f32> {
+    pub translation: Vector<2, T>,
+    /// same direction as `Matrix::<3>::rotate`
+    pub rotation: Rad<T>,
+    pub scale: Vector<2, T>,
+    pub pivot: Vector<2, T>,
+}
+
+impl<T: Scalar> Transform2D<T> {
+    pub fn identity() -> Self {
+        Self {
+            translation: Vector::zero(),
+            rotation: Rad(T::ZERO),
+            scale: Vector::splat(T::ONE),
+            pivot: Vector::zero(),
+        }
+    }
+
+    pub fn from_translation(x: T, y: T) -> Self {
+        Self {
+            translation: Vector::<2, T>::new(x, y),
+            ..Self::identity()
+        }
+    }
+
+    pub fn from_rotation(rotation: impl Into<Rad<T>>) -> Self {
+        Self {
+            rotation: rotation.into(),
+            ..Self::identity()
+        }
+    }
+
+    pub fn from_scale(x: T, y: T) -> Self {
+        Self {
+            scale: Vector::<2, T>::new(x, y),
+            ..Self::identity()
+        }
+    }
+
+    pub fn with_pivot(self, pivot: Vector<2, T>) -> Self {
+        Self { pivot, ..self }
+    }
+
+    /// T(translation) * T(pivot) * R * S * T(-pivot)
+    pub fn to_matrix(self) -> Matrix<3, T> {
+        Matrix::<3, T>::translate(
+            self.translation[0] + self.pivot[0],
+            self.translation[1] + self.pivot[1],
+        ) * Matrix::<3, T>::rotate(self.rotation)
+            * Matrix::<3, T>::scale(self.scale[0], self.scale[1])
+            * Matrix::<3, T>::translate(-self.pivot[0], -self.pivot[1])
+    }
+
+    pub fn transform_point(&self, point: Vector<2, T>) -> Vector<2, T> {
+        self.to_matrix().transform_point(point)
+    }
+
+    pub fn transform_vector(&self, vector: Vector<2, T>) -> Vector<2, T> {
+        self.to_matrix().transform_vector(vector)
+    }
+
+    /// same transform expressed with the pivot folded into the translation
+    pub fn without_pivot(&self) -> Self {
+        Self {
+            translation: self.translation + self.pivot - self.linear().transform_vector(self.pivot),
+            pivot: Vector::zero(),
+            ..*self
+        }
+    }
+
+    /// the pivot of the result is zero
+    pub fn inverse(&self) -> Result<Self, TransformError> {
+        let this = self.without_pivot();
+        let scale = Vector::<2, T>::new(T::ONE / this.scale[0], T::ONE / this.scale[1]);
+        if !(0..2).all(|i| scale[i].abs() < T::INFINITY) {
+            return Err(TransformError::NotInvertible);
+        }
+        let linear =
+            Matrix::<3, T>::scale(scale[0], scale[1]) * Matrix::<3, T>::rotate(-this.rotation);
+        let inverse = Self {
+            translation: -linear.transform_vector(this.translation),
+            rotation: -this.rotation,
+            scale,
+            pivot: Vector::zero(),
+        };
+        // S^-1 * R^-1 is only a rotation followed by a scale when the two commute
+        if !same_matrix(&inverse.linear(), &linear) {
+            return Err(TransformError::Shear);
+        }
+        Ok(inverse)
+    }
+
+    /// `self` applied after `rhs` as one transform, the pivot of the result is zero
+    pub fn compose(&self, rhs: &Self) -> Result<Self, TransformError> {
+        let lhs = self.without_pivot();
+        let rhs = rhs.without_pivot();
+        let composed = Self {
+            translation: lhs.transform_point(rhs.translation),
+            rotation: lhs.rotation + rhs.rotation,
+            scale: Vector::<2, T>::new(lhs.scale[0] * rhs.scale[0], lhs.scale[1] * rhs.scale[1]),
+            pivot: Vector::zero(),
+        };
+        // the translation is exact whenever the linear parts agree
+        if !same_matrix(&composed.linear(), &(lhs.linear() * rhs.linear())) {
+            return Err(TransformError::Shear);
+        }
+        Ok(composed)
+    }
+
+    /// R * S without translation and pivot
+    fn linear(&self) -> Matrix<3, T> {
+        Matrix::<3, T>::rotate(self.rotation) * Matrix::<3, T>::scale(self.scale[0], self.scale[1])
+    }
+
+    /// linear blend of every component, the rotation takes the direct path between the two angles
+    pub fn lerp(&self, other: &Self, t: T) -> Self {
+        Self {
+            translation: self.translation.lerp(&other.translation, t),
+            rotation: self.rotation + (other.rotation - self.rotation) * t,
+            scale: self.scale.lerp(&other.scale, t),
+            pivot: self.pivot.lerp(&other.pivot, t),
+        }
+    }
+}
+
+impl<T: Scalar> Default for Transform2D<T> {
+    fn default() -> Self {
+        Self::identity()
+    }
+}
+
+/// `a * b` applies b first and then a, see `compose` to keep a transform
+impl<T: Scalar> Mul for Transform2D<T> {
+    type Output = Matrix<3, T>;
+    fn mul(self, rhs: Self) -> Self::Output {
+        self.to_matrix() * rhs.to_matrix()
+    }
+}
+
+impl<T: Scalar> From<Transform2D<T>> for Matrix<3, T> {
+    fn from(transform: Transform2D<T>) -> Self {
+        transform.to_matrix()
+    }
+}
+
+/// 3D counterpart of `Transform2D`, `compose` and `inverse` fail the same way
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Transform3D<T: Scalar = f32> {
+    pub translation: Vector<3, T>,
+    pub rotation: Quaternion<T>,
+    pub scale: Vector<3, T>,
+    pub pivot: Vector<3, T>,
+}
+
+impl<T: Scalar> Transform3D<T> {
+    pub fn identity() -> Self {
+        Self {
+            translation: Vector::zero(),
+            rotation: Quaternion::identity(),
+            scale: Vector::splat(T::ONE),
+            pivot: Vector::zero(),
+        }
+    }
+
+    pub fn from_translation(x: T, y: T, z: T) -> Self {
+        Self {
+            translation: Vector::<3, T>::new(x, y, z),
+            ..Self::identity()
+        }
+    }
+
+    pub fn from_rotation(rotation: Quaternion<T>) -> Self {
+        Self {
+            rotation,
+            ..Self::identity()
+        }
+    }
+
+    pub fn from_scale(x: T, y: T, z: T) -> Self {
+        Self {
+            scale: Vector::<3, T>::new(x, y, z),
+            ..Self::identity()
+        }
+    }
+
+    pub fn with_pivot(self, pivot: Vector<3, T>) -> Self {
+        Self { pivot, ..self }
+    }
+
+    /// T(translation) * T(pivot) * R * S * T(-pivot)
+    pub fn to_matrix(self) -> Matrix<4, T> {
+        let origin = self.translation + self.pivot;
+        Matrix::<4, T>::translate(origin[0], origin[1], origin[2])
+            * Matrix::<4, T>::from(self.rotation)
+            * Matrix::<4, T>::scale(self.scale[0], self.scale[1], self.scale[2])
+            * Matrix::<4, T>::translate(-self.pivot[0], -self.pivot[1], -self.pivot[2])
+    }
+
+    pub fn transform_point(&self, point: Vector<3, T>) -> Vector<3, T> {
+        self.to_matrix().transform_point(point)
+    }
+
+    pub fn transform_vector(&self, vector: Vector<3, T>) -> Vector<3, T> {
+        self.to_matrix().transform_vector(vector)
+    }
+
+    /// same transform expressed with the pivot folded into the translation
+    pub fn without_pivot(&self) -> Self {
+        let scaled = Vector::<3, T>::new(
+            self.pivot[0] * self.scale[0],
+            self.pivot[1] * self.scale[1],
+            self.pivot[2] * self.scale[2],
+        );
+        Self {
+            translation: self.translation + self.pivot - self.rotation.rotate(scaled),
+            pivot: Vector::zero(),
+            ..*self
+        }
+    }
+
+    /// the pivot of the result is zero
+    pub fn inverse(&self) -> Result<Self, TransformError> {
+        let this = self.without_pivot();
+        let rotation = this.rotation.inverse();
+        let scale = Vector::<3, T>::new(
+            T::ONE / this.scale[0],
+            T::ONE / this.scale[1],
+            T::ONE / this.scale[2],
+        );
+        if !(0..3).all(|i| scale[i].abs() < T::INFINITY) {
+            return Err(TransformError::NotInvertible);
+        }
+        let rotated = rotation.rotate(-this.translation);
+        let linear =
+            Matrix::<4, T>::scale(scale[0], scale[1], scale[2]) * Matrix::<4, T>::from(rotation);
+        let inverse = Self {
+            translation: Vector::<3, T>::new(
+                rotated[0] * scale[0],
+                rotated[1] * scale[1],
+                rotated[2] * scale[2],
+            ),
+            rotation,
+            scale,
+            pivot: Vector::zero(),
+        };
+        // S^-1 * R^-1 is only a rotation followed by a scale when the two commute
+        if !same_matrix(&inverse.linear(), &linear) {
+            return Err(TransformError::Shear);
+        }
+        Ok(inverse)
+    }
+
+    /// `self` applied after `rhs` as one transform, the pivot of the result is zero
+    pub fn compose(&self, rhs: &Self) -> Result<Self, TransformError> {
+        let lhs = self.without_pivot();
+        let rhs = rhs.without_pivot();
+        let composed = Self {
+            translation: lhs.transform_point(rhs.translation),
+            rotation: (lhs.rotation * rhs.rotation).normalize(),
+            scale: Vector::<3, T>::new(
+                lhs.scale[0] * rhs.scale[0],
+                lhs.scale[1] * rhs.scale[1],
+                lhs.scale[2] * rhs.scale[2],
+            ),
+            pivot: Vector::zero(),
+        };
+        // the translation is exact whenever the linear parts agree
+        if !same_matrix(&composed.linear(), &(lhs.linear() * rhs.linear())) {
+            return Err(TransformError::Shear);
+        }
+        Ok(composed)
+    }
+
+    /// R * S without translation and pivot
+    fn linear(&self) -> Matrix<4, T> {
+        Matrix::<4, T>::from(self.rotation)
+            * Matrix::<4, T>::scale(self.scale[0], self.scale[1], self.scale[2])
+    }
+
+    /// lerps translation, scale and pivot, slerps the rotation
+    pub fn lerp(&self, other: &Self, t: T) -> Self {
+        Self {
+            translation: self.translation.lerp(&other.translation, t),
+            rotation: self.rotation.slerp(&other.rotation, t),
+            scale: self.scale.lerp(&other.scale, t),
+            pivot: self.pivot.lerp(&other.pivot, t),
+        }
+    }
+}
+
+impl<T: Scalar> Default for Transform3D<T> {
+    fn default() -> Self {
+        Self::identity()
+    }
+}
+
+/// `a * b` applies b first and then a, see `compose` to keep a transform
+impl<T: Scalar> Mul for Transform3D<T> {
+    type Output = Matrix<4, T>;
+    fn mul(self, rhs: Self) -> Self::Output {
+        self.to_matrix() * rhs.to_matrix()
+    }
+}
+
+impl<T: Scalar> From<Transform3D<T>> for Matrix<4, T> {
+    fn from(transform: Transform3D<T>) -> Self {
+        transform.to_matrix()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::{
+        angle::Deg,
+        common::{ApproxEq, Tolerance},
+    };
+
+    fn v2(x: f32, y: f32) -> Vector<2> {
+        Vector::<2>::new(x, y)
+    }
+
+    fn v3(x: f32, y: f32, z: f32) -> Vector<3> {
+        Vector::<3>::new(x, y, z)
+    }
+
+    fn close<const D: usize>(a: Matrix<D>, b: Matrix<D>) -> bool {
+        a.approx_eq_with(&b, Tolerance::Absolute(1e-4))
+    }
+
+    fn uniform_2d() -> [Transform2D; 3] {
+        [
+            Transform2D::identity(),
+            Transform2D {
+                translation: v2(10.0, -4.0),
+                rotation: Deg(30.0).into(),
+                scale: Vector::splat(2.0),
+                pivot: v2(1.0, 2.0),
+            },
+            Transform2D {
+                translation: v2(-3.0, 7.0),
+                rotation: Deg(-100.0).into(),
+                scale: Vector::splat(-0.5),
+                pivot: v2(-5.0, 0.0),
+            },
+        ]
+    }
+
+    fn uniform_3d() -> [Transform3D; 3] {
+        [
+            Transform3D::identity(),
+            Transform3D {
+                translation: v3(1.0, 2.0, 3.0),
+                rotation: Quaternion::from_euler(Deg(10.0), Deg(20.0), Deg(30.0)),
+                scale: Vector::splat(3.0),
+                pivot: v3(0.5, -1.0, 2.0),
+            },
+            Transform3D {
+                translation: v3(-4.0, 0.0, 6.0),
+                rotation: Quaternion::from_euler(Deg(-70.0), Deg(5.0), Deg(140.0)),
+                scale: Vector::splat(0.25),
+                pivot: v3(0.0, 3.0, 0.0),
+            },
+        ]
+    }
+
+    #[test]
+    fn pivot_stays_in_place() {
+        let transform = Transform2D::from_rotation(Deg(90.0)).with_pivot(v2(5.0, 5.0));
+        assert_eq!(transform.transform_point(v2(5.0, 5.0)), v2(5.0, 5.0));
+        assert_eq!(transform.transform_point(v2(6.0, 5.0)), v2(5.0, 6.0));
+        let scaled = Transform2D::from_scale(2.0, 3.0).with_pivot(v2(1.0, 1.0));
+        assert_eq!(scaled.transform_point(v2(1.0, 1.0)), v2(1.0, 1.0));
+        assert_eq!(scaled.transform_point(v2(2.0, 2.0)), v2(3.0, 4.0));
+        assert!(close(
+            scaled.without_pivot().to_matrix(),
+            scaled.to_matrix()
+        ));
+
+        for transform in uniform_3d() {
+            let moved = transform.transform_point(transform.pivot);
+            assert!(moved.approx_eq_with(
+                &(transform.pivot + transform.translation),
+                Tolerance::Absolute(1e-5)
+            ));
+            assert!(close(
+                transform.without_pivot().to_matrix(),
+                transform.to_matrix()
+            ));
+        }
+    }
+
+    #[test]
+    fn composition_matches_the_matrix_product() {
+        for a in uniform_2d() {
+            for b in uniform_2d() {
+                let composed = a.compose(&b).unwrap();
+                assert!(close(composed.to_matrix(), a.to_matrix() * b.to_matrix()));
+                assert!(close(a * b, a.to_matrix() * b.to_matrix()));
+            }
+        }
+        for a in uniform_3d() {
+            for b in uniform_3d() {
+                let composed = a.compose(&b).unwrap();
+                assert!(close(composed.to_matrix(), a.to_matrix() * b.to_matrix()));
+            }
+        }
+        // non-uniform scale is fine as long as nothing rotates after it
+        let stretch = Transform2D::from_scale(2.0, 0.5);
+        let moved = Transform2D::from_translation(3.0, 4.0);
+        let composed = stretch.compose(&moved).unwrap();
+        assert!(close(
+            composed.to_matrix(),
+            stretch.to_matrix() * moved.to_matrix()
+        ));
+    }
+
+    #[test]
+    fn non_uniform_scale_after_rotation_has_shear() {
+        let stretch = Transform2D::from_scale(2.0, 0.5);
+        let turn = Transform2D::from_rotation(Deg(30.0));
+        assert_eq!(stretch.compose(&turn), Err(TransformError::Shear));
+        // the other order is a plain TRS
+        assert!(turn.compose(&stretch).is_ok());
+        // the matrix product is exact either way
+        let product = stretch * turn;
+        assert!(close(product, stretch.to_matrix() * turn.to_matrix()));
+
+        let stretch = Transform3D::from_scale(1.0, 2.0, 3.0);
+        let turn =
+            Transform3D::from_rotation(Quaternion::from_euler(Deg(0.0), Deg(45.0), Deg(0.0)));
+        assert_eq!(stretch.compose(&turn), Err(TransformError::Shear));
+        assert!(turn.compose(&stretch).is_ok());
+    }
+
+    #[test]
+    fn inverse_round_trips() {
+        for transform in uniform_2d() {
+            let inverse = transform.inverse().unwrap();
+            assert!(close(
+                inverse.to_matrix() * transform.to_matrix(),
+                Matrix::identity()
+            ));
+            assert!(close(
+                transform.to_matrix() * inverse.to_matrix(),
+                Matrix::identity()
+            ));
+        }
+        for transform in uniform_3d() {
+            let inverse = transform.inverse().unwrap();
+            assert!(close(
+                inverse.to_matrix() * transform.to_matrix(),
+                Matrix::identity()
+            ));
+        }
+        // non-uniform scale inverts without rotation
+        let stretch = Transform2D::from_scale(4.0, 0.5).with_pivot(v2(2.0, 2.0));
+        let inverse = stretch.inverse().unwrap();
+        assert!(close(
+            inverse.to_matrix() * stretch.to_matrix(),
+            Matrix::identity()
+        ));
+    }
+
+    #[test]
+    fn inverse_failures() {
+        let flat = Transform2D::from_scale(1.0, 0.0);
+        assert_eq!(flat.inverse(), Err(TransformError::NotInvertible));
+        let flat = Transform3D::from_scale(0.0, 1.0, 1.0);
+        assert_eq!(flat.inverse(), Err(TransformError::NotInvertible));
+        let sheared = Transform2D {
+            rotation: Deg(45.0).into(),
+            scale: v2(2.0, 1.0),
+            ..Transform2D::identity()
+        };
+        assert_eq!(sheared.inverse(), Err(TransformError::Shear));
+        let sheared = Transform3D {
+            rotation: Quaternion::from_euler(Deg(30.0), Deg(0.0), Deg(0.0)),
+            scale: v3(1.0, 1.0, 5.0),
+            ..Transform3D::identity()
+        };
+        assert_eq!(sheared.inverse(), Err(TransformError::Shear));
+    }
+
+    #[test]
+    fn interpolation() {
+        let [_, a, b] = uniform_2d();
+        assert_eq!(a.lerp(&b, 0.0), a);
+        assert_eq!(a.lerp(&b, 1.0), b);
+        let half = a.lerp(&b, 0.5);
+        assert_eq!(half.translation, v2(3.5, 1.5));
+        assert_eq!(half.rotation, Rad::from(Deg(-35.0)));
+        assert_eq!(half.scale, Vector::splat(0.75));
+        assert_eq!(half.pivot, v2(-2.0, 1.0));
+
+        let start = Transform3D::from_translation(0.0, 0.0, 0.0);
+        let end = Transform3D {
+            translation: v3(2.0, 4.0, 6.0),
+            rotation: Quaternion::from_euler(Deg(0.0), Deg(90.0), Deg(0.0)),
+            ..Transform3D::identity()
+        };
+        let half = start.lerp(&end, 0.5);
+        assert_eq!(half.translation, v3(1.0, 2.0, 3.0));
+        let expected: Quaternion = Quaternion::from_euler(Deg(0.0), Deg(45.0), Deg(0.0));
+        assert!(close(
+            Matrix::<4>::from(half.rotation),
+            Matrix::from(expected)
+        ));
+        assert_eq!(start.lerp(&end, 1.0).translation, end.translation);
+    }
+}
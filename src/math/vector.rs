@@ -1,29 +1,148 @@
-use std::ops::{Index, IndexMut};
+use std::ops::{
+    Add, AddAssign, Div, DivAssign, Index, IndexMut, Mul, MulAssign, Neg, Sub, SubAssign,
+};
 
-use super::common::{Dimension3, Dimension4, FuzzyEq};
+use super::common::{
+    ApproxEq, Dimension2, Dimension3, Dimension4, FuzzyEq, Scalar, ToBits, Tolerance,
+};
 
 #[derive(Copy, Clone, Debug)]
-pub struct Vector<const D: usize> {
-    data: [f32; D],
+pub struct Vector<const D: usize, T: Scalar = f32> {
+    data: [T; D],
 }
 
-impl<const D: usize> Index<usize> for Vector<D> {
-    type Output = f32;
+impl<const D: usize, T: Scalar> Index<usize> for Vector<D, T> {
+    type Output = T;
     fn index(&self, index: usize) -> &Self::Output {
         &self.data[index]
     }
 }
 
-impl<const D: usize> IndexMut<usize> for Vector<D> {
+impl<const D: usize, T: Scalar> IndexMut<usize> for Vector<D, T> {
     fn index_mut(&mut self, index: usize) -> &mut Self::Output {
         &mut self.data[index]
     }
 }
 
-impl<const D: usize> FuzzyEq for Vector<D> {
+impl<const D: usize, T: Scalar> Vector<D, T> {
+    pub fn zero() -> Self {
+        Self { data: [T::ZERO; D] }
+    }
+
+    pub fn splat(value: T) -> Self {
+        Self { data: [value; D] }
+    }
+
+    pub fn to_array(self) -> [T; D] {
+        self.data
+    }
+
+    pub fn dot(&self, other: &Self) -> T {
+        let mut sum = T::ZERO;
+        for i in 0..D {
+            sum += self[i] * other[i];
+        }
+        sum
+    }
+
+    pub fn length_squared(&self) -> T {
+        self.dot(self)
+    }
+
+    pub fn length(&self) -> T {
+        self.length_squared().sqrt()
+    }
+
+    /// returns the zero vector when every component is zero or denormal, short and long
+    /// vectors still normalize
+    pub fn normalize(&self) -> Self {
+        let length = self.length();
+        if length >= T::MIN_POSITIVE && length < T::INFINITY {
+            return *self / length;
+        }
+        // the squares under- or overflowed, divide by the largest component first
+        let largest = self
+            .data
+            .iter()
+            .fold(T::ZERO, |largest, value| largest.max(value.abs()));
+        if largest < T::MIN_POSITIVE {
+            return Self::zero();
+        }
+        let scaled = *self / largest;
+        scaled / scaled.length()
+    }
+
+    pub fn distance(&self, other: &Self) -> T {
+        (*other - *self).length()
+    }
+
+    /// t = 0 gives self, t = 1 gives other
+    pub fn lerp(&self, other: &Self, t: T) -> Self {
+        *self + (*other - *self) * t
+    }
+
+    /// component-wise minimum
+    pub fn min(&self, other: &Self) -> Self {
+        let mut res = *self;
+        for i in 0..D {
+            res[i] = res[i].min(other[i]);
+        }
+        res
+    }
+
+    /// component-wise maximum
+    pub fn max(&self, other: &Self) -> Self {
+        let mut res = *self;
+        for i in 0..D {
+            res[i] = res[i].max(other[i]);
+        }
+        res
+    }
+
+    /// converts to another scalar type, rounding when narrowing
+    pub fn cast<U: Scalar>(self) -> Vector<D, U> {
+        Vector {
+            data: self.data.map(|value| U::from_f64(value.to_f64())),
+        }
+    }
+}
+
+impl<const D: usize> Vector<D, f64> {
+    /// lossy conversion for GPU upload
+    pub fn to_f32(self) -> Vector<D, f32> {
+        self.cast()
+    }
+}
+
+/// lossless widening
+impl<const D: usize> From<Vector<D, f32>> for Vector<D, f64> {
+    fn from(vector: Vector<D, f32>) -> Self {
+        vector.cast()
+    }
+}
+
+impl<const D: usize, T: Scalar> Default for Vector<D, T> {
+    fn default() -> Self {
+        Self::zero()
+    }
+}
+
+impl<const D: usize, T: Scalar> From<[T; D]> for Vector<D, T> {
+    fn from(data: [T; D]) -> Self {
+        Self { data }
+    }
+}
+
+impl<const D: usize, T: Scalar> From<Vector<D, T>> for [T; D] {
+    fn from(vector: Vector<D, T>) -> Self {
+        vector.data
+    }
+}
+
+impl<const D: usize, T: Scalar> FuzzyEq for Vector<D, T> {
     fn fuzzy_eq(&self, other: &Self) -> bool {
         for i in 0..D {
-            if self[i].fuzzy_eq(&other[i]) == false {
+            if !self[i].fuzzy_eq(&other[i]) {
                 return false;
             }
         }
@@ -31,14 +150,131 @@ impl<const D: usize> FuzzyEq for Vector<D> {
     }
 }
 
-impl<const D: usize> PartialEq for Vector<D> {
+impl<const D: usize, T: Scalar> ApproxEq<T> for Vector<D, T> {
+    fn approx_eq_with(&self, other: &Self, tolerance: Tolerance<T>) -> bool {
+        (0..D).all(|i| self[i].approx_eq_with(&other[i], tolerance))
+    }
+}
+
+impl<const D: usize, T: Scalar> ToBits for Vector<D, T> {
+    type Bits = [T::Bits; D];
+    fn to_bits(&self) -> Self::Bits {
+        self.data.map(|value| value.to_bits())
+    }
+}
+
+/// fuzzy, see `FuzzyEq`, wrap in `Exact` for bitwise equality
+impl<const D: usize, T: Scalar> PartialEq for Vector<D, T> {
     fn eq(&self, other: &Self) -> bool {
         self.fuzzy_eq(other)
     }
 }
 
-impl Dimension4 for Vector<4> {
-    type Value = f32;
+impl<const D: usize, T: Scalar> Add for Vector<D, T> {
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self::Output {
+        let mut res = self;
+        res += rhs;
+        res
+    }
+}
+
+impl<const D: usize, T: Scalar> AddAssign for Vector<D, T> {
+    fn add_assign(&mut self, rhs: Self) {
+        for i in 0..D {
+            self[i] += rhs[i];
+        }
+    }
+}
+
+impl<const D: usize, T: Scalar> Sub for Vector<D, T> {
+    type Output = Self;
+    fn sub(self, rhs: Self) -> Self::Output {
+        let mut res = self;
+        res -= rhs;
+        res
+    }
+}
+
+impl<const D: usize, T: Scalar> SubAssign for Vector<D, T> {
+    fn sub_assign(&mut self, rhs: Self) {
+        for i in 0..D {
+            self[i] -= rhs[i];
+        }
+    }
+}
+
+impl<const D: usize, T: Scalar> Neg for Vector<D, T> {
+    type Output = Self;
+    fn neg(self) -> Self::Output {
+        Self {
+            data: self.data.map(|value| -value),
+        }
+    }
+}
+
+impl<const D: usize, T: Scalar> Mul<T> for Vector<D, T> {
+    type Output = Self;
+    fn mul(self, rhs: T) -> Self::Output {
+        let mut res = self;
+        res *= rhs;
+        res
+    }
+}
+
+impl<const D: usize> Mul<Vector<D, f32>> for f32 {
+    type Output = Vector<D, f32>;
+    fn mul(self, rhs: Vector<D, f32>) -> Self::Output {
+        rhs * self
+    }
+}
+
+impl<const D: usize> Mul<Vector<D, f64>> for f64 {
+    type Output = Vector<D, f64>;
+    fn mul(self, rhs: Vector<D, f64>) -> Self::Output {
+        rhs * self
+    }
+}
+
+impl<const D: usize, T: Scalar> MulAssign<T> for Vector<D, T> {
+    fn mul_assign(&mut self, rhs: T) {
+        for i in 0..D {
+            self[i] *= rhs;
+        }
+    }
+}
+
+impl<const D: usize, T: Scalar> Div<T> for Vector<D, T> {
+    type Output = Self;
+    fn div(self, rhs: T) -> Self::Output {
+        let mut res = self;
+        res /= rhs;
+        res
+    }
+}
+
+impl<const D: usize, T: Scalar> DivAssign<T> for Vector<D, T> {
+    fn div_assign(&mut self, rhs: T) {
+        for i in 0..D {
+            self[i] /= rhs;
+        }
+    }
+}
+
+impl<T: Scalar> Vector<3, T> {
+    pub fn cross(&self, other: &Self) -> Self {
+        Self {
+            data: [
+                self[1] * other[2] - self[2] * other[1],
+                self[2] * other[0] - self[0] * other[2],
+                self[0] * other[1] - self[1] * other[0],
+            ],
+        }
+    }
+}
+
+impl<T: Scalar> Dimension4 for Vector<4, T> {
+    type Value = T;
 
     fn new(x: Self::Value, y: Self::Value, z: Self::Value, w: Self::Value) -> Self {
         Self { data: [x, y, z, w] }
@@ -59,8 +295,8 @@ impl Dimension4 for Vector<4> {
     }
 }
 
-impl Dimension3 for Vector<3> {
-    type Value = f32;
+impl<T: Scalar> Dimension3 for Vector<3, T> {
+    type Value = T;
 
     fn new(x: Self::Value, y: Self::Value, z: Self::Value) -> Self {
         Self { data: [x, y, z] }
@@ -76,3 +312,144 @@ impl Dimension3 for Vector<3> {
         self[2]
     }
 }
+
+impl<T: Scalar> Dimension2 for Vector<2, T> {
+    type Value = T;
+
+    fn new(x: Self::Value, y: Self::Value) -> Self {
+        Self { data: [x, y] }
+    }
+    fn get_x(&self) -> Self::Value {
+        self[0]
+    }
+
+    fn get_y(&self) -> Self::Value {
+        self[1]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::common::Exact;
+
+    fn v3(x: f32, y: f32, z: f32) -> Vector<3> {
+        Vector::<3>::new(x, y, z)
+    }
+
+    #[test]
+    fn arithmetic() {
+        let a = v3(1.0, -2.0, 3.0);
+        let b = v3(4.0, 0.5, -1.0);
+        assert_eq!(Exact(a + b), Exact(v3(5.0, -1.5, 2.0)));
+        assert_eq!(Exact(a - b), Exact(v3(-3.0, -2.5, 4.0)));
+        assert_eq!(Exact(-a), Exact(v3(-1.0, 2.0, -3.0)));
+        assert_eq!(Exact(a * 2.0), Exact(v3(2.0, -4.0, 6.0)));
+        assert_eq!(Exact(2.0 * a), Exact(a * 2.0));
+        assert_eq!(Exact(a / 2.0), Exact(v3(0.5, -1.0, 1.5)));
+
+        let mut c = a;
+        c += b;
+        c -= a;
+        assert_eq!(Exact(c), Exact(b));
+        c *= 4.0;
+        c /= 2.0;
+        assert_eq!(Exact(c), Exact(b * 2.0));
+    }
+
+    #[test]
+    fn products() {
+        let a = v3(1.0, 2.0, 3.0);
+        let b = v3(4.0, -5.0, 6.0);
+        assert_eq!(a.dot(&b), 12.0);
+        assert_eq!(Exact(a.cross(&b)), Exact(v3(27.0, 6.0, -13.0)));
+        assert_eq!(a.cross(&b).dot(&a), 0.0);
+        assert_eq!(Exact(b.cross(&a)), Exact(-a.cross(&b)));
+
+        let x = v3(1.0, 0.0, 0.0);
+        let y = v3(0.0, 1.0, 0.0);
+        assert_eq!(Exact(x.cross(&y)), Exact(v3(0.0, 0.0, 1.0)));
+    }
+
+    #[test]
+    fn lengths() {
+        let a = Vector::<2>::new(3.0, 4.0);
+        assert_eq!(a.length_squared(), 25.0);
+        assert_eq!(a.length(), 5.0);
+        assert_eq!(a.normalize(), Vector::<2>::new(0.6, 0.8));
+        assert_eq!(
+            Exact(Vector::<2>::zero().normalize()),
+            Exact(Vector::zero())
+        );
+
+        // far below the fuzzy EPSILON but still a direction
+        let short = Vector::<2>::new(3e-9, -4e-9);
+        assert_eq!(short.normalize(), Vector::<2>::new(0.6, -0.8));
+        assert!((short.normalize().length() - 1.0).abs() < 1e-6);
+        let tiny = Vector::<3, f64>::new(0.0, 1e-200, 0.0);
+        assert_eq!(
+            Exact(tiny.normalize()),
+            Exact(Vector::<3, f64>::new(0.0, 1.0, 0.0))
+        );
+        let huge = Vector::<2>::new(3e30, 4e30);
+        assert_eq!(huge.normalize(), Vector::<2>::new(0.6, 0.8));
+        let denormal = Vector::<2>::new(f32::from_bits(1), 0.0);
+        assert_eq!(Exact(denormal.normalize()), Exact(Vector::zero()));
+        assert_eq!(a.distance(&Vector::<2>::new(0.0, 8.0)), 5.0);
+        let b = Vector::<4>::new(1.0, 1.0, 1.0, 1.0);
+        assert_eq!(b.length(), 2.0);
+    }
+
+    #[test]
+    fn lerp_min_max() {
+        let a = v3(0.0, 10.0, -4.0);
+        let b = v3(2.0, -10.0, 4.0);
+        assert_eq!(Exact(a.lerp(&b, 0.0)), Exact(a));
+        assert_eq!(Exact(a.lerp(&b, 1.0)), Exact(b));
+        assert_eq!(Exact(a.lerp(&b, 0.25)), Exact(v3(0.5, 5.0, -2.0)));
+        assert_eq!(Exact(a.min(&b)), Exact(v3(0.0, -10.0, -4.0)));
+        assert_eq!(Exact(a.max(&b)), Exact(v3(2.0, 10.0, 4.0)));
+    }
+
+    #[test]
+    fn constructors() {
+        assert_eq!(Exact(Vector::<3>::splat(2.0)), Exact(v3(2.0, 2.0, 2.0)));
+        assert_eq!(
+            Vector::<3>::from([1.0, 2.0, 3.0]).to_array(),
+            [1.0, 2.0, 3.0]
+        );
+        let wide: Vector<3, f64> = v3(0.1, 0.2, 0.3).into();
+        assert_eq!(Exact(wide.to_f32()), Exact(v3(0.1, 0.2, 0.3)));
+    }
+
+    #[test]
+    fn f64_arithmetic() {
+        let a = Vector::<3, f64>::new(1e10, -2.0, 0.1);
+        let b = Vector::<3, f64>::new(1.0, 0.5, 0.2);
+        assert_eq!(
+            Exact(a + b),
+            Exact(Vector::<3, f64>::new(1e10 + 1.0, -1.5, 0.1 + 0.2))
+        );
+        assert_eq!(Exact((a + b - a)[0]), Exact(1.0), "f32 would lose the 1");
+        assert_eq!(Exact(2.0 * a), Exact(a * 2.0));
+        assert_eq!(
+            Exact(a / 2.0),
+            Exact(Vector::<3, f64>::new(5e9, -1.0, 0.05))
+        );
+        assert_eq!(a.dot(&b), 1e10 - 1.0 + 0.020000000000000004);
+        assert_eq!(
+            Exact(a.cross(&b)),
+            Exact(Vector::<3, f64>::new(-0.4 - 0.05, 0.1 - 2e9, 5e9 + 2.0))
+        );
+
+        // within 1e-12 rather than the 1e-6 of f32
+        let c = Vector::<2, f64>::new(3.0, 4.0);
+        assert_eq!(c.normalize(), Vector::<2, f64>::new(0.6, 0.8));
+        assert_ne!(c.normalize(), Vector::<2, f64>::new(0.6, 0.8 + 1e-9));
+        assert_eq!(c.length(), 5.0);
+        assert_eq!(
+            c.lerp(&Vector::zero(), 0.5),
+            Vector::<2, f64>::new(1.5, 2.0)
+        );
+    }
+}
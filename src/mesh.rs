@@ -0,0 +1,309 @@
+use std::{
+    error::Error,
+    fmt::{self, Display},
+};
+
+use bytemuck::{Pod, cast_slice};
+use wgpu::{
+    Buffer, BufferAddress, BufferUsages, COPY_BUFFER_ALIGNMENT, Device, IndexFormat,
+    PrimitiveTopology, Queue, RenderPass, VertexAttribute, VertexBufferLayout, VertexFormat,
+    VertexStepMode,
+    util::{BufferInitDescriptor, DeviceExt},
+};
+
+use crate::{math::layout::align_to, primitive::Shape3D, util::Shape};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MeshError {
+    /// the vertex layout lacks an attribute the pipeline reads
+    IncompatibleLayout,
+    /// `upload` got vertices laid out differently from the ones the mesh was created with
+    LayoutChanged,
+}
+
+impl Display for MeshError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MeshError::IncompatibleLayout => write!(f, "vertex layout does not match the shader"),
+            MeshError::LayoutChanged => write!(f, "upload changes the vertex layout of the mesh"),
+        }
+    }
+}
+
+impl Error for MeshError {}
+
+/// vertex data a `Mesh` can hold, `LAYOUT` tells the pipeline how to read it
+pub trait Vertex: Pod {
+    const LAYOUT: VertexBufferLayout<'static>;
+}
+
+/// pixel position at location 0, what the pipeline of `State` reads
+impl Vertex for [f32; 2] {
+    const LAYOUT: VertexBufferLayout<'static> = VertexBufferLayout {
+        array_stride: size_of::<Self>() as BufferAddress,
+        step_mode: VertexStepMode::Vertex,
+        attributes: &[VertexAttribute {
+            format: VertexFormat::Float32x2,
+            offset: 0,
+            shader_location: 0,
+        }],
+    };
+}
+
+/// CPU side index data, u16 halves the index buffer of meshes up to 65536 vertices
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Indices {
+    U16(Vec<u16>),
+    U32(Vec<u32>),
+}
+
+impl Indices {
+    /// u16 when every index fits, u32 otherwise
+    pub fn compact(indices: Vec<u32>) -> Self {
+        if indices.iter().all(|&index| index <= u16::MAX as u32) {
+            Indices::U16(indices.into_iter().map(|index| index as u16).collect())
+        } else {
+            Indices::U32(indices)
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        match self {
+            Indices::U16(indices) => indices.len(),
+            Indices::U32(indices) => indices.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn format(&self) -> IndexFormat {
+        match self {
+            Indices::U16(_) => IndexFormat::Uint16,
+            Indices::U32(_) => IndexFormat::Uint32,
+        }
+    }
+
+    fn bytes(&self) -> &[u8] {
+        match self {
+            Indices::U16(indices) => cast_slice(indices),
+            Indices::U32(indices) => cast_slice(indices),
+        }
+    }
+}
+
+impl From<Vec<u16>> for Indices {
+    fn from(indices: Vec<u16>) -> Self {
+        Indices::U16(indices)
+    }
+}
+
+impl From<Vec<u32>> for Indices {
+    fn from(indices: Vec<u32>) -> Self {
+        Indices::U32(indices)
+    }
+}
+
+/// GPU vertex and index buffers with what it takes to draw them
+///
+/// The topology and the vertex layout pick the pipeline, both are fixed for the life of the
+/// mesh.
+pub struct Mesh {
+    vertex_buffer: Buffer,
+    index_buffer: Buffer,
+    index_format: IndexFormat,
+    vertex_count: u32,
+    index_count: u32,
+    topology: PrimitiveTopology,
+    layout: VertexBufferLayout<'static>,
+}
+
+impl Mesh {
+    pub fn new<V: Vertex>(
+        device: &Device,
+        vertices: &[V],
+        indices: Indices,
+        topology: PrimitiveTopology,
+    ) -> Self {
+        Self {
+            vertex_buffer: create_buffer(device, cast_slice(vertices), BufferUsages::VERTEX),
+            index_buffer: create_buffer(device, indices.bytes(), BufferUsages::INDEX),
+            index_format: indices.format(),
+            vertex_count: vertices.len() as u32,
+            index_count: indices.len() as u32,
+            topology,
+            layout: V::LAYOUT,
+        }
+    }
+
+    /// triangle list of a generated shape, with u16 indices when they fit
+    pub fn from_shape(device: &Device, shape: &Shape) -> Self {
+        Self::new(
+            device,
+            &shape.vertices,
+            Indices::compact(shape.indices.clone()),
+            PrimitiveTopology::TriangleList,
+        )
+    }
+
+    /// triangle list of a generated 3D primitive
+    pub fn from_shape_3d(device: &Device, shape: &Shape3D) -> Self {
+        Self::new(
+            device,
+            &shape.vertices,
+            Indices::compact(shape.indices.clone()),
+            PrimitiveTopology::TriangleList,
+        )
+    }
+
+    /// replaces the CPU data, each buffer is reused when the new data fits
+    ///
+    /// Fails without touching the buffers when `V` is laid out differently from the vertices the
+    /// mesh was created with, the pipeline picked for the mesh could not read them.
+    pub fn upload<V: Vertex>(
+        &mut self,
+        device: &Device,
+        queue: &Queue,
+        vertices: &[V],
+        indices: Indices,
+    ) -> Result<(), MeshError> {
+        check_layout::<V>(&self.layout)?;
+        write_or_replace(
+            device,
+            queue,
+            &mut self.vertex_buffer,
+            cast_slice(vertices),
+            BufferUsages::VERTEX,
+        );
+        write_or_replace(
+            device,
+            queue,
+            &mut self.index_buffer,
+            indices.bytes(),
+            BufferUsages::INDEX,
+        );
+        self.index_format = indices.format();
+        self.vertex_count = vertices.len() as u32;
+        self.index_count = indices.len() as u32;
+        Ok(())
+    }
+
+    pub fn vertex_count(&self) -> u32 {
+        self.vertex_count
+    }
+
+    pub fn index_count(&self) -> u32 {
+        self.index_count
+    }
+
+    pub fn index_format(&self) -> IndexFormat {
+        self.index_format
+    }
+
+    pub fn topology(&self) -> PrimitiveTopology {
+        self.topology
+    }
+
+    pub fn layout(&self) -> &VertexBufferLayout<'static> {
+        &self.layout
+    }
+
+    /// binds the buffers and draws, the pipeline for `topology` and `layout` must already be set
+    pub fn draw(&self, render_pass: &mut RenderPass) {
+        if self.index_count == 0 {
+            return;
+        }
+        render_pass.set_vertex_buffer(0, self.vertex_buffer.slice(..));
+        render_pass.set_index_buffer(self.index_buffer.slice(..), self.index_format);
+        render_pass.draw_indexed(0..self.index_count, 0, 0..1);
+    }
+}
+
+fn check_layout<V: Vertex>(layout: &VertexBufferLayout<'static>) -> Result<(), MeshError> {
+    if V::LAYOUT == *layout {
+        Ok(())
+    } else {
+        Err(MeshError::LayoutChanged)
+    }
+}
+
+fn create_buffer(device: &Device, contents: &[u8], usage: BufferUsages) -> Buffer {
+    device.create_buffer_init(&BufferInitDescriptor {
+        label: None,
+        contents,
+        usage: usage | BufferUsages::COPY_DST,
+    })
+}
+
+/// `write_buffer` needs a size that is a multiple of 4, odd u16 counts get padded
+fn write_or_replace(
+    device: &Device,
+    queue: &Queue,
+    buffer: &mut Buffer,
+    contents: &[u8],
+    usage: BufferUsages,
+) {
+    let padded = align_to(contents.len(), COPY_BUFFER_ALIGNMENT as usize);
+    if padded as BufferAddress > buffer.size() {
+        *buffer = create_buffer(device, contents, usage);
+        return;
+    }
+    if contents.len() == padded {
+        queue.write_buffer(buffer, 0, contents);
+    } else {
+        let mut bytes = contents.to_vec();
+        bytes.resize(padded, 0);
+        queue.write_buffer(buffer, 0, &bytes);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::primitive::Vertex3D;
+
+    #[test]
+    fn compact_picks_the_smallest_index_type() {
+        let small = Indices::compact(vec![0, 1, 2, u16::MAX as u32]);
+        assert_eq!(small, Indices::U16(vec![0, 1, 2, u16::MAX]));
+        assert_eq!(small.format(), IndexFormat::Uint16);
+
+        let large = Indices::compact(vec![0, 1, u16::MAX as u32 + 1]);
+        assert_eq!(large, Indices::U32(vec![0, 1, 65536]));
+        assert_eq!(large.format(), IndexFormat::Uint32);
+
+        let empty = Indices::compact(Vec::new());
+        assert_eq!(empty.format(), IndexFormat::Uint16);
+        assert!(empty.is_empty());
+    }
+
+    #[test]
+    fn counts_and_bytes() {
+        let short = Indices::from(vec![0u16, 1, 2]);
+        assert_eq!(short.len(), 3);
+        assert!(!short.is_empty());
+        assert_eq!(short.bytes().len(), 6);
+
+        let long = Indices::from(vec![0u32, 1, 2, 2, 3, 0]);
+        assert_eq!(long.len(), 6);
+        assert_eq!(long.format(), IndexFormat::Uint32);
+        assert_eq!(long.bytes().len(), 24);
+        assert_eq!(&long.bytes()[4..8], &1u32.to_ne_bytes());
+    }
+
+    #[test]
+    fn upload_rejects_a_different_layout() {
+        let pixels = <[f32; 2]>::LAYOUT;
+        assert_eq!(check_layout::<[f32; 2]>(&pixels), Ok(()));
+        assert_eq!(
+            check_layout::<Vertex3D>(&pixels),
+            Err(MeshError::LayoutChanged)
+        );
+        assert_eq!(check_layout::<Vertex3D>(&Vertex3D::LAYOUT), Ok(()));
+        assert_eq!(
+            check_layout::<[f32; 2]>(&Vertex3D::LAYOUT),
+            Err(MeshError::LayoutChanged)
+        );
+    }
+}
@@ -0,0 +1,580 @@
+// 3D primitive generators, the counterpart of the 2D shapes of `util`.
+//
+// Right-handed like the projections of `Matrix<4>`: y up, the default camera looks down -z.
+// Every primitive is centered on the origin with y as its axis, and every triangle is
+// counter-clockwise seen from outside, which is what `FrontFace::Ccw` keeps when back faces are
+// culled. UVs follow wgpu textures: u goes right and v goes down seen from outside, so images
+// are not mirrored. Seams repeat their vertices with u = 0 and u = 1.
+
+use std::{
+    collections::HashMap,
+    f32::consts::{PI, TAU},
+};
+
+use bytemuck::{Pod, Zeroable};
+use wgpu::{BufferAddress, VertexAttribute, VertexBufferLayout, VertexStepMode};
+
+use crate::{
+    math::{common::Dimension3, vector::Vector},
+    mesh::Vertex,
+};
+
+/// position, unit normal and texture coordinate
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Pod, Zeroable)]
+pub struct Vertex3D {
+    pub position: [f32; 3],
+    pub normal: [f32; 3],
+    pub uv: [f32; 2],
+}
+
+impl Vertex3D {
+    pub const ATTRIBUTES: [VertexAttribute; 3] =
+        wgpu::vertex_attr_array![0 => Float32x3, 1 => Float32x3, 2 => Float32x2];
+
+    pub fn new(position: Vector<3>, normal: Vector<3>, uv: [f32; 2]) -> Self {
+        Self {
+            position: position.to_array(),
+            normal: normal.to_array(),
+            uv,
+        }
+    }
+}
+
+impl Vertex for Vertex3D {
+    /// locations 0, 1 and 2 for position, normal and uv
+    const LAYOUT: VertexBufferLayout<'static> = VertexBufferLayout {
+        array_stride: size_of::<Self>() as BufferAddress,
+        step_mode: VertexStepMode::Vertex,
+        attributes: &Self::ATTRIBUTES,
+    };
+}
+
+/// indexed triangle list
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Shape3D {
+    pub vertices: Vec<Vertex3D>,
+    pub indices: Vec<u32>,
+}
+
+impl Shape3D {
+    pub fn triangle_count(&self) -> usize {
+        self.indices.len() / 3
+    }
+
+    fn vertex(&mut self, vertex: Vertex3D) -> u32 {
+        self.vertices.push(vertex);
+        self.vertices.len() as u32 - 1
+    }
+
+    /// turned to face the side the vertex normals point to, degenerate triangles are skipped
+    fn triangle(&mut self, a: u32, b: u32, c: u32) {
+        let [va, vb, vc] = [a, b, c].map(|i| self.vertices[i as usize]);
+        let [pa, pb, pc] = [va, vb, vc].map(|vertex| Vector::from(vertex.position));
+        let (ab, ac) = (pb - pa, pc - pa);
+        let face = ab.cross(&ac);
+        if face.length_squared() <= ab.length_squared() * ac.length_squared() * 1e-12 {
+            return;
+        }
+        let normal = Vector::from(va.normal) + Vector::from(vb.normal) + Vector::from(vc.normal);
+        if face.dot(&normal) >= 0.0 {
+            self.indices.extend([a, b, c]);
+        } else {
+            self.indices.extend([a, c, b]);
+        }
+    }
+
+    /// `columns` by `rows` quads, `point` maps u and v in 0..1 to position and normal
+    fn grid(
+        &mut self,
+        columns: u32,
+        rows: u32,
+        point: impl Fn(f32, f32) -> (Vector<3>, Vector<3>),
+    ) {
+        let first = self.vertices.len() as u32;
+        for row in 0..=rows {
+            let v = row as f32 / rows as f32;
+            for column in 0..=columns {
+                let u = column as f32 / columns as f32;
+                let (position, normal) = point(u, v);
+                self.vertex(Vertex3D::new(position, normal, [u, v]));
+            }
+        }
+        let at = |column: u32, row: u32| first + row * (columns + 1) + column;
+        for row in 0..rows {
+            for column in 0..columns {
+                let (top_left, bottom_left) = (at(column, row), at(column, row + 1));
+                let (bottom_right, top_right) = (at(column + 1, row + 1), at(column + 1, row));
+                self.triangle(top_left, bottom_left, bottom_right);
+                self.triangle(top_left, bottom_right, top_right);
+            }
+        }
+    }
+
+    /// flat disc at height `y` facing up or down along y
+    fn disc(&mut self, radius: f32, y: f32, segments: u32, up: bool) {
+        let normal = Vector::<3>::new(0.0, if up { 1.0 } else { -1.0 }, 0.0);
+        let center = self.vertex(Vertex3D::new(
+            Vector::<3>::new(0.0, y, 0.0),
+            normal,
+            [0.5, 0.5],
+        ));
+        let first = self.vertices.len() as u32;
+        for i in 0..segments {
+            let (sin, cos) = turn(i as f32 / segments as f32);
+            // seen from outside: -z is up on the top cap and down on the bottom one
+            let v = if up { 0.5 + cos / 2.0 } else { 0.5 - cos / 2.0 };
+            self.vertex(Vertex3D::new(
+                Vector::<3>::new(radius * sin, y, radius * cos),
+                normal,
+                [0.5 + sin / 2.0, v],
+            ));
+        }
+        for i in 0..segments {
+            self.triangle(center, first + i, first + (i + 1) % segments);
+        }
+    }
+}
+
+/// sine and cosine of a fraction of a full turn, exact at the end so seams close
+fn turn(fraction: f32) -> (f32, f32) {
+    if fraction >= 1.0 {
+        (0.0, 1.0)
+    } else {
+        (fraction * TAU).sin_cos()
+    }
+}
+
+/// point at longitude `u` turns from +z towards +x, `radius` away from the y axis
+fn around_y(u: f32, radius: f32, y: f32) -> Vector<3> {
+    let (sin, cos) = turn(u);
+    Vector::<3>::new(radius * sin, y, radius * cos)
+}
+
+/// box with edge lengths `size`, every face split into `segments` by `segments` quads
+pub fn cuboid(size: Vector<3>, segments: u32) -> Shape3D {
+    let segments = segments.max(1);
+    let half = size * 0.5;
+    let mut shape = Shape3D::default();
+    let x = Vector::<3>::new(1.0, 0.0, 0.0);
+    let y = Vector::<3>::new(0.0, 1.0, 0.0);
+    let z = Vector::<3>::new(0.0, 0.0, 1.0);
+    // normal, right and down seen from outside, the normal is down x right
+    let faces = [
+        (x, -z, -y),
+        (-x, z, -y),
+        (y, x, z),
+        (-y, x, -z),
+        (z, x, -y),
+        (-z, -x, -y),
+    ];
+    for (normal, right, down) in faces {
+        shape.grid(segments, segments, |u, v| {
+            let corner = normal - right - down;
+            let point = corner + right * (2.0 * u) + down * (2.0 * v);
+            let position =
+                Vector::<3>::new(point[0] * half[0], point[1] * half[1], point[2] * half[2]);
+            (position, normal)
+        });
+    }
+    shape
+}
+
+pub fn cube(size: f32, segments: u32) -> Shape3D {
+    cuboid(Vector::splat(size), segments)
+}
+
+/// `width` along x by `depth` along z, facing +y, -z is the top of the texture
+pub fn plane(width: f32, depth: f32, columns: u32, rows: u32) -> Shape3D {
+    let mut shape = Shape3D::default();
+    let normal = Vector::<3>::new(0.0, 1.0, 0.0);
+    shape.grid(columns.max(1), rows.max(1), |u, v| {
+        let position = Vector::<3>::new((u - 0.5) * width, 0.0, (v - 0.5) * depth);
+        (position, normal)
+    });
+    shape
+}
+
+/// `segments` around the y axis and `rings` from the top pole to the bottom one
+pub fn uv_sphere(radius: f32, segments: u32, rings: u32) -> Shape3D {
+    let mut shape = Shape3D::default();
+    shape.grid(segments.max(3), rings.max(2), |u, v| {
+        // exact at the bottom pole so its triangles collapse like at the top
+        let (sin, cos) = if v >= 1.0 {
+            (0.0, -1.0)
+        } else {
+            (v * PI).sin_cos()
+        };
+        let normal = around_y(u, sin, cos);
+        (normal * radius, normal)
+    });
+    shape
+}
+
+/// icosahedron with every triangle split in 4 `subdivisions` times, evenly spread vertices
+/// with the UV mapping of `uv_sphere`
+///
+/// Triangles across the seam get u past 1 instead of a split, so textures need a repeating
+/// address mode along u.
+pub fn icosphere(radius: f32, subdivisions: u32) -> Shape3D {
+    let t = (1.0 + 5.0f32.sqrt()) / 2.0;
+    let mut points: Vec<Vector<3>> = [
+        [-1.0, t, 0.0],
+        [1.0, t, 0.0],
+        [-1.0, -t, 0.0],
+        [1.0, -t, 0.0],
+        [0.0, -1.0, t],
+        [0.0, 1.0, t],
+        [0.0, -1.0, -t],
+        [0.0, 1.0, -t],
+        [t, 0.0, -1.0],
+        [t, 0.0, 1.0],
+        [-t, 0.0, -1.0],
+        [-t, 0.0, 1.0],
+    ]
+    .into_iter()
+    .map(|point| Vector::from(point).normalize())
+    .collect();
+    let mut faces: Vec<[u32; 3]> = vec![
+        [0, 11, 5],
+        [0, 5, 1],
+        [0, 1, 7],
+        [0, 7, 10],
+        [0, 10, 11],
+        [1, 5, 9],
+        [5, 11, 4],
+        [11, 10, 2],
+        [10, 7, 6],
+        [7, 1, 8],
+        [3, 9, 4],
+        [3, 4, 2],
+        [3, 2, 6],
+        [3, 6, 8],
+        [3, 8, 9],
+        [4, 9, 5],
+        [2, 4, 11],
+        [6, 2, 10],
+        [8, 6, 7],
+        [9, 8, 1],
+    ];
+
+    for _ in 0..subdivisions {
+        let mut midpoints: HashMap<(u32, u32), u32> = HashMap::new();
+        let mut midpoint = |a: u32, b: u32| {
+            *midpoints.entry((a.min(b), a.max(b))).or_insert_with(|| {
+                let point = (points[a as usize] + points[b as usize]).normalize();
+                points.push(point);
+                points.len() as u32 - 1
+            })
+        };
+        faces = faces
+            .into_iter()
+            .flat_map(|[a, b, c]| {
+                let (ab, bc, ca) = (midpoint(a, b), midpoint(b, c), midpoint(c, a));
+                [[a, ab, ca], [b, bc, ab], [c, ca, bc], [ab, bc, ca]]
+            })
+            .collect();
+    }
+
+    let mut shape = Shape3D::default();
+    // a vertex per point and u, triangles across the seam or touching a pole need their own u
+    let mut vertices: HashMap<(u32, u32), u32> = HashMap::new();
+    for face in faces {
+        let [a, b, c] = face.map(|i| points[i as usize]);
+        // outward first, the seam test below depends on the winding
+        let face = if (b - a).cross(&(c - a)).dot(&a) < 0.0 {
+            [face[0], face[2], face[1]]
+        } else {
+            face
+        };
+        let mut uvs = face.map(|i| sphere_uv(points[i as usize]));
+        let pole = face.map(|i| {
+            let point = points[i as usize];
+            point[0].abs() < 1e-6 && point[2].abs() < 1e-6
+        });
+        if let Some(corner) = pole.iter().position(|&pole| pole) {
+            let (a, b) = ((corner + 1) % 3, (corner + 2) % 3);
+            if (uvs[a][0] - uvs[b][0]).abs() > 0.5 {
+                let smaller = if uvs[a][0] < uvs[b][0] { a } else { b };
+                uvs[smaller][0] += 1.0;
+            }
+            uvs[corner][0] = (uvs[a][0] + uvs[b][0]) / 2.0;
+        } else if texture_area(&uvs) > 0.0 {
+            // u wrapped from 1 back to 0 inside the triangle, which mirrors it in the texture
+            for uv in uvs.iter_mut().filter(|uv| uv[0] < 0.5) {
+                uv[0] += 1.0;
+            }
+        }
+        let [a, b, c] = [0, 1, 2].map(|corner| {
+            let (index, uv) = (face[corner], uvs[corner]);
+            *vertices.entry((index, uv[0].to_bits())).or_insert_with(|| {
+                let normal = points[index as usize];
+                shape.vertex(Vertex3D::new(normal * radius, normal, uv))
+            })
+        });
+        shape.triangle(a, b, c);
+    }
+    shape
+}
+
+/// twice the signed area in texture space, negative for a triangle facing outside
+fn texture_area([a, b, c]: &[[f32; 2]; 3]) -> f32 {
+    (b[0] - a[0]) * (c[1] - a[1]) - (c[0] - a[0]) * (b[1] - a[1])
+}
+
+/// longitude and latitude of a unit vector as in `uv_sphere`
+fn sphere_uv(normal: Vector<3>) -> [f32; 2] {
+    let u = normal[0].atan2(normal[2]) / TAU;
+    let v = normal[1].clamp(-1.0, 1.0).acos() / PI;
+    [u.rem_euclid(1.0), v]
+}
+
+/// `segments` around the y axis and `rings` along it, with a flat cap on each end when `caps`
+pub fn cylinder(radius: f32, height: f32, segments: u32, rings: u32, caps: bool) -> Shape3D {
+    let segments = segments.max(3);
+    let half = height / 2.0;
+    let mut shape = Shape3D::default();
+    shape.grid(segments, rings.max(1), |u, v| {
+        let normal = around_y(u, 1.0, 0.0);
+        (around_y(u, radius, (0.5 - v) * height), normal)
+    });
+    if caps {
+        shape.disc(radius, half, segments, true);
+        shape.disc(radius, -half, segments, false);
+    }
+    shape
+}
+
+/// tip on top, `segments` around the y axis and `rings` from the tip to the base, closed by a
+/// flat base
+pub fn cone(radius: f32, height: f32, segments: u32, rings: u32) -> Shape3D {
+    let segments = segments.max(3);
+    let half = height / 2.0;
+    let mut shape = Shape3D::default();
+    shape.grid(segments, rings.max(1), |u, v| {
+        // the tip has no normal of its own, the middle of its quad shades the side smoothly
+        let angle = if v == 0.0 {
+            u + 0.5 / segments as f32
+        } else {
+            u
+        };
+        let normal = around_y(angle, height, radius).normalize();
+        (around_y(u, radius * v, (0.5 - v) * height), normal)
+    });
+    shape.disc(radius, -half, segments, false);
+    shape
+}
+
+/// ring of `radius` around the y axis with a tube of `tube_radius`, `segments` around the axis
+/// and `tube_segments` around the tube starting on the outside
+pub fn torus(radius: f32, tube_radius: f32, segments: u32, tube_segments: u32) -> Shape3D {
+    let mut shape = Shape3D::default();
+    shape.grid(segments.max(3), tube_segments.max(3), |u, v| {
+        let (sin, cos) = turn(v);
+        let normal = around_y(u, cos, -sin);
+        let position = around_y(u, radius + tube_radius * cos, -tube_radius * sin);
+        (position, normal)
+    });
+    shape
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    const SEGMENTS: [u32; 4] = [3, 4, 7, 16];
+
+    fn position(shape: &Shape3D, index: u32) -> Vector<3> {
+        Vector::from(shape.vertices[index as usize].position)
+    }
+
+    /// checks the indices, the unit normals and that every triangle faces the side its vertex
+    /// normals point to
+    fn check(shape: &Shape3D) {
+        assert!(shape.triangle_count() > 0);
+        assert_eq!(shape.indices.len() % 3, 0);
+        for vertex in &shape.vertices {
+            let length = Vector::from(vertex.normal).length();
+            assert!((length - 1.0).abs() <= 1e-5, "normal of length {length}");
+        }
+        for triangle in shape.indices.chunks(3) {
+            assert!(
+                triangle
+                    .iter()
+                    .all(|&i| (i as usize) < shape.vertices.len())
+            );
+            let [a, b, c] = [0, 1, 2].map(|i| position(shape, triangle[i]));
+            let normal: Vector<3> = triangle
+                .iter()
+                .map(|&i| Vector::from(shape.vertices[i as usize].normal))
+                .fold(Vector::zero(), |sum, normal| sum + normal);
+            assert!((b - a).cross(&(c - a)).dot(&normal) > 0.0, "facing inside");
+        }
+    }
+
+    /// triangles with seam and pole vertices merged by position
+    fn weld(shape: &Shape3D) -> Vec<[usize; 3]> {
+        let mut points: Vec<Vector<3>> = Vec::new();
+        let ids: Vec<usize> = (0..shape.vertices.len() as u32)
+            .map(|index| {
+                let point = position(shape, index);
+                points
+                    .iter()
+                    .position(|&other| (other - point).length() <= 1e-4)
+                    .unwrap_or_else(|| {
+                        points.push(point);
+                        points.len() - 1
+                    })
+            })
+            .collect();
+        shape
+            .indices
+            .chunks(3)
+            .map(|triangle| [0, 1, 2].map(|i| ids[triangle[i] as usize]))
+            .collect()
+    }
+
+    /// how often each directed edge is used after welding
+    fn edges(shape: &Shape3D) -> HashMap<(usize, usize), usize> {
+        let mut edges = HashMap::new();
+        for [a, b, c] in weld(shape) {
+            assert!(a != b && b != c && c != a, "collapsed triangle");
+            for edge in [(a, b), (b, c), (c, a)] {
+                *edges.entry(edge).or_insert(0) += 1;
+            }
+        }
+        edges
+    }
+
+    /// every edge shared by exactly two triangles winding it in opposite directions, returns
+    /// the Euler characteristic
+    fn assert_closed(shape: &Shape3D) -> i64 {
+        check(shape);
+        let edges = edges(shape);
+        for (&(a, b), &count) in &edges {
+            assert_eq!(count, 1, "edge used twice in the same direction");
+            assert_eq!(edges.get(&(b, a)), Some(&1), "open or non-manifold edge");
+        }
+        let welded = weld(shape);
+        let vertices: HashSet<_> = welded.iter().flatten().collect();
+        vertices.len() as i64 - edges.len() as i64 / 2 + welded.len() as i64
+    }
+
+    /// the edges used by a single triangle, every other edge as in `assert_closed`
+    fn boundary(shape: &Shape3D) -> usize {
+        check(shape);
+        let edges = edges(shape);
+        let mut boundary = 0;
+        for (&(a, b), &count) in &edges {
+            assert_eq!(count, 1, "edge used twice in the same direction");
+            match edges.get(&(b, a)) {
+                Some(&1) => {}
+                None => boundary += 1,
+                Some(_) => panic!("non-manifold edge"),
+            }
+        }
+        boundary
+    }
+
+    #[test]
+    fn cuboids() {
+        for segments in [1, 2, 3, 5] {
+            let n = segments as usize;
+            let shape = cuboid(Vector::<3>::new(2.0, 3.0, 0.5), segments);
+            assert_eq!(shape.vertices.len(), 6 * (n + 1) * (n + 1));
+            assert_eq!(shape.triangle_count(), 12 * n * n);
+            assert_eq!(assert_closed(&shape), 2);
+        }
+        assert_eq!(cube(1.0, 0), cube(1.0, 1));
+    }
+
+    #[test]
+    fn planes() {
+        for (columns, rows) in [(1, 1), (3, 2), (8, 5)] {
+            let shape = plane(4.0, 2.0, columns, rows);
+            let (columns, rows) = (columns as usize, rows as usize);
+            assert_eq!(shape.vertices.len(), (columns + 1) * (rows + 1));
+            assert_eq!(shape.triangle_count(), 2 * columns * rows);
+            assert_eq!(boundary(&shape), 2 * (columns + rows));
+        }
+    }
+
+    #[test]
+    fn uv_spheres() {
+        for segments in SEGMENTS {
+            for rings in [2, 3, 8] {
+                let shape = uv_sphere(2.0, segments, rings);
+                let (n, r) = (segments as usize, rings as usize);
+                assert_eq!(shape.vertices.len(), (n + 1) * (r + 1));
+                // the quads touching a pole lose their collapsed half
+                assert_eq!(shape.triangle_count(), 2 * n * r - 2 * n);
+                assert_eq!(assert_closed(&shape), 2);
+            }
+        }
+        assert_eq!(uv_sphere(1.0, 0, 0), uv_sphere(1.0, 3, 2));
+    }
+
+    #[test]
+    fn icospheres() {
+        for subdivisions in 0..4 {
+            let shape = icosphere(2.0, subdivisions);
+            assert_eq!(shape.triangle_count(), 20 * 4usize.pow(subdivisions));
+            assert_eq!(assert_closed(&shape), 2);
+            for vertex in &shape.vertices {
+                let radius = Vector::from(vertex.position).length();
+                assert!((radius - 2.0).abs() <= 1e-5);
+            }
+        }
+    }
+
+    #[test]
+    fn cylinders() {
+        for segments in SEGMENTS {
+            for rings in [1, 4] {
+                let (n, r) = (segments as usize, rings as usize);
+                let open = cylinder(1.0, 3.0, segments, rings, false);
+                assert_eq!(open.vertices.len(), (n + 1) * (r + 1));
+                assert_eq!(open.triangle_count(), 2 * n * r);
+                assert_eq!(boundary(&open), 2 * n);
+                let closed = cylinder(1.0, 3.0, segments, rings, true);
+                assert_eq!(closed.triangle_count(), 2 * n * r + 2 * n);
+                assert_eq!(assert_closed(&closed), 2);
+            }
+        }
+    }
+
+    #[test]
+    fn cones() {
+        for segments in SEGMENTS {
+            for rings in [1, 3] {
+                let shape = cone(1.0, 2.0, segments, rings);
+                let (n, r) = (segments as usize, rings as usize);
+                // the quads at the tip lose their collapsed half, the base fan makes up for it
+                assert_eq!(shape.triangle_count(), 2 * n * r);
+                assert_eq!(assert_closed(&shape), 2);
+            }
+        }
+    }
+
+    #[test]
+    fn tori() {
+        for segments in SEGMENTS {
+            for tube_segments in [3, 5, 12] {
+                let shape = torus(2.0, 0.5, segments, tube_segments);
+                let (n, m) = (segments as usize, tube_segments as usize);
+                assert_eq!(shape.vertices.len(), (n + 1) * (m + 1));
+                assert_eq!(shape.triangle_count(), 2 * n * m);
+                assert_eq!(assert_closed(&shape), 0);
+            }
+        }
+    }
+
+    #[test]
+    fn layout_matches_the_vertex() {
+        let layout = Vertex3D::LAYOUT;
+        assert_eq!(layout.array_stride, 32);
+        let offsets: Vec<_> = layout.attributes.iter().map(|a| a.offset).collect();
+        assert_eq!(offsets, [0, 12, 24]);
+    }
+}
@@ -0,0 +1,287 @@
+use std::ops::Range;
+
+use rand::{Rng, SeedableRng, rngs::StdRng};
+
+use crate::{
+    configuration::Configuration,
+    math::{
+        angle::{Deg, Rad},
+        color::Color,
+        common::{Dimension2, Dimension3},
+        geometry::{Aabb, Rect, Sphere},
+        vector::Vector,
+    },
+};
+
+/// the one source of randomness, seeded so a run can be replayed
+///
+/// StdRng is only reproducible for the same version of rand, bumping rand may change the
+/// sequence of a seed.
+pub struct Random {
+    rng: StdRng,
+    seed: u64,
+}
+
+impl Random {
+    pub fn seeded(seed: u64) -> Self {
+        Self {
+            rng: StdRng::seed_from_u64(seed),
+            seed,
+        }
+    }
+
+    /// a fresh seed from the OS, still readable through `seed` to replay the run
+    pub fn from_entropy() -> Self {
+        Self::seeded(rand::rng().random())
+    }
+
+    pub fn from_configuration(configuration: &Configuration) -> Self {
+        configuration
+            .seed
+            .map_or_else(Self::from_entropy, Self::seeded)
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// independent generator seeded from this one, for subsystems that should not shift
+    /// each other's sequences
+    pub fn fork(&mut self) -> Self {
+        Self::seeded(self.rng.random())
+    }
+
+    /// the underlying generator for everything rand offers
+    pub fn rng(&mut self) -> &mut StdRng {
+        &mut self.rng
+    }
+
+    pub fn range(&mut self, range: Range<f32>) -> f32 {
+        if range.is_empty() {
+            return range.start;
+        }
+        self.rng.random_range(range)
+    }
+
+    /// uniform in 0..1
+    pub fn unit(&mut self) -> f32 {
+        self.rng.random()
+    }
+
+    pub fn chance(&mut self, probability: f32) -> bool {
+        self.unit() < probability
+    }
+
+    pub fn angle(&mut self) -> Rad {
+        Deg(self.range(0.0..360.0)).into()
+    }
+
+    /// opaque, uniform over the sRGB cube
+    pub fn color(&mut self) -> Color {
+        Color::from_srgb(self.unit(), self.unit(), self.unit(), 1.0)
+    }
+
+    /// random hue at a fixed saturation and value, keeps colors equally vivid
+    pub fn hue(&mut self, saturation: f32, value: f32) -> Color {
+        Color::from_hsv(Deg(self.range(0.0..360.0)), saturation, value, 1.0)
+    }
+
+    /// uniform on the unit circle
+    pub fn unit_vector2(&mut self) -> Vector<2> {
+        let (sin, cos) = self.angle().sin_cos();
+        Vector::<2>::new(cos, sin)
+    }
+
+    /// uniform on the unit sphere, z is uniform by Archimedes' hat-box theorem
+    pub fn unit_vector3(&mut self) -> Vector<3> {
+        let z = self.range(-1.0..1.0);
+        let (sin, cos) = self.angle().sin_cos();
+        let ring = (1.0 - z * z).sqrt();
+        Vector::<3>::new(ring * cos, ring * sin, z)
+    }
+
+    pub fn point_in_rect(&mut self, rect: &Rect) -> Vector<2> {
+        Vector::<2>::new(
+            rect.x + self.unit() * rect.width,
+            rect.y + self.unit() * rect.height,
+        )
+    }
+
+    /// uniform over the disk, not bunched at the center
+    pub fn point_in_circle(&mut self, center: Vector<2>, radius: f32) -> Vector<2> {
+        center + self.unit_vector2() * (radius * self.unit().sqrt())
+    }
+
+    /// uniform over the triangle
+    pub fn point_in_triangle(&mut self, a: Vector<2>, b: Vector<2>, c: Vector<2>) -> Vector<2> {
+        let (mut u, mut v) = (self.unit(), self.unit());
+        // fold the far half of the parallelogram back into the triangle
+        if u + v > 1.0 {
+            (u, v) = (1.0 - u, 1.0 - v);
+        }
+        a + (b - a) * u + (c - a) * v
+    }
+
+    pub fn point_in_aabb(&mut self, aabb: &Aabb) -> Vector<3> {
+        let size = aabb.max - aabb.min;
+        Vector::<3>::new(
+            aabb.min[0] + self.unit() * size[0],
+            aabb.min[1] + self.unit() * size[1],
+            aabb.min[2] + self.unit() * size[2],
+        )
+    }
+
+    /// uniform over the ball
+    pub fn point_in_sphere(&mut self, sphere: &Sphere) -> Vector<3> {
+        sphere.center + self.unit_vector3() * (sphere.radius * self.unit().cbrt())
+    }
+
+    pub fn point_on_sphere(&mut self, sphere: &Sphere) -> Vector<3> {
+        sphere.center + self.unit_vector3() * sphere.radius
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLES: usize = 1000;
+
+    #[test]
+    fn seeds_replay() {
+        let mut a = Random::seeded(42);
+        let mut b = Random::seeded(42);
+        let first: Vec<f32> = (0..100).map(|_| a.unit()).collect();
+        let second: Vec<f32> = (0..100).map(|_| b.unit()).collect();
+        assert_eq!(first, second);
+        assert_eq!(a.seed(), 42);
+        assert_eq!(a.unit_vector3(), b.unit_vector3());
+
+        let mut other = Random::seeded(43);
+        let third: Vec<f32> = (0..100).map(|_| other.unit()).collect();
+        assert_ne!(first, third);
+
+        // forks replay as well and do not repeat the parent
+        let (mut a, mut b) = (Random::seeded(7), Random::seeded(7));
+        let (mut fork_a, mut fork_b) = (a.fork(), b.fork());
+        assert_eq!(fork_a.seed(), fork_b.seed());
+        assert_eq!(fork_a.unit(), fork_b.unit());
+        assert_ne!(fork_a.unit(), a.unit());
+
+        let configured = Random::from_configuration(&Configuration { seed: Some(9) });
+        assert_eq!(configured.seed(), 9);
+        let fresh = Random::from_entropy();
+        assert_eq!(Random::seeded(fresh.seed()).seed(), fresh.seed());
+    }
+
+    #[test]
+    fn scalars_stay_in_range() {
+        let mut random = Random::seeded(1);
+        for _ in 0..SAMPLES {
+            let unit = random.unit();
+            assert!((0.0..1.0).contains(&unit));
+            let value = random.range(-3.0..5.0);
+            assert!((-3.0..5.0).contains(&value));
+            let angle = Deg::from(random.angle());
+            assert!(angle.0 >= 0.0 && angle.0 <= 360.0);
+        }
+        assert_eq!(random.range(2.0..2.0), 2.0);
+        assert!(!random.chance(0.0));
+        assert!(random.chance(1.0));
+    }
+
+    #[test]
+    fn colors_stay_in_range() {
+        let mut random = Random::seeded(2);
+        for _ in 0..SAMPLES {
+            let color = random.color();
+            assert!(
+                [color.r, color.g, color.b]
+                    .iter()
+                    .all(|c| (0.0..=1.0).contains(c))
+            );
+            assert_eq!(color.a, 1.0);
+
+            let vivid = random.hue(0.8, 0.9);
+            assert!(
+                [vivid.r, vivid.g, vivid.b]
+                    .iter()
+                    .all(|c| (0.0..=1.0).contains(c))
+            );
+            let (_, saturation, value) = vivid.to_hsv();
+            assert!((saturation - 0.8).abs() < 1e-3 && (value - 0.9).abs() < 1e-3);
+        }
+    }
+
+    #[test]
+    fn unit_vectors() {
+        let mut random = Random::seeded(3);
+        let (mut sum2, mut sum3) = (Vector::<2>::zero(), Vector::<3>::zero());
+        for _ in 0..SAMPLES {
+            let v2 = random.unit_vector2();
+            let v3 = random.unit_vector3();
+            assert!((v2.length() - 1.0).abs() < 1e-5);
+            assert!((v3.length() - 1.0).abs() < 1e-5);
+            sum2 += v2;
+            sum3 += v3;
+        }
+        // evenly spread directions nearly cancel
+        assert!(sum2.length() / (SAMPLES as f32) < 0.1);
+        assert!(sum3.length() / (SAMPLES as f32) < 0.1);
+    }
+
+    #[test]
+    fn points_in_2d_shapes() {
+        let mut random = Random::seeded(4);
+        let rect = Rect::new(-10.0, 5.0, 20.0, 3.0);
+        let center = Vector::<2>::new(3.0, -4.0);
+        let (a, b, c) = (
+            Vector::<2>::new(0.0, 0.0),
+            Vector::<2>::new(10.0, 0.0),
+            Vector::<2>::new(0.0, 5.0),
+        );
+        let mut inner = 0;
+        for _ in 0..SAMPLES {
+            assert!(rect.contains_point(random.point_in_rect(&rect)));
+
+            let distance = random.point_in_circle(center, 2.0).distance(&center);
+            assert!(distance <= 2.0 + 1e-5);
+            if distance < 1.0 {
+                inner += 1;
+            }
+
+            let p = random.point_in_triangle(a, b, c);
+            assert!(p[0] >= -1e-5 && p[1] >= -1e-5);
+            assert!(p[0] / 10.0 + p[1] / 5.0 <= 1.0 + 1e-5);
+        }
+        // the inner half radius holds a quarter of the area
+        let fraction = inner as f32 / SAMPLES as f32;
+        assert!((fraction - 0.25).abs() < 0.05, "{fraction}");
+    }
+
+    #[test]
+    fn points_in_3d_shapes() {
+        let mut random = Random::seeded(5);
+        let aabb = Aabb::new(
+            Vector::<3>::new(-1.0, 0.0, 2.0),
+            Vector::<3>::new(1.0, 4.0, 3.0),
+        );
+        let sphere = Sphere::new(Vector::<3>::new(1.0, 2.0, 3.0), 5.0);
+        let mut inner = 0;
+        for _ in 0..SAMPLES {
+            assert!(aabb.contains_point(random.point_in_aabb(&aabb)));
+
+            let distance = random.point_in_sphere(&sphere).distance(&sphere.center);
+            assert!(distance <= 5.0 + 1e-4);
+            if distance < 2.5 {
+                inner += 1;
+            }
+
+            let surface = random.point_on_sphere(&sphere).distance(&sphere.center);
+            assert!((surface - 5.0).abs() < 1e-4);
+        }
+        // the inner half radius holds an eighth of the volume
+        let fraction = inner as f32 / SAMPLES as f32;
+        assert!((fraction - 0.125).abs() < 0.04, "{fraction}");
+    }
+}
@@ -1,20 +1,50 @@
-use bytemuck::cast_slice;
+use bytemuck::{Pod, Zeroable, bytes_of};
 use pollster::FutureExt;
-use rand::Rng;
-use std::{f32, iter::once, ops::Mul, sync::Arc};
+use std::{collections::HashMap, f32, iter::once, sync::Arc, time::Instant};
 use wgpu::{
     Adapter, BindGroup, BindGroupDescriptor, BindGroupEntry, BindGroupLayoutDescriptor,
     BindGroupLayoutEntry, BindingType, BlendComponent, BlendState, Buffer, BufferBindingType,
-    BufferUsages, ColorTargetState, ColorWrites, Device, FragmentState, Instance,
+    BufferUsages, ColorTargetState, ColorWrites, Device, FragmentState, Instance, PipelineLayout,
     PipelineLayoutDescriptor, PrimitiveState, PrimitiveTopology, Queue, RenderPipeline,
-    RenderPipelineDescriptor, ShaderModuleDescriptor, ShaderSource, ShaderStages, Surface,
-    SurfaceCapabilities, SurfaceConfiguration, SurfaceError, VertexAttribute, VertexBufferLayout,
-    VertexFormat, VertexState, VertexStepMode,
+    RenderPipelineDescriptor, ShaderModule, ShaderModuleDescriptor, ShaderSource, ShaderStages,
+    Surface, SurfaceCapabilities, SurfaceConfiguration, SurfaceError, TextureFormat,
+    VertexBufferLayout, VertexFormat, VertexState,
     util::{BufferInitDescriptor, DeviceExt},
 };
 use winit::{dpi::PhysicalSize, window::Window};
 
-use crate::math::matrix::Matrix;
+use web_gpu::{
+    animation::{Easing, Repeat, Track},
+    configuration::Configuration,
+    constant::SEED_ENV,
+    math::{
+        angle::Deg,
+        color::Color,
+        common::Dimension2,
+        layout::{GpuLayout, GpuMat3, GpuVec2, GpuVec4},
+        matrix::Matrix,
+        transform::Transform2D,
+        vector::Vector,
+    },
+    mesh::{Indices, Mesh, MeshError},
+    random::Random,
+};
+
+/// mirrors `Uni` in shader.wgsl, the const block below checks the std140 offsets
+#[repr(C)]
+#[derive(Clone, Copy, Pod, Zeroable)]
+struct Uniform {
+    color: GpuVec4,
+    resolution: GpuVec2,
+    _padding: [f32; 2],
+    transform: GpuMat3,
+}
+
+const _: () = {
+    assert!(std::mem::offset_of!(Uniform, resolution).is_multiple_of(Vector::<2>::ALIGN));
+    assert!(std::mem::offset_of!(Uniform, transform).is_multiple_of(Matrix::<3>::ALIGN));
+    assert!(size_of::<Uniform>().is_multiple_of(16));
+};
 
 pub struct State<'a> {
     surface: Surface<'a>,
@@ -23,14 +53,28 @@ pub struct State<'a> {
     size: PhysicalSize<u32>,
     config: SurfaceConfiguration,
     window: Arc<Window>,
-    render_pipeline: RenderPipeline,
-    vertex_buffer: Buffer,
-    bind_group_value: [f32; 20],
+    pipeline_layout: PipelineLayout,
+    shader: ShaderModule,
+    /// one pipeline per topology and vertex layout in use
+    pipelines: HashMap<(PrimitiveTopology, VertexBufferLayout<'static>), RenderPipeline>,
+    meshes: Vec<Mesh>,
+    uniform: Uniform,
     bind_group_buffer: Buffer,
     bind_group: BindGroup,
-    angle: f32,
-    translation: (f32, f32),
-    scale: f32,
+    transform: Transform2D,
+    transform_track: Track<Transform2D>,
+    start: Instant,
+    clear_color: Color,
+}
+
+/// colors are linear, an sRGB surface encodes them on write, any other surface shows the
+/// values as they are so they are encoded up front
+fn surface_color(color: Color, format: TextureFormat) -> Color {
+    if format.is_srgb() {
+        color
+    } else {
+        color.to_srgb().into()
+    }
 }
 
 impl<'a> State<'a> {
@@ -76,13 +120,63 @@ impl<'a> State<'a> {
         instance
             .request_adapter(&wgpu::RequestAdapterOptionsBase {
                 power_preference: wgpu::PowerPreference::default(),
-                compatible_surface: Some(&surface),
+                compatible_surface: Some(surface),
                 force_fallback_adapter: false,
             })
             .block_on()
             .unwrap()
     }
 
+    fn create_render_pipeline(
+        device: &Device,
+        layout: &PipelineLayout,
+        shader: &ShaderModule,
+        format: TextureFormat,
+        topology: PrimitiveTopology,
+        vertex_layout: &VertexBufferLayout,
+    ) -> RenderPipeline {
+        device.create_render_pipeline(&RenderPipelineDescriptor {
+            label: None,
+            layout: Some(layout),
+            vertex: VertexState {
+                module: shader,
+                entry_point: Some("vs"),
+                buffers: std::slice::from_ref(vertex_layout),
+                compilation_options: Default::default(),
+            },
+            fragment: Some(FragmentState {
+                module: shader,
+                entry_point: Some("fs"),
+                targets: &[Some(ColorTargetState {
+                    format,
+                    blend: Some(BlendState {
+                        color: BlendComponent::REPLACE,
+                        alpha: BlendComponent::REPLACE,
+                    }),
+                    write_mask: ColorWrites::ALL,
+                })],
+                compilation_options: Default::default(),
+            }),
+            primitive: PrimitiveState {
+                topology,
+                strip_index_format: None,
+                front_face: wgpu::FrontFace::Ccw,
+                cull_mode: Some(wgpu::Face::Back),
+                polygon_mode: wgpu::PolygonMode::Fill,
+                unclipped_depth: false,
+                conservative: false,
+            },
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState {
+                count: 1,
+                mask: !0,
+                alpha_to_coverage_enabled: false,
+            },
+            multiview: None,
+            cache: None,
+        })
+    }
+
     fn create_gpu_instance() -> Instance {
         Instance::new(&wgpu::InstanceDescriptor {
             backends: wgpu::Backends::PRIMARY,
@@ -90,7 +184,7 @@ impl<'a> State<'a> {
         })
     }
 
-    pub fn new(window: Window) -> Self {
+    pub fn new(window: Window, configuration: &Configuration) -> Self {
         let window_arc = Arc::new(window);
         let size = window_arc.inner_size();
         let instance = Self::create_gpu_instance();
@@ -101,49 +195,36 @@ impl<'a> State<'a> {
         let config = Self::create_surface_config(size, surface_caps);
         surface.configure(&device, &config);
 
-        // vertex buffer
-        let vertex_info: [f32; 6] = [0.0, 0.0, 50.0, 150.0, 100.0, 0.0];
-
-        let vertex_buffer = device.create_buffer_init(&BufferInitDescriptor {
-            label: None,
-            contents: cast_slice(&vertex_info),
-            usage: BufferUsages::VERTEX | BufferUsages::COPY_DST,
-        });
-
-        let angle: f32 = 0.0;
-        let translation: (f32, f32) = (0.0, 0.0);
-        let scale: f32 = 1.0;
-        let mut transform = Matrix::<3>::scale(scale, scale);
-        transform = Matrix::<3>::rotate(angle).mul(transform);
-        transform = Matrix::<3>::translate(translation.0, translation.1).mul(transform);
-        transform = transform.transpose();
-        let mut rng = rand::rng();
-        let bind_group_value: [f32; 20] = [
-            rng.random_range(0.0..1.0),
-            rng.random_range(0.0..1.0),
-            rng.random_range(0.0..1.0),
-            1.0, //color
-            config.width as f32,
-            config.height as f32, //resolution
-            0.0,
-            0.0, //padding
-            transform[0][0],
-            transform[0][1],
-            transform[0][2],
-            0.0,
-            transform[1][0],
-            transform[1][1],
-            transform[1][2],
-            0.0,
-            transform[2][0],
-            transform[2][1],
-            transform[2][2],
-            0.0,
-        ];
+        // the triangle spins around its centroid while moving across the window and back
+        let pivot = Vector::<2>::new(50.0, 50.0);
+        let transform = Transform2D::identity().with_pivot(pivot);
+        let transform_track = Track::new()
+            .with_key(0.0, transform, Easing::CubicInOut)
+            .with_key(
+                3.0,
+                Transform2D {
+                    translation: Vector::<2>::new(400.0, 250.0),
+                    rotation: Deg(360.0).into(),
+                    scale: Vector::splat(2.0),
+                    pivot,
+                },
+                Easing::Linear,
+            )
+            .with_repeat(Repeat::PingPong);
+        let mut random = Random::from_configuration(configuration);
+        // also printed when the seed was picked at random, so any run can be replayed
+        eprintln!("seed {0}, replay with {SEED_ENV}={0}", random.seed());
+        let color = random.hue(0.7, 0.9);
+        let uniform = Uniform {
+            color: surface_color(color, config.format).to_gpu(),
+            resolution: Vector::<2>::new(config.width as f32, config.height as f32).to_gpu(),
+            _padding: [0.0; 2],
+            transform: transform.to_matrix().to_gpu(),
+        };
 
         let bind_group_buffer = device.create_buffer_init(&BufferInitDescriptor {
             label: None,
-            contents: cast_slice(&bind_group_value),
+            contents: bytes_of(&uniform),
             usage: BufferUsages::UNIFORM | BufferUsages::COPY_DST,
         });
 
@@ -177,76 +258,72 @@ impl<'a> State<'a> {
         });
 
         //prepare render pipeline
-        let render_pipeline_layout = device.create_pipeline_layout(&PipelineLayoutDescriptor {
+        let pipeline_layout = device.create_pipeline_layout(&PipelineLayoutDescriptor {
             label: None,
             bind_group_layouts: &[&bind_group_layout],
             push_constant_ranges: &[],
         });
-        let render_pipeline = device.create_render_pipeline(&RenderPipelineDescriptor {
-            label: None,
-            layout: Some(&render_pipeline_layout),
-            vertex: VertexState {
-                module: &shader,
-                entry_point: Some("vs"),
-                buffers: &[VertexBufferLayout {
-                    array_stride: 2 * 4,
-                    attributes: &[VertexAttribute {
-                        shader_location: 0,
-                        offset: 0,
-                        format: VertexFormat::Float32x2,
-                    }],
-                    step_mode: VertexStepMode::Vertex,
-                }],
-                compilation_options: Default::default(),
-            },
-            fragment: Some(FragmentState {
-                module: &shader,
-                entry_point: Some("fs"),
-                targets: &[Some(ColorTargetState {
-                    format: config.format,
-                    blend: Some(BlendState {
-                        color: BlendComponent::REPLACE,
-                        alpha: BlendComponent::REPLACE,
-                    }),
-                    write_mask: ColorWrites::ALL,
-                })],
-                compilation_options: Default::default(),
-            }),
-            primitive: PrimitiveState {
-                topology: PrimitiveTopology::TriangleList,
-                strip_index_format: None,
-                front_face: wgpu::FrontFace::Ccw,
-                cull_mode: Some(wgpu::Face::Back),
-                polygon_mode: wgpu::PolygonMode::Fill,
-                unclipped_depth: false,
-                conservative: false,
-            },
-            depth_stencil: None,
-            multisample: wgpu::MultisampleState {
-                count: 1,
-                mask: !0,
-                alpha_to_coverage_enabled: false,
-            },
-            multiview: None,
-            cache: None,
-        });
 
-        Self {
+        let clear_color = surface_color(Color::BLACK, config.format);
+
+        let mut state = Self {
             surface,
             device,
             queue,
             size,
             config,
             window: window_arc,
-            render_pipeline,
-            vertex_buffer,
-            bind_group_value,
+            pipeline_layout,
+            shader,
+            pipelines: HashMap::new(),
+            meshes: Vec::new(),
+            uniform,
             bind_group_buffer,
             bind_group,
-            angle,
-            scale,
-            translation,
+            transform,
+            transform_track,
+            start: Instant::now(),
+            clear_color,
+        };
+
+        // the triangle spelled out with u16 indices
+        let triangle = Mesh::new(
+            &state.device,
+            &[[0.0f32, 0.0], [50.0, 150.0], [100.0, 0.0]],
+            Indices::U16(vec![0, 1, 2]),
+            PrimitiveTopology::TriangleList,
+        );
+        state.add_mesh(triangle).unwrap();
+        state
+    }
+
+    /// draws the mesh every frame from now on, creating the pipeline for its topology and
+    /// vertex layout if needed
+    ///
+    /// shader.wgsl reads a pixel position from location 0, meshes without one are rejected.
+    pub fn add_mesh(&mut self, mesh: Mesh) -> Result<(), MeshError> {
+        let position = mesh
+            .layout()
+            .attributes
+            .iter()
+            .find(|attribute| attribute.shader_location == 0);
+        if position.is_none_or(|attribute| attribute.format != VertexFormat::Float32x2) {
+            return Err(MeshError::IncompatibleLayout);
+        }
+        let key = (mesh.topology(), mesh.layout().clone());
+        if !self.pipelines.contains_key(&key) {
+            let pipeline = Self::create_render_pipeline(
+                &self.device,
+                &self.pipeline_layout,
+                &self.shader,
+                self.config.format,
+                key.0,
+                &key.1,
+            );
+            self.pipelines.insert(key, pipeline);
         }
+        self.meshes.push(mesh);
+        Ok(())
     }
 
     pub fn resize(&mut self, new_size: PhysicalSize<u32>) {
@@ -254,42 +331,21 @@ impl<'a> State<'a> {
         self.config.width = self.size.width;
         self.config.height = self.size.height;
         self.surface.configure(&self.device, &self.config);
-        self.bind_group_value[4] = self.config.width as f32;
-        self.bind_group_value[5] = self.config.height as f32;
-        self.queue.write_buffer(
-            &self.bind_group_buffer,
-            0,
-            cast_slice(&self.bind_group_value),
-        );
+        self.uniform.resolution =
+            Vector::<2>::new(self.config.width as f32, self.config.height as f32).to_gpu();
+        self.queue
+            .write_buffer(&self.bind_group_buffer, 0, bytes_of(&self.uniform));
     }
 
     pub fn update(&mut self) {
-        self.angle = self.angle + 2.0 * std::f32::consts::PI / 180.0;
-        self.translation = (self.translation.0 + 0.5, self.translation.1 + 0.5);
-        self.scale = self.scale + 0.001;
-
-        let mut transform = Matrix::<3>::scale(self.scale, self.scale);
-        transform = Matrix::<3>::rotate(self.angle).mul(transform);
-        transform = Matrix::<3>::translate(self.translation.0, self.translation.1).mul(transform);
-        transform = transform.transpose();
-
-        self.bind_group_value[8] = transform[0][0];
-        self.bind_group_value[9] = transform[0][1];
-        self.bind_group_value[10] = transform[0][2];
-
-        self.bind_group_value[12] = transform[1][0];
-        self.bind_group_value[13] = transform[1][1];
-        self.bind_group_value[14] = transform[1][2];
-
-        self.bind_group_value[16] = transform[2][0];
-        self.bind_group_value[17] = transform[2][1];
-        self.bind_group_value[18] = transform[2][2];
+        let elapsed = self.start.elapsed().as_secs_f32();
+        if let Some(transform) = self.transform_track.sample(elapsed) {
+            self.transform = transform;
+        }
 
-        self.queue.write_buffer(
-            &self.bind_group_buffer,
-            0,
-            cast_slice(&self.bind_group_value),
-        );
+        self.uniform.transform = self.transform.to_matrix().to_gpu();
+        self.queue
+            .write_buffer(&self.bind_group_buffer, 0, bytes_of(&self.uniform));
     }
 
     pub fn render(&mut self) -> Result<(), SurfaceError> {
@@ -304,7 +360,7 @@ impl<'a> State<'a> {
                 view: &view,
                 resolve_target: None,
                 ops: wgpu::Operations {
-                    load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+                    load: wgpu::LoadOp::Clear(self.clear_color.into()),
                     store: wgpu::StoreOp::Store,
                 },
             })],
@@ -312,10 +368,11 @@ impl<'a> State<'a> {
             occlusion_query_set: None,
             timestamp_writes: None,
         });
-        render_pass.set_pipeline(&self.render_pipeline);
-        render_pass.set_vertex_buffer(0, self.vertex_buffer.slice(..));
         render_pass.set_bind_group(0, &self.bind_group, &[]);
-        render_pass.draw(0..3, 0..1);
+        for mesh in &self.meshes {
+            render_pass.set_pipeline(&self.pipelines[&(mesh.topology(), mesh.layout().clone())]);
+            mesh.draw(&mut render_pass);
+        }
         drop(render_pass);
         self.queue.submit(once(encoder.finish()));
         output.present();
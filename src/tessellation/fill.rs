@@ -0,0 +1,476 @@
+use crate::{
+    math::{common::Dimension2, vector::Vector},
+    util::Shape,
+};
+
+/// indices of a triangle list filling `outline` minus `holes`
+///
+/// Indices refer to the points of `outline` followed by the points of every hole in order, so
+/// the same concatenation is the vertex buffer. Rings may have either winding, triangles come
+/// out counter-clockwise on screen like every shape of `util`. Duplicate and collinear points
+/// are skipped, rings with fewer than 3 usable points are ignored, and self-intersecting
+/// input still terminates with a best-effort result instead of panicking.
+pub fn triangulate(outline: &[Vector<2>], holes: &[Vec<Vector<2>>]) -> Vec<u32> {
+    let points: Vec<Vector<2>> = outline
+        .iter()
+        .chain(holes.iter().flatten())
+        .copied()
+        .collect();
+    let tolerance = tolerance(&points);
+
+    let Some(mut polygon) = clean_ring(&points, 0..outline.len(), true, tolerance) else {
+        return Vec::new();
+    };
+
+    let mut offset = outline.len();
+    let mut hole_rings: Vec<Vec<usize>> = holes
+        .iter()
+        .filter_map(|hole| {
+            let range = offset..offset + hole.len();
+            offset += hole.len();
+            clean_ring(&points, range, false, tolerance)
+        })
+        .collect();
+    // right to left, so every bridge only crosses holes that are already merged
+    hole_rings.sort_by(|a, b| rightmost_x(&points, b).total_cmp(&rightmost_x(&points, a)));
+    for hole in hole_rings {
+        bridge(&points, &mut polygon, &hole);
+    }
+
+    clip_ears(&points, polygon, tolerance)
+}
+
+/// `triangulate` with the points as vertices
+pub fn fill(outline: &[Vector<2>], holes: &[Vec<Vector<2>>]) -> Shape {
+    let indices = triangulate(outline, holes);
+    let vertices = outline
+        .iter()
+        .chain(holes.iter().flatten())
+        .map(|point| point.to_array())
+        .collect();
+    Shape { vertices, indices }
+}
+
+/// areas below this count as zero, relative to the extent of the input
+fn tolerance(points: &[Vector<2>]) -> f32 {
+    let finite = points.iter().filter(|point| is_finite(point));
+    let (min, max) = finite.fold(
+        (Vector::splat(f32::MAX), Vector::splat(f32::MIN)),
+        |(min, max), point| (min.min(point), max.max(point)),
+    );
+    let extent = (max - min).length();
+    if extent.is_finite() {
+        extent * extent * 1e-7
+    } else {
+        0.0
+    }
+}
+
+fn is_finite(point: &Vector<2>) -> bool {
+    point[0].is_finite() && point[1].is_finite()
+}
+
+/// twice the signed area of a, b, c, positive when they turn left with y up
+fn cross(a: Vector<2>, b: Vector<2>, c: Vector<2>) -> f32 {
+    (b[0] - a[0]) * (c[1] - a[1]) - (b[1] - a[1]) * (c[0] - a[0])
+}
+
+/// indices of the usable points of a ring, positive (outline) or negative (hole) area
+fn clean_ring(
+    points: &[Vector<2>],
+    range: std::ops::Range<usize>,
+    positive: bool,
+    tolerance: f32,
+) -> Option<Vec<usize>> {
+    let mut ring: Vec<usize> = Vec::with_capacity(range.len());
+    for index in range {
+        let point = points[index];
+        if !is_finite(&point) || ring.last().is_some_and(|&last| points[last] == point) {
+            continue;
+        }
+        ring.push(index);
+    }
+    while ring.len() > 1 && points[ring[0]] == points[ring[ring.len() - 1]] {
+        ring.pop();
+    }
+
+    let area: f32 = (0..ring.len())
+        .map(|i| {
+            let (a, b) = (points[ring[i]], points[ring[(i + 1) % ring.len()]]);
+            a[0] * b[1] - b[0] * a[1]
+        })
+        .sum();
+    if ring.len() < 3 || area.abs() <= tolerance {
+        return None;
+    }
+    if (area > 0.0) != positive {
+        ring.reverse();
+    }
+    Some(ring)
+}
+
+fn rightmost_x(points: &[Vector<2>], ring: &[usize]) -> f32 {
+    ring.iter().map(|&i| points[i][0]).fold(f32::MIN, f32::max)
+}
+
+/// splices a hole into the polygon through a zero width channel from its rightmost point to a
+/// visible polygon point, the polygon stays one ring with positive area
+fn bridge(points: &[Vector<2>], polygon: &mut Vec<usize>, hole: &[usize]) {
+    let start = (0..hole.len())
+        .max_by(|&a, &b| {
+            let (a, b) = (points[hole[a]], points[hole[b]]);
+            a[0].total_cmp(&b[0]).then(a[1].total_cmp(&b[1]))
+        })
+        .unwrap_or(0);
+    let m = points[hole[start]];
+
+    // closest edge hit by a ray from m towards +x, only edges going up face the inside
+    let count = polygon.len();
+    let mut hit: Option<(f32, usize)> = None;
+    for i in 0..count {
+        let (a, b) = (points[polygon[i]], points[polygon[(i + 1) % count]]);
+        if a[1] > m[1] || b[1] < m[1] || a[1] == b[1] {
+            continue;
+        }
+        let x = a[0] + (m[1] - a[1]) / (b[1] - a[1]) * (b[0] - a[0]);
+        if x >= m[0] && hit.is_none_or(|(best, _)| x < best) {
+            hit = Some((x, i));
+        }
+    }
+    // a hole outside the outline has nothing to connect to
+    let Some((x, edge)) = hit else {
+        return;
+    };
+
+    let (a, b) = (edge, (edge + 1) % count);
+    let mut visible = if points[polygon[a]][0] > points[polygon[b]][0] {
+        a
+    } else {
+        b
+    };
+    let intersection = Vector::<2>::new(x, m[1]);
+    let p = points[polygon[visible]];
+    if p != intersection {
+        // reflex points inside m, intersection, p hide p, the one closest in angle to the
+        // ray is visible instead
+        let mut best = f32::MAX;
+        for i in 0..count {
+            let point = points[polygon[i]];
+            if i == visible || point == m {
+                continue;
+            }
+            let prev = points[polygon[(i + count - 1) % count]];
+            let next = points[polygon[(i + 1) % count]];
+            if cross(prev, point, next) > 0.0 || !in_triangle(point, m, intersection, p, 0.0) {
+                continue;
+            }
+            let dx = point[0] - m[0];
+            let slope = (point[1] - m[1]).abs() / dx.max(f32::MIN_POSITIVE);
+            if slope < best || (slope == best && point[0] < points[polygon[visible]][0]) {
+                best = slope;
+                visible = i;
+            }
+        }
+    }
+
+    let mut channel: Vec<usize> = (0..=hole.len())
+        .map(|i| hole[(start + i) % hole.len()])
+        .collect();
+    channel.push(polygon[visible]);
+    polygon.splice(visible + 1..visible + 1, channel);
+}
+
+/// inclusive point in triangle test for either winding, with slack for rounding
+fn in_triangle(p: Vector<2>, a: Vector<2>, b: Vector<2>, c: Vector<2>, tolerance: f32) -> bool {
+    let (d1, d2, d3) = (cross(a, b, p), cross(b, c, p), cross(c, a, p));
+    let negative = d1 < -tolerance || d2 < -tolerance || d3 < -tolerance;
+    let positive = d1 > tolerance || d2 > tolerance || d3 > tolerance;
+    !(negative && positive)
+}
+
+/// ear clipping of a positive ring, triangles are emitted reversed for the screen
+fn clip_ears(points: &[Vector<2>], mut ring: Vec<usize>, tolerance: f32) -> Vec<u32> {
+    let mut indices = Vec::with_capacity(3 * ring.len().saturating_sub(2));
+    let mut emit = |a: usize, b: usize, c: usize| indices.extend([a as u32, c as u32, b as u32]);
+    let mut cursor = 0;
+
+    while ring.len() > 3 {
+        let count = ring.len();
+        let corner = |i: usize| {
+            let (prev, next) = ((i + count - 1) % count, (i + 1) % count);
+            (ring[prev], ring[i], ring[next])
+        };
+
+        let mut clipped = None;
+        for step in 0..count {
+            let i = (cursor + step) % count;
+            let (prev, current, next) = corner(i);
+            let turn = cross(points[prev], points[current], points[next]);
+            if turn.abs() <= tolerance {
+                // collinear or a spike, dropping it loses no area
+                clipped = Some((i, None));
+                break;
+            }
+            if turn > 0.0 && is_ear(points, &ring, i, tolerance) {
+                clipped = Some((i, Some((prev, current, next))));
+                break;
+            }
+        }
+        // nothing qualifies on self-intersecting input, fall back to any convex corner, or
+        // drop a point without a triangle when every corner turns the wrong way
+        let (i, triangle) = clipped.unwrap_or_else(|| {
+            let convex = (0..count).find(|&i| {
+                let (prev, current, next) = corner(i);
+                cross(points[prev], points[current], points[next]) > 0.0
+            });
+            match convex {
+                Some(i) => (i, Some(corner(i))),
+                None => (0, None),
+            }
+        });
+
+        if let Some((a, b, c)) = triangle {
+            emit(a, b, c);
+        }
+        ring.remove(i);
+        cursor = i % ring.len();
+    }
+
+    if let [a, b, c] = ring[..]
+        && cross(points[a], points[b], points[c]) > tolerance
+    {
+        emit(a, b, c);
+    }
+    indices
+}
+
+/// no reflex corner of the ring lies in the triangle at corner i, points sharing a position
+/// with the triangle (the ends of a hole channel) don't count
+fn is_ear(points: &[Vector<2>], ring: &[usize], i: usize, tolerance: f32) -> bool {
+    let count = ring.len();
+    let (a, b, c) = (
+        points[ring[(i + count - 1) % count]],
+        points[ring[i]],
+        points[ring[(i + 1) % count]],
+    );
+    (0..count).all(|j| {
+        let point = points[ring[j]];
+        if point == a || point == b || point == c {
+            return true;
+        }
+        let prev = points[ring[(j + count - 1) % count]];
+        let next = points[ring[(j + 1) % count]];
+        let convex = cross(prev, point, next) > tolerance;
+        convex || !in_triangle(point, a, b, c, tolerance)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ring(points: &[[f32; 2]]) -> Vec<Vector<2>> {
+        points.iter().map(|&point| point.into()).collect()
+    }
+
+    /// absolute shoelace area
+    fn area(ring: &[Vector<2>]) -> f32 {
+        let twice: f32 = (0..ring.len())
+            .map(|i| {
+                let (a, b) = (ring[i], ring[(i + 1) % ring.len()]);
+                a[0] * b[1] - b[0] * a[1]
+            })
+            .sum();
+        twice.abs() / 2.0
+    }
+
+    /// checks the indices and that every triangle is counter-clockwise on screen (negative
+    /// pixel space cross), returns the covered area
+    fn check(shape: &Shape) -> f32 {
+        assert_eq!(shape.indices.len() % 3, 0);
+        assert!(
+            shape
+                .indices
+                .iter()
+                .all(|&index| (index as usize) < shape.vertices.len())
+        );
+        let crosses: Vec<f32> = shape
+            .indices
+            .chunks(3)
+            .map(|triangle| {
+                let [a, b, c] =
+                    [0, 1, 2].map(|i| Vector::from(shape.vertices[triangle[i] as usize]));
+                cross(a, b, c)
+            })
+            .collect();
+        assert!(
+            crosses.iter().all(|&cross| cross < 0.0),
+            "not counter-clockwise on screen"
+        );
+        -crosses.iter().sum::<f32>() / 2.0
+    }
+
+    /// fills and checks that the triangles cover exactly the outline minus the holes
+    fn assert_covers(outline: &[Vector<2>], holes: &[Vec<Vector<2>>]) -> Shape {
+        let shape = fill(outline, holes);
+        let expected = area(outline) - holes.iter().map(|hole| area(hole)).sum::<f32>();
+        let covered = check(&shape);
+        assert!(
+            (covered - expected).abs() <= 1e-4 * expected,
+            "covered {covered}, expected {expected}"
+        );
+        shape
+    }
+
+    fn square(x: f32, y: f32, size: f32) -> Vec<Vector<2>> {
+        ring(&[[x, y], [x + size, y], [x + size, y + size], [x, y + size]])
+    }
+
+    /// `points` corners alternating between two radii
+    fn star(points: usize, inner: f32, outer: f32) -> Vec<Vector<2>> {
+        (0..2 * points)
+            .map(|i| {
+                let angle = std::f32::consts::PI * i as f32 / points as f32;
+                let radius = if i % 2 == 0 { outer } else { inner };
+                Vector::<2>::new(50.0 + radius * angle.cos(), 50.0 + radius * angle.sin())
+            })
+            .collect()
+    }
+
+    #[test]
+    fn convex_in_either_winding() {
+        let outline = square(0.0, 0.0, 10.0);
+        assert_eq!(assert_covers(&outline, &[]).triangle_count(), 2);
+        let reversed: Vec<_> = outline.iter().rev().copied().collect();
+        assert_eq!(assert_covers(&reversed, &[]).triangle_count(), 2);
+    }
+
+    #[test]
+    fn concave() {
+        let l_shape = ring(&[
+            [0.0, 0.0],
+            [30.0, 0.0],
+            [30.0, 10.0],
+            [10.0, 10.0],
+            [10.0, 30.0],
+            [0.0, 30.0],
+        ]);
+        assert_eq!(assert_covers(&l_shape, &[]).triangle_count(), 4);
+        let comb = ring(&[
+            [0.0, 0.0],
+            [50.0, 0.0],
+            [50.0, 30.0],
+            [40.0, 30.0],
+            [40.0, 10.0],
+            [30.0, 10.0],
+            [30.0, 30.0],
+            [20.0, 30.0],
+            [20.0, 10.0],
+            [10.0, 10.0],
+            [10.0, 30.0],
+            [0.0, 30.0],
+        ]);
+        // clipping can leave the tooth bottoms collinear, those need no triangle
+        assert!(assert_covers(&comb, &[]).triangle_count() <= 10);
+        for points in [3, 5, 12] {
+            let star = star(points, 10.0, 40.0);
+            assert_eq!(assert_covers(&star, &[]).triangle_count(), 2 * points - 2);
+        }
+    }
+
+    #[test]
+    fn skips_duplicate_and_collinear_points() {
+        let outline = ring(&[
+            [0.0, 0.0],
+            [0.0, 0.0],
+            [5.0, 0.0],
+            [10.0, 0.0],
+            [10.0, 10.0],
+            [0.0, 10.0],
+            [0.0, 0.0],
+        ]);
+        assert_covers(&outline, &[]);
+    }
+
+    #[test]
+    fn holes() {
+        let outline = square(0.0, 0.0, 30.0);
+        // holes come out the same in either winding
+        let hole = square(10.0, 10.0, 10.0);
+        let reversed: Vec<_> = hole.iter().rev().copied().collect();
+        for hole in [hole, reversed] {
+            assert_eq!(assert_covers(&outline, &[hole]).triangle_count(), 8);
+        }
+
+        let wide = ring(&[[0.0, 0.0], [60.0, 0.0], [60.0, 30.0], [0.0, 30.0]]);
+        let side_by_side = [square(5.0, 5.0, 10.0), square(40.0, 12.0, 8.0)];
+        assert_covers(&wide, &side_by_side);
+        let stacked = [square(20.0, 2.0, 6.0), square(20.0, 20.0, 6.0)];
+        assert_covers(&wide, &stacked);
+        let star_hole = star(5, 5.0, 15.0);
+        assert_covers(&square(0.0, 0.0, 100.0), &[star_hole]);
+    }
+
+    #[test]
+    fn hole_behind_a_notch() {
+        // a spike from the right edge points at the hole, the bridge has to pass below it
+        let outline = ring(&[
+            [0.0, 0.0],
+            [20.0, 0.0],
+            [20.0, 4.0],
+            [8.0, 7.0],
+            [20.0, 6.0],
+            [20.0, 10.0],
+            [0.0, 10.0],
+        ]);
+        assert_covers(&outline, &[square(2.0, 4.0, 2.0)]);
+    }
+
+    #[test]
+    fn hole_outside_is_ignored() {
+        let outline = square(0.0, 0.0, 10.0);
+        let shape = assert_covers(&outline, &[]);
+        let with_hole = fill(&outline, &[square(20.0, 0.0, 5.0)]);
+        assert_eq!(with_hole.vertices.len(), 8);
+        assert_eq!(with_hole.indices, shape.indices);
+    }
+
+    #[test]
+    fn degenerate_input() {
+        assert!(triangulate(&[], &[]).is_empty());
+        assert!(triangulate(&ring(&[[0.0, 0.0], [1.0, 1.0]]), &[]).is_empty());
+        let collinear = ring(&[[0.0, 0.0], [1.0, 1.0], [2.0, 2.0], [3.0, 3.0]]);
+        assert!(triangulate(&collinear, &[]).is_empty());
+        let nan = ring(&[[0.0, 0.0], [f32::NAN, 1.0], [10.0, 0.0], [10.0, 10.0]]);
+        assert_eq!(check(&fill(&nan, &[])), 50.0);
+    }
+
+    #[test]
+    fn self_intersecting_input_terminates() {
+        let bowtie = ring(&[[0.0, 0.0], [20.0, 10.0], [20.0, 0.0], [0.0, 4.0]]);
+        let pentagram: Vec<_> = (0..5)
+            .map(|i| {
+                let angle = std::f32::consts::TAU * (2 * i) as f32 / 5.0;
+                Vector::<2>::new(50.0 + 40.0 * angle.cos(), 50.0 + 40.0 * angle.sin())
+            })
+            .collect();
+        let figure_eight = ring(&[
+            [0.0, 0.0],
+            [10.0, 0.0],
+            [10.0, 10.0],
+            [20.0, 10.0],
+            [20.0, 30.0],
+            [5.0, 30.0],
+            [5.0, 5.0],
+            [0.0, 5.0],
+        ]);
+        // no ear at all, these go through the fallback of `clip_ears`
+        let crossed = ring(&[[0.0, 0.0], [1.0, 2.0], [3.0, 9.0], [6.0, 6.0], [0.0, 3.0]]);
+        let tangled = ring(&[[0.0, 0.0], [1.0, 10.0], [8.0, 2.0], [10.0, 3.0], [9.0, 5.0]]);
+        for outline in [bowtie, pentagram, figure_eight, crossed, tangled] {
+            let shape = fill(&outline, &[]);
+            assert!(shape.triangle_count() <= outline.len() - 2);
+            check(&shape);
+        }
+    }
+}
@@ -0,0 +1,5 @@
+// Turns outlines into triangle lists for the `Float32x2` pixel space pipeline, with the same
+// counter-clockwise on screen winding as the shapes of `util`.
+
+pub mod fill;
+pub mod stroke;
@@ -0,0 +1,584 @@
+use std::f32::consts::PI;
+
+use crate::{
+    math::{common::Dimension2, curve::Curve, vector::Vector},
+    util::Shape,
+};
+
+/// shape of the outer corner where two segments meet
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LineJoin {
+    /// sharp corner, bevelled once it would reach past `miter_limit`
+    #[default]
+    Miter,
+    Round,
+    Bevel,
+}
+
+/// shape of the ends of an open path and of every dash
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LineCap {
+    /// ends flush with the end point
+    #[default]
+    Butt,
+    /// extends half the width past the end point
+    Square,
+    Round,
+}
+
+/// SVG stroke properties, lengths in pixels
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq)]
+pub struct StrokeStyle {
+    pub width: f32,
+    pub join: LineJoin,
+    pub cap: LineCap,
+    /// longest miter as a multiple of the width
+    pub miter_limit: f32,
+    /// alternating dash and gap lengths, an odd list is repeated, empty draws a solid line
+    pub dashes: Vec<f32>,
+    /// distance into the dash pattern at the start of the path
+    pub dash_offset: f32,
+    /// largest distance of round joins, round caps and flattened curves from the exact shape
+    pub tolerance: f32,
+}
+
+impl Default for StrokeStyle {
+    fn default() -> Self {
+        Self {
+            width: 1.0,
+            join: LineJoin::Miter,
+            cap: LineCap::Butt,
+            miter_limit: 4.0,
+            dashes: Vec::new(),
+            dash_offset: 0.0,
+            tolerance: 0.25,
+        }
+    }
+}
+
+impl StrokeStyle {
+    pub fn new(width: f32) -> Self {
+        Self {
+            width,
+            ..Self::default()
+        }
+    }
+
+    pub fn with_join(self, join: LineJoin) -> Self {
+        Self { join, ..self }
+    }
+
+    pub fn with_cap(self, cap: LineCap) -> Self {
+        Self { cap, ..self }
+    }
+
+    pub fn with_miter_limit(self, miter_limit: f32) -> Self {
+        Self {
+            miter_limit,
+            ..self
+        }
+    }
+
+    pub fn with_dashes(self, dashes: Vec<f32>, dash_offset: f32) -> Self {
+        Self {
+            dashes,
+            dash_offset,
+            ..self
+        }
+    }
+
+    pub fn with_tolerance(self, tolerance: f32) -> Self {
+        Self { tolerance, ..self }
+    }
+}
+
+/// triangles covering the stroke of a polyline, `closed` connects the last point to the first
+///
+/// Triangles overlap at joins, which is invisible with a single color and opaque blending.
+/// Repeated points are skipped, a path that collapses to one point becomes a dot for round
+/// and square caps, like in SVG.
+pub fn stroke(points: &[Vector<2>], closed: bool, style: &StrokeStyle) -> Shape {
+    let mut builder = Builder::default();
+    if !(style.width > 0.0 && style.width.is_finite()) {
+        return builder.shape;
+    }
+    let mut points = clean(points);
+    // a closing point repeating the first one is implied by `closed`
+    if closed && points.len() > 1 && points.first() == points.last() {
+        points.pop();
+    }
+    if closed && points.len() > 2 {
+        points.push(points[0]);
+    }
+
+    match dash_pattern(style) {
+        Some(pattern) => {
+            for dash in dash(&points, &pattern, style.dash_offset) {
+                stroke_open(&mut builder, &clean(&dash), style);
+            }
+        }
+        None if closed && points.len() > 3 => {
+            points.pop();
+            stroke_closed(&mut builder, &points, style);
+        }
+        None => stroke_open(&mut builder, &points, style),
+    }
+    builder.shape
+}
+
+/// `stroke` of the curve flattened to the style tolerance
+pub fn stroke_curve(curve: &impl Curve<2>, closed: bool, style: &StrokeStyle) -> Shape {
+    stroke(&curve.flatten(style.tolerance), closed, style)
+}
+
+/// indexed triangles, each one turned counter-clockwise on screen
+#[derive(Default)]
+struct Builder {
+    shape: Shape,
+}
+
+impl Builder {
+    fn vertex(&mut self, point: Vector<2>) -> u32 {
+        self.shape.vertices.push(point.to_array());
+        self.shape.vertices.len() as u32 - 1
+    }
+
+    fn triangle(&mut self, a: u32, b: u32, c: u32) {
+        let [pa, pb, pc] = [a, b, c].map(|i| self.shape.vertices[i as usize]);
+        let turn = (pb[0] - pa[0]) * (pc[1] - pa[1]) - (pb[1] - pa[1]) * (pc[0] - pa[0]);
+        // y points down, a negative turn is counter-clockwise on screen
+        if turn < 0.0 {
+            self.shape.indices.extend([a, b, c]);
+        } else if turn > 0.0 {
+            self.shape.indices.extend([a, c, b]);
+        }
+    }
+
+    fn quad(&mut self, a: Vector<2>, b: Vector<2>, c: Vector<2>, d: Vector<2>) {
+        let [a, b, c, d] = [a, b, c, d].map(|point| self.vertex(point));
+        self.triangle(a, b, c);
+        self.triangle(a, c, d);
+    }
+
+    /// fan around center from `from` turning by `angle`, positive turns towards the normal
+    fn arc(&mut self, center: Vector<2>, from: Vector<2>, angle: f32, tolerance: f32) {
+        let offset = from - center;
+        let radius = offset.length();
+        let steps = arc_steps(radius, angle.abs(), tolerance);
+        let step = angle / steps as f32;
+        let pivot = self.vertex(center);
+        let mut previous = self.vertex(from);
+        for i in 1..=steps {
+            let (sin, cos) = (step * i as f32).sin_cos();
+            let rotated = Vector::<2>::new(
+                offset[0] * cos - offset[1] * sin,
+                offset[0] * sin + offset[1] * cos,
+            );
+            let next = self.vertex(center + rotated);
+            self.triangle(pivot, previous, next);
+            previous = next;
+        }
+    }
+}
+
+/// segments of an arc so the chords stay within tolerance of it
+fn arc_steps(radius: f32, angle: f32, tolerance: f32) -> u32 {
+    let tolerance = tolerance.clamp(1e-3, radius.max(1e-3));
+    let step = 2.0 * (1.0 - tolerance / radius.max(tolerance)).acos();
+    if step > 0.0 {
+        (angle / step).ceil().clamp(1.0, 1024.0) as u32
+    } else {
+        1
+    }
+}
+
+/// left of the direction with y up, so the stroke spans point ± normal
+fn normal(direction: Vector<2>) -> Vector<2> {
+    Vector::<2>::new(-direction[1], direction[0])
+}
+
+fn cross(a: Vector<2>, b: Vector<2>) -> f32 {
+    a[0] * b[1] - a[1] * b[0]
+}
+
+/// finite points without consecutive repeats
+fn clean(points: &[Vector<2>]) -> Vec<Vector<2>> {
+    let mut cleaned: Vec<Vector<2>> = Vec::with_capacity(points.len());
+    for &point in points {
+        let finite = point[0].is_finite() && point[1].is_finite();
+        if finite && cleaned.last() != Some(&point) {
+            cleaned.push(point);
+        }
+    }
+    cleaned
+}
+
+/// the pattern of the style as SVG reads it, `None` for a solid line
+fn dash_pattern(style: &StrokeStyle) -> Option<Vec<f32>> {
+    let dashes = &style.dashes;
+    let valid = dashes
+        .iter()
+        .all(|&length| length >= 0.0 && length.is_finite());
+    if dashes.is_empty() || !valid || dashes.iter().sum::<f32>() <= 0.0 {
+        return None;
+    }
+    let mut pattern = dashes.clone();
+    if pattern.len() % 2 == 1 {
+        pattern.extend_from_slice(dashes);
+    }
+    Some(pattern)
+}
+
+/// the "on" pieces of the polyline, zero length dashes are kept as two equal points
+fn dash(points: &[Vector<2>], pattern: &[f32], offset: f32) -> Vec<Vec<Vector<2>>> {
+    let Some(&first) = points.first() else {
+        return Vec::new();
+    };
+    let total: f32 = pattern.iter().sum();
+    let mut index = 0;
+    let mut remaining = offset.rem_euclid(total);
+    while remaining > 0.0 && remaining >= pattern[index] {
+        remaining -= pattern[index];
+        index = (index + 1) % pattern.len();
+    }
+    remaining = pattern[index] - remaining;
+
+    let mut dashes = Vec::new();
+    let mut on = index % 2 == 0;
+    let mut current = if on { vec![first] } else { Vec::new() };
+    for segment in points.windows(2) {
+        let (from, to) = (segment[0], segment[1]);
+        let length = from.distance(&to);
+        let mut travelled = 0.0;
+        while length - travelled > remaining {
+            travelled += remaining;
+            let point = from.lerp(&to, travelled / length);
+            if on {
+                current.push(point);
+                dashes.push(std::mem::take(&mut current));
+            } else {
+                current = vec![point];
+            }
+            on = !on;
+            index = (index + 1) % pattern.len();
+            remaining = pattern[index];
+        }
+        remaining -= length - travelled;
+        if on {
+            current.push(to);
+        }
+    }
+    if on && current.len() > 1 {
+        dashes.push(current);
+    }
+    dashes
+}
+
+fn stroke_open(builder: &mut Builder, points: &[Vector<2>], style: &StrokeStyle) {
+    let half = style.width / 2.0;
+    match points {
+        [] => {}
+        [point] => dot(builder, *point, style),
+        _ => {
+            for segment in points.windows(2) {
+                segment_quad(builder, segment[0], segment[1], half);
+            }
+            for corner in points.windows(3) {
+                join(builder, corner[0], corner[1], corner[2], style);
+            }
+            let start_direction = (points[1] - points[0]).normalize();
+            let end_direction = (points[points.len() - 1] - points[points.len() - 2]).normalize();
+            cap(builder, points[0], -start_direction, style);
+            cap(builder, points[points.len() - 1], end_direction, style);
+        }
+    }
+}
+
+fn stroke_closed(builder: &mut Builder, points: &[Vector<2>], style: &StrokeStyle) {
+    let count = points.len();
+    for i in 0..count {
+        let (previous, current, next) = (
+            points[(i + count - 1) % count],
+            points[i],
+            points[(i + 1) % count],
+        );
+        segment_quad(builder, current, next, style.width / 2.0);
+        join(builder, previous, current, next, style);
+    }
+}
+
+fn segment_quad(builder: &mut Builder, from: Vector<2>, to: Vector<2>, half: f32) {
+    let offset = normal((to - from).normalize()) * half;
+    builder.quad(from + offset, from - offset, to - offset, to + offset);
+}
+
+/// fills the gap on the outside of the corner at `current`
+fn join(
+    builder: &mut Builder,
+    previous: Vector<2>,
+    current: Vector<2>,
+    next: Vector<2>,
+    style: &StrokeStyle,
+) {
+    let half = style.width / 2.0;
+    let incoming = (current - previous).normalize();
+    let outgoing = (next - current).normalize();
+    let turn = cross(incoming, outgoing);
+    let alignment = incoming.dot(&outgoing);
+    if turn.abs() < 1e-6 && alignment > 0.0 {
+        return;
+    }
+
+    // the gap opens on the side the path turns away from
+    let side = if turn > 0.0 { -1.0 } else { 1.0 };
+    let from = current + normal(incoming) * (half * side);
+    let to = current + normal(outgoing) * (half * side);
+
+    match style.join {
+        LineJoin::Round => {
+            // away from the gap, also when the path turns straight back and turn is 0
+            let angle = -side * turn.abs().atan2(alignment);
+            builder.arc(current, from, angle, style.tolerance);
+        }
+        LineJoin::Miter | LineJoin::Bevel => {
+            // the miter reaches 1 / cos(half the turn) half widths out
+            let cos_half = ((1.0 + alignment) / 2.0).max(0.0).sqrt();
+            let [center, from, to] = [current, from, to].map(|point| builder.vertex(point));
+            builder.triangle(center, from, to);
+            if style.join == LineJoin::Miter
+                && cos_half > 1e-6
+                && 1.0 / cos_half <= style.miter_limit
+            {
+                let bisector = (normal(incoming) + normal(outgoing)).normalize();
+                let tip = builder.vertex(current + bisector * (half / cos_half * side));
+                builder.triangle(from, tip, to);
+            }
+        }
+    }
+}
+
+/// end piece at `point` for a path leaving it along `direction`
+fn cap(builder: &mut Builder, point: Vector<2>, direction: Vector<2>, style: &StrokeStyle) {
+    let half = style.width / 2.0;
+    let offset = normal(direction) * half;
+    match style.cap {
+        LineCap::Butt => {}
+        LineCap::Square => {
+            let extension = direction * half;
+            builder.quad(
+                point + offset,
+                point - offset,
+                point - offset + extension,
+                point + offset + extension,
+            );
+        }
+        LineCap::Round => builder.arc(point, point + offset, -PI, style.tolerance),
+    }
+}
+
+/// a zero length path, SVG draws it for round and square caps
+fn dot(builder: &mut Builder, point: Vector<2>, style: &StrokeStyle) {
+    let half = style.width / 2.0;
+    match style.cap {
+        LineCap::Butt => {}
+        LineCap::Square => {
+            let (x, y) = (Vector::<2>::new(half, 0.0), Vector::<2>::new(0.0, half));
+            builder.quad(point - x - y, point - x + y, point + x + y, point + x - y);
+        }
+        LineCap::Round => builder.arc(
+            point,
+            point + Vector::<2>::new(half, 0.0),
+            2.0 * PI,
+            style.tolerance,
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// checks that every triangle is counter-clockwise on screen (negative pixel space cross),
+    /// returns their summed area, which counts the overlaps at joins twice
+    fn area(shape: &Shape) -> f32 {
+        assert_eq!(shape.indices.len() % 3, 0);
+        let crosses: Vec<f32> = shape
+            .indices
+            .chunks(3)
+            .map(|triangle| {
+                let [a, b, c] = [0, 1, 2].map(|i| shape.vertices[triangle[i] as usize]);
+                (b[0] - a[0]) * (c[1] - a[1]) - (b[1] - a[1]) * (c[0] - a[0])
+            })
+            .collect();
+        assert!(
+            crosses.iter().all(|&cross| cross < 0.0),
+            "not counter-clockwise on screen"
+        );
+        -crosses.iter().sum::<f32>() / 2.0
+    }
+
+    fn close(a: f32, b: f32) -> bool {
+        (a - b).abs() <= 1e-4 * b.abs().max(1.0)
+    }
+
+    /// a polygon inscribed in a circle of `radius` with chords within `tolerance` of it
+    fn round(area: f32, radius: f32, angle: f32, tolerance: f32) -> bool {
+        let exact = radius * radius * angle / 2.0;
+        area <= exact * (1.0 + 1e-5) && area >= exact - radius * angle * tolerance
+    }
+
+    const LINE: [[f32; 2]; 2] = [[10.0, 20.0], [40.0, 60.0]];
+    const CORNER: [[f32; 2]; 3] = [[0.0, 0.0], [30.0, 0.0], [30.0, 20.0]];
+
+    fn path(points: &[[f32; 2]]) -> Vec<Vector<2>> {
+        points.iter().map(|&point| point.into()).collect()
+    }
+
+    #[test]
+    fn caps() {
+        // length 50 in either direction
+        for points in [path(&LINE), path(&LINE).into_iter().rev().collect()] {
+            let style = StrokeStyle::new(4.0);
+            let butt = stroke(&points, false, &style);
+            assert_eq!(butt.triangle_count(), 2);
+            assert!(close(area(&butt), 50.0 * 4.0));
+            let square = stroke(&points, false, &style.clone().with_cap(LineCap::Square));
+            assert!(close(area(&square), 50.0 * 4.0 + 4.0 * 4.0));
+            let style = style.with_cap(LineCap::Round).with_tolerance(0.01);
+            let caps = area(&stroke(&points, false, &style)) - 50.0 * 4.0;
+            assert!(round(caps, 2.0, 2.0 * PI, 0.01), "{caps}");
+        }
+    }
+
+    #[test]
+    fn joins() {
+        // two segments of 30 and 20 with a right angle, half width 2 outside the corner
+        let points = path(&CORNER);
+        let segments = (30.0 + 20.0) * 4.0;
+        let style = StrokeStyle::new(4.0);
+        let miter = area(&stroke(&points, false, &style));
+        assert!(close(miter - segments, 4.0));
+        let bevel = area(&stroke(
+            &points,
+            false,
+            &style.clone().with_join(LineJoin::Bevel),
+        ));
+        assert!(close(bevel - segments, 2.0));
+        let style = style.with_join(LineJoin::Round).with_tolerance(0.01);
+        let join = area(&stroke(&points, false, &style)) - segments;
+        assert!(round(join, 2.0, PI / 2.0, 0.01), "{join}");
+    }
+
+    #[test]
+    fn miter_limit_falls_back_to_bevel() {
+        // a right angle miter reaches sqrt(2) half widths out
+        let points = path(&CORNER);
+        let bevel = StrokeStyle::new(4.0).with_join(LineJoin::Bevel);
+        let bevelled = stroke(&points, false, &bevel);
+        let limited = StrokeStyle::new(4.0).with_miter_limit(1.4);
+        assert_eq!(stroke(&points, false, &limited), bevelled);
+        let allowed = StrokeStyle::new(4.0).with_miter_limit(1.5);
+        assert_ne!(stroke(&points, false, &allowed), bevelled);
+
+        // a sharp turn with the default limit of 4
+        let sharp = path(&[[0.0, 0.0], [100.0, 0.0], [0.0, 20.0]]);
+        assert_eq!(
+            stroke(&sharp, false, &StrokeStyle::new(4.0)),
+            stroke(&sharp, false, &bevel)
+        );
+        // turning straight back has no miter at all
+        let back = path(&[[0.0, 0.0], [10.0, 0.0], [5.0, 0.0]]);
+        assert!(close(
+            area(&stroke(&back, false, &StrokeStyle::new(2.0))),
+            30.0
+        ));
+    }
+
+    #[test]
+    fn closed_paths() {
+        // a 10 by 10 square, mitered corners fill the outside of all four corners
+        let square = path(&[[0.0, 0.0], [10.0, 0.0], [10.0, 10.0], [0.0, 10.0]]);
+        let style = StrokeStyle::new(2.0);
+        let closed = stroke(&square, true, &style);
+        assert!(close(area(&closed), 4.0 * 10.0 * 2.0 + 4.0));
+        // the closing point may be repeated, caps never apply
+        let mut repeated = square.clone();
+        repeated.push(square[0]);
+        let style = style.with_cap(LineCap::Square);
+        assert!(close(area(&stroke(&repeated, true, &style)), 84.0));
+        // counter-clockwise input gives the same triangles reversed
+        let reversed: Vec<_> = square.iter().rev().copied().collect();
+        assert!(close(area(&stroke(&reversed, true, &style)), 84.0));
+        // open, the last side is missing and the ends get caps
+        let open = stroke(&square, false, &StrokeStyle::new(2.0));
+        assert!(close(area(&open), 3.0 * 10.0 * 2.0 + 2.0));
+    }
+
+    #[test]
+    fn dashes() {
+        let line = path(&[[0.0, 0.0], [10.0, 0.0]]);
+        let dashed = |dashes: Vec<f32>, offset: f32| {
+            let style = StrokeStyle::new(2.0).with_dashes(dashes, offset);
+            let shape = stroke(&line, false, &style);
+            (shape.triangle_count() / 2, area(&shape) / 2.0)
+        };
+        // on 0..2, 3..5, 6..8 and 9..10
+        assert_eq!(dashed(vec![2.0, 1.0], 0.0), (4, 7.0));
+        // the offset shifts the pattern back along the path
+        assert_eq!(dashed(vec![2.0, 1.0], 1.0), (4, 7.0));
+        assert_eq!(dashed(vec![2.0, 1.0], 2.5), (4, 6.5));
+        assert_eq!(dashed(vec![2.0, 1.0], -1.0), (3, 6.0));
+        assert_eq!(dashed(vec![2.0, 1.0], 3.0), dashed(vec![2.0, 1.0], 0.0));
+        // an odd list repeats, on 0..3, 4..5 and 8..9
+        assert_eq!(dashed(vec![3.0, 1.0, 1.0], 0.0), (3, 5.0));
+        // a dash across a corner keeps its join
+        let corner = path(&CORNER);
+        let style = StrokeStyle::new(4.0).with_dashes(vec![40.0, 5.0], 0.0);
+        let segments = (30.0 + 10.0) * 4.0 + 5.0 * 4.0;
+        assert!(close(area(&stroke(&corner, false, &style)) - segments, 4.0));
+        // invalid or all zero patterns draw a solid line
+        for dashes in [vec![], vec![0.0, 0.0], vec![2.0, -1.0], vec![f32::NAN]] {
+            assert_eq!(dashed(dashes, 0.0), (1, 10.0));
+        }
+    }
+
+    #[test]
+    fn zero_length_dashes() {
+        // dots every 2 pixels from 0 to 8, a dash starting right at the end is not drawn
+        let line = path(&[[0.0, 0.0], [10.0, 0.0]]);
+        let dots = StrokeStyle::new(2.0).with_dashes(vec![0.0, 2.0], 0.0);
+        assert!(stroke(&line, false, &dots).indices.is_empty());
+        let square = stroke(&line, false, &dots.clone().with_cap(LineCap::Square));
+        assert_eq!(square.triangle_count(), 5 * 2);
+        assert!(close(area(&square), 5.0 * 4.0));
+        let style = dots.with_cap(LineCap::Round).with_tolerance(0.01);
+        let round_dots = area(&stroke(&line, false, &style)) / 5.0;
+        assert!(round(round_dots, 1.0, 2.0 * PI, 0.01), "{round_dots}");
+    }
+
+    #[test]
+    fn degenerate_paths() {
+        let point = path(&[[5.0, 5.0], [5.0, 5.0]]);
+        assert!(
+            stroke(&point, false, &StrokeStyle::new(2.0))
+                .indices
+                .is_empty()
+        );
+        let square = StrokeStyle::new(2.0).with_cap(LineCap::Square);
+        assert!(close(area(&stroke(&point, false, &square)), 4.0));
+        assert!(stroke(&[], false, &square).indices.is_empty());
+        for width in [0.0, -1.0, f32::NAN, f32::INFINITY] {
+            assert!(
+                stroke(&path(&LINE), false, &StrokeStyle::new(width))
+                    .indices
+                    .is_empty()
+            );
+        }
+        // a closed path of two points is an open one drawn back and forth
+        let closed = stroke(&path(&LINE), true, &StrokeStyle::new(4.0));
+        assert!(area(&closed) > 0.0);
+    }
+}
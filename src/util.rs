@@ -1,44 +1,463 @@
-// pub fn create_annulus_vertices<const D: usize>(
-//     radius: f32,
-//     inner_radius: f32,
-//     start_angle: f32,
-//     end_angle: f32,
-// ) -> [[f32; 12]; D] {
-//     let mut vertices = [[0.0; 12]; D];
-//     let angle_gap = (end_angle - start_angle) / (D as f32);
-//     for i in 0..D {
-//         let angle1 = start_angle + (i as f32) * angle_gap;
-//         let angle2 = start_angle + ((i + 1) as f32) * angle_gap;
-//         let (c1, s1, c2, s2) = (
-//             f32::cos(angle1),
-//             f32::sin(angle1),
-//             f32::cos(angle2),
-//             f32::sin(angle2),
-//         );
-
-//         // 2 triangles per subdivision
-//         //
-//         // 0--1 4
-//         // | / /|
-//         // |/ / |
-//         // 2 3--5
-//         vertices[i] = [
-//             // first triangle
-//             c1 * radius,
-//             s1 * radius,
-//             c2 * radius,
-//             s2 * radius,
-//             c1 * inner_radius,
-//             s1 * inner_radius,
-//             // second triangle
-//             c1 * inner_radius,
-//             s1 * inner_radius,
-//             c2 * radius,
-//             s2 * radius,
-//             c2 * inner_radius,
-//             s2 * inner_radius,
-//         ]
-//     }
-
-//     vertices
-// }
+// 2D shape generators in pixel space, the `Float32x2` vertex layout of `State`.
+//
+// Pixel space has y pointing down and the shader flips it, so every triangle is emitted
+// counter-clockwise as seen on screen, which is what `FrontFace::Ccw` keeps when back faces
+// are culled. Angles follow the same screen convention: 0 points right, 90 degrees points up.
+//
+// Every shape comes as an indexed `Shape` sized at runtime, and as a fixed size array of
+// triangles per subdivision whose count is a const generic, ready for `cast_slice`.
+
+use std::{array, f32::consts::TAU};
+
+use crate::math::{angle::Rad, geometry::Rect, vector::Vector};
+
+/// indexed triangle list
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Shape {
+    pub vertices: Vec<[f32; 2]>,
+    pub indices: Vec<u32>,
+}
+
+impl Shape {
+    pub fn triangle_count(&self) -> usize {
+        self.indices.len() / 3
+    }
+
+    /// every triangle spelled out, for a draw without index buffer
+    pub fn triangles(&self) -> Vec<[f32; 2]> {
+        self.indices
+            .iter()
+            .map(|&index| self.vertices[index as usize])
+            .collect()
+    }
+
+    /// triangles from `first` to every edge of a closed outline, the outline must be star
+    /// shaped around `first` and counter-clockwise on screen
+    fn fan(first: [f32; 2], outline: Vec<[f32; 2]>) -> Self {
+        let count = outline.len() as u32;
+        let mut vertices = vec![first];
+        vertices.extend(outline);
+        let indices = (0..count)
+            .flat_map(|i| [0, 1 + i, 1 + (i + 1) % count])
+            .collect();
+        Self { vertices, indices }
+    }
+
+    /// `N` floats per chunk, `D` chunks, which is exactly the triangles of the shape
+    fn to_array<const D: usize, const N: usize>(&self) -> [[f32; N]; D] {
+        let floats: Vec<f32> = self.triangles().into_iter().flatten().collect();
+        assert_eq!(floats.len(), D * N, "shape does not fill the array");
+        array::from_fn(|chunk| array::from_fn(|i| floats[chunk * N + i]))
+    }
+}
+
+/// point at an angle counter-clockwise on screen
+fn on_circle(center: Vector<2>, radius: f32, angle: f32) -> [f32; 2] {
+    let (sin, cos) = angle.sin_cos();
+    [center[0] + radius * cos, center[1] - radius * sin]
+}
+
+/// regular polygon approximation, one triangle per segment
+pub fn circle(center: Vector<2>, radius: f32, segments: u32) -> Shape {
+    regular_polygon(center, radius, segments, Rad(0.0))
+}
+
+/// full ring between two radii, two triangles per segment, the larger radius is the outer one
+pub fn annulus(center: Vector<2>, radius: f32, inner_radius: f32, segments: u32) -> Shape {
+    ring(center, radius, inner_radius, 0.0, TAU, segments, true)
+}
+
+/// ring sector from `start` to `end`, a reversed range is swept the other way round, and an
+/// inner radius of 0 gives a pie slice, swapped radii are put back in order
+pub fn arc(
+    center: Vector<2>,
+    radius: f32,
+    inner_radius: f32,
+    start: impl Into<Rad>,
+    end: impl Into<Rad>,
+    segments: u32,
+) -> Shape {
+    let (start, end) = (start.into().0, end.into().0);
+    let (start, end) = if end < start {
+        (end, start)
+    } else {
+        (start, end)
+    };
+    ring(center, radius, inner_radius, start, end, segments, false)
+}
+
+/// outer and inner vertex per step, the quad between two steps is split from its inner start
+fn ring(
+    center: Vector<2>,
+    radius: f32,
+    inner_radius: f32,
+    start: f32,
+    end: f32,
+    segments: u32,
+    closed: bool,
+) -> Shape {
+    if segments == 0 {
+        return Shape::default();
+    }
+    // an inner radius beyond the outer one would flip the winding and get culled
+    let (radius, inner_radius) = (radius.max(inner_radius), radius.min(inner_radius));
+    let steps = if closed { segments } else { segments + 1 };
+    let step = (end - start) / segments as f32;
+    let vertices = (0..steps)
+        .flat_map(|i| {
+            let angle = start + i as f32 * step;
+            [
+                on_circle(center, radius, angle),
+                on_circle(center, inner_radius, angle),
+            ]
+        })
+        .collect();
+    let indices = (0..segments)
+        .flat_map(|i| {
+            let (outer, inner) = (2 * i, 2 * i + 1);
+            let next = 2 * ((i + 1) % steps);
+            let (next_outer, next_inner) = (next, next + 1);
+            [inner, outer, next_outer, inner, next_outer, next_inner]
+        })
+        .collect();
+    Shape { vertices, indices }
+}
+
+pub fn rect(rect: &Rect) -> Shape {
+    let Rect {
+        x,
+        y,
+        width,
+        height,
+    } = *rect;
+    Shape {
+        vertices: vec![
+            [x, y],
+            [x, y + height],
+            [x + width, y + height],
+            [x + width, y],
+        ],
+        indices: vec![0, 1, 2, 0, 2, 3],
+    }
+}
+
+/// corners rounded with `segments` steps each, the radius is clamped to half the shorter side
+pub fn rounded_rect(rect: &Rect, radius: f32, segments: u32) -> Shape {
+    let radius = radius.clamp(0.0, rect.width.min(rect.height) / 2.0);
+    let Rect {
+        x,
+        y,
+        width,
+        height,
+    } = *rect;
+    // corner centers from top right on, counter-clockwise on screen
+    let corners = [
+        Vector::from([x + width - radius, y + radius]),
+        Vector::from([x + radius, y + radius]),
+        Vector::from([x + radius, y + height - radius]),
+        Vector::from([x + width - radius, y + height - radius]),
+    ];
+    let segments = segments.max(1);
+    let step = TAU / 4.0 / segments as f32;
+    let outline = corners
+        .iter()
+        .enumerate()
+        .flat_map(|(corner, &center)| {
+            let start = corner as f32 * TAU / 4.0;
+            (0..=segments).map(move |i| on_circle(center, radius, start + i as f32 * step))
+        })
+        .collect();
+    let center = [x + width / 2.0, y + height / 2.0];
+    Shape::fan(center, outline)
+}
+
+/// `sides` vertices on the circle, the first at `rotation`
+pub fn regular_polygon(
+    center: Vector<2>,
+    radius: f32,
+    sides: u32,
+    rotation: impl Into<Rad>,
+) -> Shape {
+    if sides < 3 {
+        return Shape::default();
+    }
+    let rotation = rotation.into().0;
+    let step = TAU / sides as f32;
+    let outline = (0..sides)
+        .map(|i| on_circle(center, radius, rotation + i as f32 * step))
+        .collect();
+    Shape::fan(center.to_array(), outline)
+}
+
+/// `points` tips on the outer radius, the first at `rotation`, with the notches between them
+/// on the inner radius
+pub fn star(
+    center: Vector<2>,
+    radius: f32,
+    inner_radius: f32,
+    points: u32,
+    rotation: impl Into<Rad>,
+) -> Shape {
+    if points < 2 {
+        return Shape::default();
+    }
+    let rotation = rotation.into().0;
+    let step = TAU / (2 * points) as f32;
+    let outline = (0..2 * points)
+        .map(|i| {
+            let radius = if i % 2 == 0 { radius } else { inner_radius };
+            on_circle(center, radius, rotation + i as f32 * step)
+        })
+        .collect();
+    Shape::fan(center.to_array(), outline)
+}
+
+/// one triangle of 3 vertices per segment
+pub fn circle_array<const D: usize>(center: Vector<2>, radius: f32) -> [[f32; 6]; D] {
+    const {
+        assert!(D >= 3, "a circle needs at least 3 segments");
+    }
+    circle(center, radius, D as u32).to_array()
+}
+
+/// two triangles per segment
+pub fn annulus_array<const D: usize>(
+    center: Vector<2>,
+    radius: f32,
+    inner_radius: f32,
+) -> [[f32; 12]; D] {
+    annulus(center, radius, inner_radius, D as u32).to_array()
+}
+
+/// two triangles per segment
+pub fn arc_array<const D: usize>(
+    center: Vector<2>,
+    radius: f32,
+    inner_radius: f32,
+    start: impl Into<Rad>,
+    end: impl Into<Rad>,
+) -> [[f32; 12]; D] {
+    arc(center, radius, inner_radius, start, end, D as u32).to_array()
+}
+
+pub fn rect_array(bounds: &Rect) -> [[f32; 6]; 2] {
+    rect(bounds).to_array()
+}
+
+/// one triangle per outline vertex, `D` is a multiple of 4 and the outline has D / 4 vertices
+/// per corner
+pub fn rounded_rect_array<const D: usize>(bounds: &Rect, radius: f32) -> [[f32; 6]; D] {
+    const {
+        assert!(
+            D >= 8 && D.is_multiple_of(4),
+            "D must be a multiple of 4, at least 8"
+        );
+    }
+    rounded_rect(bounds, radius, (D / 4 - 1) as u32).to_array()
+}
+
+/// one triangle per side
+pub fn regular_polygon_array<const D: usize>(
+    center: Vector<2>,
+    radius: f32,
+    rotation: impl Into<Rad>,
+) -> [[f32; 6]; D] {
+    const {
+        assert!(D >= 3, "a polygon needs at least 3 sides");
+    }
+    regular_polygon(center, radius, D as u32, rotation).to_array()
+}
+
+/// two triangles per tip
+pub fn star_array<const D: usize>(
+    center: Vector<2>,
+    radius: f32,
+    inner_radius: f32,
+    rotation: impl Into<Rad>,
+) -> [[f32; 12]; D] {
+    const {
+        assert!(D >= 2, "a star needs at least 2 tips");
+    }
+    star(center, radius, inner_radius, D as u32, rotation).to_array()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::{angle::Deg, common::Dimension2};
+
+    /// twice the signed area in pixel space, negative for counter-clockwise on screen
+    fn cross(a: [f32; 2], b: [f32; 2], c: [f32; 2]) -> f32 {
+        (b[0] - a[0]) * (c[1] - a[1]) - (b[1] - a[1]) * (c[0] - a[0])
+    }
+
+    /// `cross` of every triangle
+    fn crosses(shape: &Shape) -> Vec<f32> {
+        shape
+            .indices
+            .chunks(3)
+            .map(|triangle| {
+                let [a, b, c] = [0, 1, 2].map(|i| shape.vertices[triangle[i] as usize]);
+                cross(a, b, c)
+            })
+            .collect()
+    }
+
+    /// checks the counts and winding, returns the covered area
+    fn check(shape: &Shape, vertices: usize, triangles: usize) -> f32 {
+        assert_eq!(shape.vertices.len(), vertices);
+        assert_eq!(shape.triangle_count(), triangles);
+        assert_eq!(shape.indices.len(), 3 * triangles);
+        let crosses = crosses(shape);
+        assert!(
+            crosses.iter().all(|&cross| cross < 0.0),
+            "not counter-clockwise on screen"
+        );
+        -crosses.iter().sum::<f32>() / 2.0
+    }
+
+    fn close(a: f32, b: f32) -> bool {
+        (a - b).abs() <= 1e-4 * b.abs()
+    }
+
+    fn center() -> Vector<2> {
+        Vector::<2>::new(100.0, 80.0)
+    }
+
+    #[test]
+    fn circles_and_polygons() {
+        for segments in [3, 4, 7, 64] {
+            let n = segments as f32;
+            let expected = n / 2.0 * 100.0 * (TAU / n).sin();
+            let area = check(
+                &circle(center(), 10.0, segments),
+                segments as usize + 1,
+                segments as usize,
+            );
+            assert!(close(area, expected));
+            let polygon = regular_polygon(center(), 10.0, segments, Deg(15.0));
+            assert!(close(
+                check(&polygon, segments as usize + 1, segments as usize),
+                expected
+            ));
+        }
+        assert_eq!(
+            regular_polygon(center(), 10.0, 2, Rad(0.0)),
+            Shape::default()
+        );
+    }
+
+    #[test]
+    fn rings() {
+        for segments in [1, 3, 16] {
+            let n = segments as f32;
+            if segments >= 3 {
+                let area = check(
+                    &annulus(center(), 10.0, 4.0, segments),
+                    2 * segments as usize,
+                    2 * segments as usize,
+                );
+                assert!(close(area, n / 2.0 * 84.0 * (TAU / n).sin()));
+            }
+            // a reversed range covers the same sector
+            for (start, end) in [(Deg(30.0), Deg(120.0)), (Deg(120.0), Deg(30.0))] {
+                let arc = arc(center(), 10.0, 4.0, start, end, segments);
+                let count = 2 * (segments as usize + 1);
+                let area = check(&arc, count, 2 * segments as usize);
+                let step = Rad::from(Deg(90.0)).0 / n;
+                assert!(close(area, n / 2.0 * 84.0 * step.sin()));
+            }
+        }
+        assert_eq!(annulus(center(), 10.0, 4.0, 0), Shape::default());
+    }
+
+    #[test]
+    fn swapped_radii() {
+        let swapped = annulus(center(), 4.0, 10.0, 16);
+        assert_eq!(swapped, annulus(center(), 10.0, 4.0, 16));
+        let area = check(&swapped, 32, 32);
+        assert!(close(area, 8.0 * 84.0 * (TAU / 16.0).sin()));
+
+        let swapped = arc(center(), 0.0, 10.0, Deg(0.0), Deg(90.0), 8);
+        assert_eq!(swapped, arc(center(), 10.0, 0.0, Deg(0.0), Deg(90.0), 8));
+        assert!(crosses(&swapped).iter().all(|&cross| cross <= 0.0));
+
+        let array = annulus_array::<8>(center(), 2.0, 6.0);
+        assert_eq!(array, annulus_array::<8>(center(), 6.0, 2.0));
+    }
+
+    #[test]
+    fn pie_slice() {
+        let slice = arc(center(), 10.0, 0.0, Deg(0.0), Deg(90.0), 8);
+        // the inner vertices collapse onto the center, so every other triangle is empty
+        let crosses = crosses(&slice);
+        assert!(crosses.iter().all(|&cross| cross <= 0.0));
+        let area = -crosses.iter().sum::<f32>() / 2.0;
+        assert!(close(area, 4.0 * 100.0 * (TAU / 32.0).sin()));
+    }
+
+    #[test]
+    fn rects() {
+        let bounds = Rect::new(10.0, 20.0, 30.0, 40.0);
+        assert_eq!(check(&rect(&bounds), 4, 2), 1200.0);
+        for segments in [1, 2, 8] {
+            let shape = rounded_rect(&bounds, 5.0, segments);
+            let outline = 4 * (segments as usize + 1);
+            let area = check(&shape, outline + 1, outline);
+            let s = segments as f32;
+            let corner = 25.0 - s / 2.0 * 25.0 * (TAU / 4.0 / s).sin();
+            assert!(close(area, 1200.0 - 4.0 * corner));
+        }
+        // clamped to half the shorter side, 15
+        let capsule = rounded_rect(&bounds, 100.0, 4);
+        assert!(
+            capsule
+                .vertices
+                .iter()
+                .all(|&[x, _]| (10.0..=40.0).contains(&x))
+        );
+    }
+
+    #[test]
+    fn stars() {
+        for points in [2, 5, 12] {
+            let shape = star(center(), 10.0, 4.0, points, Deg(90.0));
+            let count = 2 * points as usize;
+            let area = check(&shape, count + 1, count);
+            let n = points as f32;
+            assert!(close(area, n * 40.0 * (TAU / 2.0 / n).sin()));
+            // the first tip points up, y is down on screen
+            assert!(close(shape.vertices[1][1], 70.0));
+        }
+        assert_eq!(star(center(), 10.0, 4.0, 1, Rad(0.0)), Shape::default());
+    }
+
+    #[test]
+    fn arrays_hold_every_triangle() {
+        let flat = |shape: Shape| -> Vec<f32> { shape.triangles().into_iter().flatten().collect() };
+        let bounds = Rect::new(10.0, 20.0, 30.0, 40.0);
+
+        let array = circle_array::<5>(center(), 10.0);
+        assert_eq!(array.as_flattened(), flat(circle(center(), 10.0, 5)));
+        let array = annulus_array::<6>(center(), 10.0, 4.0);
+        assert_eq!(array.as_flattened(), flat(annulus(center(), 10.0, 4.0, 6)));
+        let array = arc_array::<3>(center(), 10.0, 4.0, Deg(0.0), Deg(45.0));
+        assert_eq!(
+            array.as_flattened(),
+            flat(arc(center(), 10.0, 4.0, Deg(0.0), Deg(45.0), 3))
+        );
+        assert_eq!(rect_array(&bounds).as_flattened(), flat(rect(&bounds)));
+        let array = rounded_rect_array::<12>(&bounds, 5.0);
+        assert_eq!(array.as_flattened(), flat(rounded_rect(&bounds, 5.0, 2)));
+        let array = regular_polygon_array::<6>(center(), 10.0, Deg(30.0));
+        assert_eq!(
+            array.as_flattened(),
+            flat(regular_polygon(center(), 10.0, 6, Deg(30.0)))
+        );
+        let array = star_array::<5>(center(), 10.0, 4.0, Deg(90.0));
+        assert_eq!(
+            array.as_flattened(),
+            flat(star(center(), 10.0, 4.0, 5, Deg(90.0)))
+        );
+    }
+}